@@ -0,0 +1,560 @@
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    creature::{MagicKind, MELEE_DISTANCE},
+    loading::BalanceAssets,
+};
+
+pub struct BalancePlugin;
+
+impl Plugin for BalancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Balance>()
+            .init_asset_loader::<BalanceLoader>()
+            .init_resource::<Balance>()
+            .add_systems(Update, update_balance);
+    }
+}
+
+/// Tuning of creature generation, mutation, breeding and difficulty, loaded from
+/// `assets/game.balance.ron`. The same file is compiled in as the default, which is used instead of
+/// a malformed or invalid file and by the headless simulation.
+#[derive(Asset, TypePath, Resource, Debug, Clone, Deserialize)]
+pub struct Balance {
+    /// Number of subranges every stat range is split into.
+    pub tiers: u8,
+    pub stats: StatRanges,
+    pub physical: PhysicalRanges,
+    pub magic: MagicRanges,
+    pub population: PopulationRange,
+    pub ranged_chance: f64,
+    pub ability_templates: AbilityTemplates,
+    pub size: SizeRules,
+    pub mutation: MutationRules,
+    pub inheritance: InheritanceRules,
+    /// Population multiplier of the player species after every won round.
+    pub breeding_multiplier: f32,
+    pub deployment: DeploymentRules,
+    pub difficulty: DifficultyCurve,
+}
+
+impl Default for Balance {
+    fn default() -> Self {
+        ron::from_str(include_str!("../assets/game.balance.ron"))
+            .expect("built-in balance is valid")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StatRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatRanges {
+    pub movement_speed: StatRange,
+    pub hp: StatRange,
+    pub stamina: StatRange,
+    pub stamina_regen: StatRange,
+    pub mana: StatRange,
+    pub mana_regen: StatRange,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PhysicalRanges {
+    pub damage: StatRange,
+    pub stamina_cost: StatRange,
+    pub cooldown: StatRange,
+    pub range: StatRange,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MagicRanges {
+    pub ability_count: usize,
+    pub power: StatRange,
+    pub buff_power: StatRange,
+    pub mana_cost: StatRange,
+    pub cooldown: StatRange,
+    pub range: StatRange,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PopulationRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Names of the physical abilities generated for each archetype.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AbilityTemplates {
+    pub melee: Vec<String>,
+    pub ranged: Vec<String>,
+}
+
+/// Size of a species relative to the normal size.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SizeRules {
+    pub range: StatRange,
+    /// HP bonus per size above the normal size.
+    pub hp_effect: f32,
+    /// Movement speed malus per size above the normal size.
+    pub speed_effect: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MutationRules {
+    pub chance: f64,
+    pub spread: f32,
+    /// Largest change of a mutated body part hue, in degrees.
+    pub hue_spread: f32,
+    pub archetype_chance: f64,
+}
+
+/// Rules shared by every inheritance model.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InheritanceRules {
+    /// Chance to pass down an ability only one parent has.
+    pub ability_gain_chance: f64,
+    /// Chance to lose an ability both parents have.
+    pub ability_loss_chance: f64,
+    /// Standard deviation of blended stats, in tier subranges.
+    pub blend_noise: f32,
+    /// Chance that the stronger trait is passed down by the biased flip.
+    pub stronger_chance: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeploymentRules {
+    /// Creatures which can be deployed in the first round.
+    pub start_cap: u32,
+    /// Added to the cap after every won round.
+    pub cap_increase: u32,
+    /// Population multiplier of the creatures kept in reserve, used instead of the breeding
+    /// multiplier.
+    pub reserve_breeding_multiplier: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DifficultyCurve {
+    pub start: DifficultyValues,
+    pub increase: DifficultyValues,
+    pub std_dev: DifficultyValues,
+    pub min: DifficultyValues,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DifficultyValues {
+    pub enemy_tier: f32,
+    pub enemy_count: f32,
+    pub enemy_pop_mult: f32,
+}
+
+impl Balance {
+    /// Checks the values which would break the game or make it panic, all problems are reported at
+    /// once.
+    pub fn validate(&self) -> Result<(), BalanceError> {
+        let mut errors = Vec::new();
+
+        let ranges = [
+            ("stats.movement_speed", self.stats.movement_speed),
+            ("stats.hp", self.stats.hp),
+            ("stats.stamina", self.stats.stamina),
+            ("stats.stamina_regen", self.stats.stamina_regen),
+            ("stats.mana", self.stats.mana),
+            ("stats.mana_regen", self.stats.mana_regen),
+            ("physical.damage", self.physical.damage),
+            ("physical.stamina_cost", self.physical.stamina_cost),
+            ("physical.cooldown", self.physical.cooldown),
+            ("physical.range", self.physical.range),
+            ("magic.power", self.magic.power),
+            ("magic.buff_power", self.magic.buff_power),
+            ("magic.mana_cost", self.magic.mana_cost),
+            ("magic.cooldown", self.magic.cooldown),
+            ("magic.range", self.magic.range),
+            ("size.range", self.size.range),
+        ];
+        for (name, range) in ranges {
+            if !(range.min.is_finite() && range.max.is_finite() && range.min < range.max) {
+                errors.push(format!(
+                    "{name}: min {} has to be less than max {}",
+                    range.min, range.max
+                ));
+            }
+            if range.min < 0.0 {
+                errors.push(format!("{name}: min {} can not be negative", range.min));
+            }
+        }
+
+        if self.physical.range.min <= MELEE_DISTANCE {
+            errors.push(format!(
+                "physical.range: min {} has to be greater than the melee distance {MELEE_DISTANCE}",
+                self.physical.range.min
+            ));
+        }
+        if self.size.range.min <= 0.0 {
+            errors.push(format!(
+                "size.range: min {} has to be greater than 0",
+                self.size.range.min
+            ));
+        }
+        if self.size.speed_effect * (self.size.range.max - 1.0) >= 1.0 {
+            errors.push(format!(
+                "size.speed_effect: {} stops the biggest species from moving",
+                self.size.speed_effect
+            ));
+        }
+        if self.tiers == 0 {
+            errors.push("tiers: there has to be at least one tier".to_string());
+        }
+        if self.magic.ability_count > MagicKind::ALL.len() {
+            errors.push(format!(
+                "magic.ability_count: {} is more than the {} kinds of magic",
+                self.magic.ability_count,
+                MagicKind::ALL.len()
+            ));
+        }
+        if self.population.min > self.population.max {
+            errors.push(format!(
+                "population: min {} is greater than max {}",
+                self.population.min, self.population.max
+            ));
+        }
+        if self.deployment.start_cap == 0 {
+            errors.push("deployment.start_cap: at least one creature has to fight".to_string());
+        }
+        if self.ability_templates.melee.is_empty() || self.ability_templates.ranged.is_empty() {
+            errors
+                .push("ability_templates: every archetype needs at least one ability".to_string());
+        }
+
+        let chances = [
+            ("ranged_chance", self.ranged_chance),
+            ("mutation.chance", self.mutation.chance),
+            ("mutation.archetype_chance", self.mutation.archetype_chance),
+            (
+                "inheritance.ability_gain_chance",
+                self.inheritance.ability_gain_chance,
+            ),
+            (
+                "inheritance.ability_loss_chance",
+                self.inheritance.ability_loss_chance,
+            ),
+            (
+                "inheritance.stronger_chance",
+                self.inheritance.stronger_chance,
+            ),
+        ];
+        for (name, chance) in chances {
+            if !(0.0..=1.0).contains(&chance) {
+                errors.push(format!("{name}: {chance} is not between 0 and 1"));
+            }
+        }
+
+        let non_negative = [
+            ("mutation.spread", self.mutation.spread),
+            ("mutation.hue_spread", self.mutation.hue_spread),
+            ("size.hp_effect", self.size.hp_effect),
+            ("size.speed_effect", self.size.speed_effect),
+            ("inheritance.blend_noise", self.inheritance.blend_noise),
+            ("breeding_multiplier", self.breeding_multiplier),
+            (
+                "deployment.reserve_breeding_multiplier",
+                self.deployment.reserve_breeding_multiplier,
+            ),
+            (
+                "difficulty.std_dev.enemy_tier",
+                self.difficulty.std_dev.enemy_tier,
+            ),
+            (
+                "difficulty.std_dev.enemy_count",
+                self.difficulty.std_dev.enemy_count,
+            ),
+            (
+                "difficulty.std_dev.enemy_pop_mult",
+                self.difficulty.std_dev.enemy_pop_mult,
+            ),
+            (
+                "difficulty.min.enemy_pop_mult",
+                self.difficulty.min.enemy_pop_mult,
+            ),
+        ];
+        for (name, value) in non_negative {
+            if !(value.is_finite() && value >= 0.0) {
+                errors.push(format!("{name}: {value} can not be negative"));
+            }
+        }
+
+        let finite = [
+            (
+                "difficulty.start.enemy_tier",
+                self.difficulty.start.enemy_tier,
+            ),
+            (
+                "difficulty.start.enemy_count",
+                self.difficulty.start.enemy_count,
+            ),
+            (
+                "difficulty.start.enemy_pop_mult",
+                self.difficulty.start.enemy_pop_mult,
+            ),
+            (
+                "difficulty.increase.enemy_tier",
+                self.difficulty.increase.enemy_tier,
+            ),
+            (
+                "difficulty.increase.enemy_count",
+                self.difficulty.increase.enemy_count,
+            ),
+            (
+                "difficulty.increase.enemy_pop_mult",
+                self.difficulty.increase.enemy_pop_mult,
+            ),
+        ];
+        for (name, value) in finite {
+            if !value.is_finite() {
+                errors.push(format!("{name}: {value} has to be a finite number"));
+            }
+        }
+
+        // enemies are generated with at least the minimum tier and count
+        let at_least_one = [
+            ("difficulty.min.enemy_tier", self.difficulty.min.enemy_tier),
+            (
+                "difficulty.min.enemy_count",
+                self.difficulty.min.enemy_count,
+            ),
+        ];
+        for (name, value) in at_least_one {
+            if !(value.is_finite() && value >= 1.0) {
+                errors.push(format!("{name}: {value} has to be at least 1"));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(BalanceError::Invalid(errors)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BalanceError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for BalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceError::Io(error) => write!(f, "failed to read balance: {error}"),
+            BalanceError::Parse(error) => write!(f, "malformed balance: {error}"),
+            BalanceError::Invalid(errors) => {
+                write!(f, "invalid balance:")?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BalanceError {}
+
+impl From<std::io::Error> for BalanceError {
+    fn from(error: std::io::Error) -> Self {
+        BalanceError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for BalanceError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        BalanceError::Parse(error)
+    }
+}
+
+/// Parses and validates a balance file, falling back to the built-in balance. The loader never
+/// fails on the content of the file, so a broken balance can't keep the loading state from
+/// finishing.
+fn parse_balance(bytes: &[u8]) -> Balance {
+    let parsed = ron::de::from_bytes::<Balance>(bytes)
+        .map_err(BalanceError::from)
+        .and_then(|balance| balance.validate().map(|_| balance));
+
+    match parsed {
+        Ok(balance) => balance,
+        Err(error) => {
+            error!("Using the built-in balance, {error}");
+            Balance::default()
+        }
+    }
+}
+
+#[derive(Default)]
+struct BalanceLoader;
+
+impl AssetLoader for BalanceLoader {
+    type Asset = Balance;
+    type Settings = ();
+    type Error = BalanceError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Balance, BalanceError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(parse_balance(&bytes))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        // other RON assets are left to their own loaders
+        &["balance.ron"]
+    }
+}
+
+/// Applies the balance once it is loaded and whenever it is hot reloaded.
+fn update_balance(
+    mut er_asset: EventReader<AssetEvent<Balance>>,
+    assets: Res<Assets<Balance>>,
+    balance_assets: Option<Res<BalanceAssets>>,
+    mut balance: ResMut<Balance>,
+) {
+    let Some(balance_assets) = balance_assets else {
+        return;
+    };
+    let id = balance_assets.balance.id();
+
+    // the load event may have been sent before the loading state finished
+    let mut changed = balance_assets.is_added();
+    for event in er_asset.read() {
+        if let AssetEvent::LoadedWithDependencies { id: loaded }
+        | AssetEvent::Modified { id: loaded } = event
+        {
+            changed |= *loaded == id;
+        }
+    }
+
+    if let (true, Some(loaded)) = (changed, assets.get(id)) {
+        info!("Applying balance {id:?}");
+        *balance = loaded.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names of the values which failed the validation.
+    fn invalid_values(balance: &Balance) -> Vec<String> {
+        match balance.validate() {
+            Err(BalanceError::Invalid(errors)) => errors
+                .iter()
+                .map(|error| error.split(':').next().unwrap().to_string())
+                .collect(),
+            result => panic!("expected an invalid balance, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn built_in_balance_is_valid() {
+        assert!(Balance::default().validate().is_ok());
+    }
+
+    #[test]
+    fn broken_file_falls_back_to_the_built_in_balance() {
+        let built_in = include_str!("../assets/game.balance.ron");
+        let modified = built_in.replace("ranged_chance: 0.3", "ranged_chance: 0.5");
+        assert_eq!(parse_balance(modified.as_bytes()).ranged_chance, 0.5);
+
+        let invalid = built_in.replace("ranged_chance: 0.3", "ranged_chance: 1.5");
+        assert_eq!(parse_balance(invalid.as_bytes()).ranged_chance, 0.3);
+        assert_eq!(parse_balance(b"(stats: oops)").ranged_chance, 0.3);
+    }
+
+    #[test]
+    fn reversed_range_is_invalid() {
+        let mut balance = Balance::default();
+        balance.stats.hp = StatRange {
+            min: 200.0,
+            max: 100.0,
+        };
+
+        assert_eq!(invalid_values(&balance), ["stats.hp"]);
+    }
+
+    #[test]
+    fn negative_values_are_invalid() {
+        let mut balance = Balance::default();
+        balance.physical.damage.min = -1.0;
+        balance.breeding_multiplier = -0.5;
+        balance.difficulty.std_dev.enemy_count = f32::NAN;
+
+        assert_eq!(
+            invalid_values(&balance),
+            [
+                "physical.damage",
+                "breeding_multiplier",
+                "difficulty.std_dev.enemy_count"
+            ]
+        );
+    }
+
+    #[test]
+    fn difficulty_without_enemies_is_invalid() {
+        let mut balance = Balance::default();
+        balance.difficulty.start.enemy_tier = f32::INFINITY;
+        balance.difficulty.min.enemy_tier = 0.0;
+        balance.difficulty.min.enemy_count = f32::NAN;
+
+        assert_eq!(
+            invalid_values(&balance),
+            [
+                "difficulty.start.enemy_tier",
+                "difficulty.min.enemy_tier",
+                "difficulty.min.enemy_count"
+            ]
+        );
+    }
+
+    #[test]
+    fn chances_outside_of_zero_to_one_are_invalid() {
+        let mut balance = Balance {
+            ranged_chance: -0.1,
+            ..default()
+        };
+        balance.mutation.chance = 1.5;
+
+        assert_eq!(
+            invalid_values(&balance),
+            ["ranged_chance", "mutation.chance"]
+        );
+    }
+
+    #[test]
+    fn all_problems_are_reported_at_once() {
+        let mut balance = Balance {
+            tiers: 0,
+            ..default()
+        };
+        balance.size.range.min = 0.0;
+        balance.inheritance.ability_loss_chance = 2.0;
+
+        assert_eq!(
+            invalid_values(&balance),
+            ["size.range", "tiers", "inheritance.ability_loss_chance"]
+        );
+    }
+}
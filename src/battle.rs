@@ -0,0 +1,1189 @@
+use core::f32;
+
+use bevior_tree::{
+    node::{Node, NodeResult},
+    prelude::{delegate_node, ConditionalLoop, Selector, Sequence},
+    task::{TaskBridge, TaskStatus},
+    BehaviorTreeBundle, BehaviorTreePlugin,
+};
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    balance::SizeRules,
+    creature::{
+        Archetype, CreatureStats, MagicAbility, MagicKind, PhysicalAbility, PopulationChangedEvent,
+        PopulationSize, MELEE_DISTANCE,
+    },
+    spatial::SpatialGrid,
+    WINDOW_SIZE,
+};
+
+const AREA_DAMAGE_RADIUS: f32 = 64.0;
+const BUFF_DURATION: f32 = 5.0;
+const PROJECTILE_SPEED: f32 = 400.0;
+const PROJECTILE_HIT_RADIUS: f32 = 16.0;
+/// Maximal deviation of a projectile from its aim in radians.
+const PROJECTILE_SPREAD: f32 = 0.1;
+/// Projectiles fly a bit further than the range of their ability before they are lost.
+const PROJECTILE_RANGE_MULTIPLIER: f32 = 1.2;
+/// Ranged creatures retreat when an enemy gets closer than this fraction of their attack range.
+const RETREAT_FRACTION: f32 = 0.5;
+/// Distance from the window border which creatures can not cross when retreating.
+pub const ARENA_MARGIN: f32 = 16.0;
+/// Creatures with the flee stance run away when their HP drops below this fraction.
+const FLEE_HP_FRACTION: f32 = 0.3;
+/// Creatures closer to each other than this push each other apart.
+const CREATURE_SPACING: f32 = 24.0;
+/// Preferred cell size of the spatial index, about the size of a creature.
+const SPATIAL_CELL_SIZE: f32 = 64.0;
+
+/// Battle logic shared by the battle screen and the headless simulation. It does not depend on
+/// any rendering or audio plugin, its users decide when [`BattleStep`] runs.
+pub struct BattlePlugin;
+
+impl Plugin for BattlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BehaviorTreePlugin::default())
+            .insert_resource(AttackRng(StdRng::from_entropy()))
+            .init_resource::<SpatialIndex>()
+            .init_resource::<BattleTime>()
+            .add_event::<DamageTakenEvent>()
+            .add_event::<CreatureDieEvent>()
+            .add_event::<SpellCastEvent>()
+            .add_event::<AbilityUsedEvent>()
+            .configure_sets(
+                BattleStep,
+                (BattleSet::Act, BattleSet::React, BattleSet::Death).chain(),
+            )
+            .add_systems(
+                BattleStep,
+                (
+                    update_spatial_index,
+                    find_targets,
+                    choose_spell,
+                    go_to_target,
+                    keep_distance,
+                    flee,
+                    update_spatial_index,
+                    separate_creatures,
+                    stats_recovery,
+                    attack_enemy,
+                    cast_spell,
+                    move_projectiles,
+                )
+                    .chain()
+                    .in_set(BattleSet::Act),
+            )
+            .add_systems(BattleStep, death_system.in_set(BattleSet::Death));
+    }
+}
+
+/// Battle logic and effects which consume [`BattleTime`], run once per step of the battle.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BattleStep;
+
+/// Parts of a [`BattleStep`] in the order they run.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BattleSet {
+    /// Creatures move, attack and cast spells.
+    Act,
+    /// Effects and records of what happened, dying creatures still exist.
+    React,
+    /// Dead creatures are removed.
+    Death,
+}
+
+#[derive(Event)]
+pub struct DamageTakenEvent {
+    pub target: Entity,
+    /// Template entity of the attacking creature.
+    pub source: Entity,
+    pub damage: f32,
+}
+
+#[derive(Event)]
+pub struct CreatureDieEvent {
+    pub entity: Entity,
+    /// Template entity of the creature.
+    pub template: Entity,
+    /// Template entity of the creature which dealt the last damage.
+    pub killer: Option<Entity>,
+    pub pos: Vec2,
+    pub is_enemy: bool,
+}
+
+#[derive(Event)]
+pub struct SpellCastEvent {
+    pub kind: MagicKind,
+    pub target_pos: Vec2,
+    /// Radius of the affected area around the target position, zero for single target spells.
+    pub radius: f32,
+}
+
+/// Sent whenever a creature uses one of its abilities.
+#[derive(Event)]
+pub struct AbilityUsedEvent {
+    pub creature: Entity,
+    /// Template entity of the creature.
+    pub template: Entity,
+    pub ability: UsedAbility,
+}
+
+/// Index of an ability in the stats of the creature.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum UsedAbility {
+    Physical(usize),
+    Magic(usize),
+}
+
+#[derive(Resource)]
+pub struct AttackRng(pub StdRng);
+
+/// Time consumed by one run of the battle systems. It is set by their users instead of using the
+/// global clock, so battles can be paused, sped up and simulated with a fixed timestep.
+#[derive(Resource, Default)]
+pub struct BattleTime {
+    delta_seconds: f32,
+}
+
+impl BattleTime {
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta_seconds
+    }
+
+    pub fn set_delta_seconds(&mut self, delta_seconds: f32) {
+        self.delta_seconds = delta_seconds;
+    }
+}
+
+/// Positions of the creatures of both sides, rebuilt by [`update_spatial_index`] so targeting,
+/// area and projectile queries do not have to check every creature.
+#[derive(Resource)]
+pub struct SpatialIndex {
+    allies: SpatialGrid,
+    enemies: SpatialGrid,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self {
+            allies: SpatialGrid::new(SPATIAL_CELL_SIZE),
+            enemies: SpatialGrid::new(SPATIAL_CELL_SIZE),
+        }
+    }
+}
+
+impl SpatialIndex {
+    /// Creatures on the given side.
+    fn side(&self, is_enemy: bool) -> &SpatialGrid {
+        match is_enemy {
+            true => &self.enemies,
+            false => &self.allies,
+        }
+    }
+
+    /// Creatures fighting against the given side.
+    fn opponents(&self, is_enemy: bool) -> &SpatialGrid {
+        self.side(!is_enemy)
+    }
+}
+
+/// Fighting style of a species, chosen by the player in the creature manager and stored on the
+/// template entity.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stance {
+    /// Goes after the nearest enemy.
+    #[default]
+    Aggressive,
+    /// Stays in place and only attacks enemies which come into range.
+    HoldLine,
+    /// Goes after the enemy with the least HP.
+    FocusWeakest,
+    /// Goes after the enemy closest to the own army.
+    ProtectAllies,
+    /// Fights like aggressive, but runs away when low on HP.
+    FleeAtLowHp,
+}
+
+impl Stance {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stance::Aggressive => "Aggressive",
+            Stance::HoldLine => "Hold Line",
+            Stance::FocusWeakest => "Focus Weakest",
+            Stance::ProtectAllies => "Protect Allies",
+            Stance::FleeAtLowHp => "Flee at Low HP",
+        }
+    }
+
+    /// Returns the stance which follows this one when cycling through them.
+    pub fn next(&self) -> Self {
+        match self {
+            Stance::Aggressive => Stance::HoldLine,
+            Stance::HoldLine => Stance::FocusWeakest,
+            Stance::FocusWeakest => Stance::ProtectAllies,
+            Stance::ProtectAllies => Stance::FleeAtLowHp,
+            Stance::FleeAtLowHp => Stance::Aggressive,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BattleCreature {
+    template: Entity,
+    stance: Stance,
+    movement_speed: f32,
+    stamina_regen: f32,
+    max_stamina: f32,
+    attack_range: f32,
+    retreat_distance: f32,
+    max_hp: f32,
+    mana_regen: f32,
+    max_mana: f32,
+    physical_abilities: Vec<PhysicalAbility>,
+    magic_abilities: Vec<MagicAbility>,
+}
+
+#[derive(Component, Clone)]
+pub struct BattleCreatureStats {
+    hp: f32,
+    stamina: f32,
+    cooldown: f32,
+    mana: f32,
+    spell_cooldown: f32,
+    /// Damage multiplier bonus granted by a buff spell.
+    damage_bonus: f32,
+    damage_bonus_duration: f32,
+    /// Template entity of the creature which damaged this one last.
+    last_attacker: Option<Entity>,
+}
+
+#[derive(Component)]
+pub struct Enemy;
+
+#[derive(Component)]
+pub struct BehaviorTreeContext {
+    target_pos: Vec2,
+    distance_squared_to_target: f32,
+    target: Option<Entity>,
+    /// Spell which can be cast right now, chosen by [`choose_spell`].
+    spell: Option<SpellTarget>,
+    /// Set by [`find_targets`] when a creature with the flee stance should run away.
+    fleeing: bool,
+}
+
+impl BehaviorTreeContext {
+    pub fn target(&self) -> Option<Entity> {
+        self.target
+    }
+
+    /// Whether moving and attacking should give way to casting a spell or to fleeing.
+    fn interrupted(&self) -> bool {
+        self.spell.is_some() || self.fleeing
+    }
+}
+
+impl Default for BehaviorTreeContext {
+    fn default() -> Self {
+        Self {
+            target_pos: Default::default(),
+            distance_squared_to_target: f32::INFINITY,
+            target: Default::default(),
+            spell: None,
+            fleeing: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct SpellTarget {
+    ability: usize,
+    target: Entity,
+    pos: Vec2,
+}
+
+/// Projectile flying in a straight line. It hits the first creature of the other side in its way,
+/// or misses once it flew further than the range of its ability.
+#[derive(Component)]
+pub struct Projectile {
+    /// Template entity of the creature which fired the projectile.
+    source: Entity,
+    from_enemy: bool,
+    magic: bool,
+    damage: f32,
+    velocity: Vec2,
+    remaining_distance: f32,
+}
+
+impl Projectile {
+    pub fn is_magic(&self) -> bool {
+        self.magic
+    }
+}
+
+impl BattleCreature {
+    /// Template entity of the species the creature belongs to.
+    pub fn template(&self) -> Entity {
+        self.template
+    }
+
+    pub fn max_hp(&self) -> f32 {
+        self.max_hp
+    }
+
+    pub fn max_stamina(&self) -> f32 {
+        self.max_stamina
+    }
+
+    /// Whether the creature is at a good distance to attack its target.
+    fn in_attack_range(&self, context: &BehaviorTreeContext, position: Vec2) -> bool {
+        let distance_squared = context.distance_squared_to_target;
+
+        distance_squared <= self.attack_range * self.attack_range
+            && (distance_squared >= self.retreat_distance * self.retreat_distance
+                || near_arena_edge(position))
+    }
+}
+
+impl BattleCreatureStats {
+    pub fn hp(&self) -> f32 {
+        self.hp
+    }
+
+    pub fn stamina(&self) -> f32 {
+        self.stamina
+    }
+}
+
+#[derive(Bundle)]
+pub struct BattleCreatureBundle {
+    creature: BattleCreature,
+    stats: BattleCreatureStats,
+    context: BehaviorTreeContext,
+    behavior_tree: BehaviorTreeBundle,
+}
+
+impl BattleCreatureBundle {
+    pub fn new(template: Entity, stats: &CreatureStats, stance: Stance, size: &SizeRules) -> Self {
+        let (attack_range, retreat_distance) = match stats.archetype {
+            Archetype::Melee => (MELEE_DISTANCE, 0.0),
+            Archetype::Ranged => {
+                let attack_range = stats
+                    .physical_abilities
+                    .iter()
+                    .map(|ability| ability.range)
+                    .fold(MELEE_DISTANCE, f32::max);
+                let retreat_distance = match stance {
+                    Stance::HoldLine => 0.0,
+                    _ => attack_range * RETREAT_FRACTION,
+                };
+
+                (attack_range, retreat_distance)
+            }
+        };
+
+        Self {
+            creature: BattleCreature {
+                template,
+                stance,
+                movement_speed: stats.sized_movement_speed(size),
+                physical_abilities: stats.physical_abilities.clone(),
+                max_stamina: stats.stamina,
+                stamina_regen: stats.stamina_regen,
+                attack_range,
+                retreat_distance,
+                max_hp: stats.sized_hp(size),
+                mana_regen: stats.mana_regen,
+                max_mana: stats.mana,
+                magic_abilities: stats.magic_abilities.clone(),
+            },
+            stats: BattleCreatureStats {
+                hp: stats.sized_hp(size),
+                stamina: stats.stamina,
+                cooldown: 0.0,
+                mana: stats.mana,
+                spell_cooldown: 0.0,
+                damage_bonus: 0.0,
+                damage_bonus_duration: 0.0,
+                last_attacker: None,
+            },
+            context: BehaviorTreeContext::default(),
+            behavior_tree: create_behavior_tree(stats.archetype, stance),
+        }
+    }
+}
+
+/// Returns a random position inside of the spawn area of the given side.
+pub fn random_spawn_position(rng: &mut StdRng, is_enemy: bool) -> Vec2 {
+    let mut position = Vec2::new(
+        rng.gen_range(-WINDOW_SIZE.x / 2.0..-WINDOW_SIZE.x / 6.0),
+        rng.gen_range(-WINDOW_SIZE.y / 2.0..WINDOW_SIZE.y / 2.0),
+    );
+
+    if is_enemy {
+        position.x *= -1.0;
+    }
+
+    position
+}
+
+/// Returns a random position inside of a region of the arena chosen by the player.
+pub fn random_position_in(rng: &mut StdRng, region: Rect) -> Vec2 {
+    Vec2::new(
+        rng.gen_range(region.min.x..region.max.x),
+        rng.gen_range(region.min.y..region.max.y),
+    )
+}
+
+fn near_arena_edge(position: Vec2) -> bool {
+    position
+        .abs()
+        .cmpge(WINDOW_SIZE / 2.0 - ARENA_MARGIN - 1.0)
+        .any()
+}
+
+fn move_within_arena(transform: &mut Transform, direction: Vec2, distance: f32) {
+    let bounds = WINDOW_SIZE / 2.0 - ARENA_MARGIN;
+    let pos = (transform.translation.xy() + direction * distance).clamp(-bounds, bounds);
+
+    transform.translation = pos.extend(transform.translation.z);
+}
+
+fn spawn_projectile(
+    commands: &mut Commands,
+    rng: &mut StdRng,
+    translation: Vec3,
+    target_pos: Vec2,
+    mut projectile: Projectile,
+) {
+    let aim = (target_pos - translation.xy()).normalize_or_zero();
+    let spread = rng.gen_range(-PROJECTILE_SPREAD..=PROJECTILE_SPREAD);
+    projectile.velocity = Vec2::from_angle(spread).rotate(aim) * PROJECTILE_SPEED;
+
+    commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(translation)),
+        projectile,
+    ));
+}
+
+/// Rebuilds the [`SpatialIndex`] from the current creature positions. It runs at the start of
+/// the battle systems and again after the creatures moved.
+fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    query: Query<(Entity, &Transform, Has<Enemy>), With<BattleCreature>>,
+) {
+    let index = &mut *index;
+    let points = |side: bool| {
+        query
+            .iter()
+            .filter(move |&(.., is_enemy)| is_enemy == side)
+            .map(|(entity, transform, _)| (entity, transform.translation.xy()))
+    };
+
+    index.allies.rebuild(points(false));
+    index.enemies.rebuild(points(true));
+}
+
+/// Chooses the target of every creature according to its stance and decides whether creatures
+/// with the flee stance should run away.
+fn find_targets(
+    mut query: Query<(
+        &BattleCreature,
+        &BattleCreatureStats,
+        &mut BehaviorTreeContext,
+        &Transform,
+        Has<Enemy>,
+    )>,
+    target_query: Query<(Entity, &BattleCreatureStats, &Transform, Has<Enemy>)>,
+    index: Res<SpatialIndex>,
+) {
+    // targets shared by all creatures of a side, indexed by whether the attacking side is the enemy
+    let mut weakest: [Option<(Entity, Vec2, f32)>; 2] = [None; 2];
+    let mut most_threatening: [Option<(Entity, Vec2, f32)>; 2] = [None; 2];
+
+    let protectors_exist = [false, true].map(|side| {
+        query.iter().any(|(creature, .., is_enemy)| {
+            is_enemy == side && creature.stance == Stance::ProtectAllies
+        })
+    });
+
+    for (entity, stats, transform, is_enemy) in target_query.iter() {
+        let position = transform.translation.xy();
+        let attacking_side = usize::from(!is_enemy);
+
+        if weakest[attacking_side].is_none_or(|(.., hp)| stats.hp < hp) {
+            weakest[attacking_side] = Some((entity, position, stats.hp));
+        }
+
+        // the threat of a creature is its squared distance to the closest creature it fights
+        if protectors_exist[attacking_side] {
+            if let Some((_, closest)) = index.opponents(is_enemy).nearest(position) {
+                let threat = position.distance_squared(closest);
+                if most_threatening[attacking_side].is_none_or(|(.., min)| threat < min) {
+                    most_threatening[attacking_side] = Some((entity, position, threat));
+                }
+            }
+        }
+    }
+
+    for (creature, stats, mut context, transform, is_enemy) in query.iter_mut() {
+        let position = transform.translation.xy();
+        let side = usize::from(is_enemy);
+
+        let target = match creature.stance {
+            Stance::FocusWeakest => weakest[side].map(|(entity, pos, _)| (entity, pos)),
+            Stance::ProtectAllies => most_threatening[side].map(|(entity, pos, _)| (entity, pos)),
+            _ => index.opponents(is_enemy).nearest(position),
+        };
+
+        context.distance_squared_to_target = f32::INFINITY;
+        if let Some((target, target_pos)) = target {
+            context.distance_squared_to_target = position.distance_squared(target_pos);
+            context.target_pos = target_pos;
+            context.target = Some(target);
+        }
+
+        context.fleeing = creature.stance == Stance::FleeAtLowHp
+            && stats.hp <= creature.max_hp * FLEE_HP_FRACTION
+            && !near_arena_edge(position);
+    }
+}
+
+/// Builds the behaviour tree of a creature. A spell is cast whenever one is available, otherwise
+/// the creature fights its target. Melee creatures walk up to the target, ranged creatures keep it
+/// at their attack range and creatures holding the line wait until the target comes into range.
+/// Creatures with the flee stance run away from their target when low on HP. Moving and attacking
+/// are interrupted as soon as a spell becomes available or the creature starts fleeing.
+fn create_behavior_tree(archetype: Archetype, stance: Stance) -> BehaviorTreeBundle {
+    let approach: Box<dyn Node> = match (stance, archetype) {
+        (Stance::HoldLine, _) => Box::new(HoldPositionTask::new()),
+        (_, Archetype::Melee) => Box::new(GoToTargetTask::new()),
+        (_, Archetype::Ranged) => Box::new(KeepDistanceTask::new()),
+    };
+
+    let mut nodes: Vec<Box<dyn Node>> = Vec::new();
+    if stance == Stance::FleeAtLowHp {
+        nodes.push(Box::new(FleeTask::new()));
+    }
+    nodes.push(Box::new(CastSpellTask::new()));
+    nodes.push(Box::new(Sequence::new(vec![
+        approach,
+        Box::new(AttackEnemyTask::new()),
+    ])));
+
+    BehaviorTreeBundle::from_root(ConditionalLoop::new(Selector::new(nodes), |In(_)| true))
+}
+
+#[delegate_node(delegate)]
+struct GoToTargetTask {
+    delegate: TaskBridge,
+}
+
+impl GoToTargetTask {
+    pub fn new() -> Self {
+        let checker =
+            move |In(entity): In<Entity>, param: Query<(&BehaviorTreeContext, &BattleCreature)>| {
+                let (context, creature) = param.get(entity).unwrap();
+                if context.interrupted() {
+                    return TaskStatus::Complete(NodeResult::Failure);
+                }
+                let distance_squared = context.distance_squared_to_target;
+
+                match distance_squared <= creature.attack_range * creature.attack_range
+                    && param.get(context.target.unwrap()).is_ok()
+                {
+                    true => TaskStatus::Complete(NodeResult::Success),
+                    false => TaskStatus::Running,
+                }
+            };
+        let task = TaskBridge::new(checker).insert_while_running(GoToTarget);
+
+        Self { delegate: task }
+    }
+}
+
+#[derive(Clone, Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct GoToTarget;
+
+fn go_to_target(
+    mut query: Query<(&mut Transform, &BehaviorTreeContext, &BattleCreature), With<GoToTarget>>,
+    entity_query: Query<Entity>,
+    time: Res<BattleTime>,
+) {
+    for (mut transform, context, creature) in query.iter_mut() {
+        if entity_query.get(context.target.unwrap()).is_err() {
+            continue;
+        }
+
+        let direction = (context.target_pos - transform.translation.xy()).normalize_or_zero();
+
+        move_within_arena(
+            &mut transform,
+            direction,
+            creature.movement_speed * time.delta_seconds(),
+        );
+    }
+}
+
+#[delegate_node(delegate)]
+struct KeepDistanceTask {
+    delegate: TaskBridge,
+}
+
+impl KeepDistanceTask {
+    pub fn new() -> Self {
+        let checker =
+            move |In(entity): In<Entity>,
+                  param: Query<(&BehaviorTreeContext, &BattleCreature, &Transform)>| {
+                let (context, creature, transform) = param.get(entity).unwrap();
+                if context.interrupted() {
+                    return TaskStatus::Complete(NodeResult::Failure);
+                }
+
+                match creature.in_attack_range(context, transform.translation.xy())
+                    && param.get(context.target.unwrap()).is_ok()
+                {
+                    true => TaskStatus::Complete(NodeResult::Success),
+                    false => TaskStatus::Running,
+                }
+            };
+        let task = TaskBridge::new(checker).insert_while_running(KeepDistance);
+
+        Self { delegate: task }
+    }
+}
+
+#[derive(Clone, Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct KeepDistance;
+
+fn keep_distance(
+    mut query: Query<(&mut Transform, &BehaviorTreeContext, &BattleCreature), With<KeepDistance>>,
+    entity_query: Query<Entity>,
+    time: Res<BattleTime>,
+) {
+    for (mut transform, context, creature) in query.iter_mut() {
+        if entity_query.get(context.target.unwrap()).is_err() {
+            continue;
+        }
+
+        let mut direction = (context.target_pos - transform.translation.xy()).normalize_or_zero();
+        if context.distance_squared_to_target
+            < creature.retreat_distance * creature.retreat_distance
+        {
+            direction = -direction;
+        }
+
+        move_within_arena(
+            &mut transform,
+            direction,
+            creature.movement_speed * time.delta_seconds(),
+        );
+    }
+}
+
+#[delegate_node(delegate)]
+struct HoldPositionTask {
+    delegate: TaskBridge,
+}
+
+impl HoldPositionTask {
+    pub fn new() -> Self {
+        let checker =
+            move |In(entity): In<Entity>,
+                  param: Query<(&BehaviorTreeContext, &BattleCreature, &Transform)>| {
+                let (context, creature, transform) = param.get(entity).unwrap();
+                if context.interrupted() {
+                    return TaskStatus::Complete(NodeResult::Failure);
+                }
+
+                match creature.in_attack_range(context, transform.translation.xy())
+                    && param.get(context.target.unwrap()).is_ok()
+                {
+                    true => TaskStatus::Complete(NodeResult::Success),
+                    false => TaskStatus::Running,
+                }
+            };
+
+        Self {
+            delegate: TaskBridge::new(checker),
+        }
+    }
+}
+
+#[delegate_node(delegate)]
+struct FleeTask {
+    delegate: TaskBridge,
+}
+
+impl FleeTask {
+    pub fn new() -> Self {
+        let checker = move |In(entity): In<Entity>, param: Query<&BehaviorTreeContext>| match param
+            .get(entity)
+            .map(|context| context.fleeing)
+        {
+            Ok(true) => TaskStatus::Running,
+            _ => TaskStatus::Complete(NodeResult::Failure),
+        };
+        let task = TaskBridge::new(checker).insert_while_running(Flee);
+
+        Self { delegate: task }
+    }
+}
+
+#[derive(Clone, Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct Flee;
+
+fn flee(
+    mut query: Query<(&mut Transform, &BehaviorTreeContext, &BattleCreature), With<Flee>>,
+    time: Res<BattleTime>,
+) {
+    for (mut transform, context, creature) in query.iter_mut() {
+        let direction = (transform.translation.xy() - context.target_pos).normalize_or_zero();
+
+        move_within_arena(
+            &mut transform,
+            direction,
+            creature.movement_speed * time.delta_seconds(),
+        );
+    }
+}
+
+/// Pushes overlapping creatures of both sides apart, so crowds spread out around their target
+/// instead of stacking on the same spot.
+fn separate_creatures(
+    mut query: Query<(Entity, &mut Transform), With<BattleCreature>>,
+    index: Res<SpatialIndex>,
+) {
+    for (entity, mut transform) in query.iter_mut() {
+        let position = transform.translation.xy();
+        let push = [false, true]
+            .into_iter()
+            .flat_map(|side| index.side(side).within_radius(position, CREATURE_SPACING))
+            .filter(|&(other, _)| other != entity)
+            .map(|(_, other_pos)| {
+                let offset = position - other_pos;
+                // creatures on the exact same spot still need to move apart somehow
+                let direction = offset
+                    .try_normalize()
+                    .unwrap_or_else(|| Vec2::from_angle(entity.index() as f32));
+
+                direction * (CREATURE_SPACING - offset.length()) / 2.0
+            })
+            .sum::<Vec2>()
+            .clamp_length_max(CREATURE_SPACING / 2.0);
+
+        move_within_arena(&mut transform, push, 1.0);
+    }
+}
+
+#[delegate_node(delegate)]
+struct AttackEnemyTask {
+    delegate: TaskBridge,
+}
+
+impl AttackEnemyTask {
+    pub fn new() -> Self {
+        let checker =
+            move |In(entity): In<Entity>,
+                  param: Query<(&BehaviorTreeContext, &BattleCreature, &Transform)>| {
+                let context = param.get(entity);
+
+                if context.is_err() {
+                    return TaskStatus::Complete(NodeResult::Success);
+                }
+                let (context, creature, transform) = context.unwrap();
+                if context.interrupted() {
+                    return TaskStatus::Complete(NodeResult::Failure);
+                }
+
+                match creature.in_attack_range(context, transform.translation.xy())
+                    && param.get(context.target.unwrap()).is_ok()
+                {
+                    true => TaskStatus::Running,
+                    false => TaskStatus::Complete(NodeResult::Success),
+                }
+            };
+        let task = TaskBridge::new(checker).insert_while_running(AttackEnemy);
+
+        Self { delegate: task }
+    }
+}
+
+#[derive(Clone, Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct AttackEnemy;
+
+fn attack_enemy(
+    mut commands: Commands,
+    attacker_query: Query<
+        (
+            Entity,
+            &BattleCreature,
+            &BehaviorTreeContext,
+            &Transform,
+            Has<Enemy>,
+        ),
+        With<AttackEnemy>,
+    >,
+    mut stats_query: Query<(Entity, &mut BattleCreatureStats)>,
+    mut attack_rng: ResMut<AttackRng>,
+    mut ew_damage_taken: EventWriter<DamageTakenEvent>,
+    mut ew_ability_used: EventWriter<AbilityUsedEvent>,
+) {
+    for (entity, creature, context, transform, is_enemy) in attacker_query.iter() {
+        let (_, stats) = stats_query.get_mut(entity).unwrap();
+        let mut stats = stats.clone();
+        if stats.cooldown > 0.0 {
+            continue;
+        }
+
+        let abilities = creature
+            .physical_abilities
+            .iter()
+            .enumerate()
+            .filter(|(_, ability)| {
+                ability.stamina_cost <= stats.stamina
+                    && ability.range * ability.range >= context.distance_squared_to_target
+            })
+            .collect::<Vec<_>>();
+
+        if abilities.is_empty() {
+            continue;
+        }
+
+        let (index, ability) = abilities[attack_rng.0.gen_range(0..abilities.len())];
+        stats.stamina -= ability.stamina_cost;
+        stats.cooldown = ability.global_cooldown;
+
+        let damage = ability.damage * (1.0 + stats.damage_bonus);
+        if ability.is_ranged() {
+            if stats_query.get(context.target.unwrap()).is_err() {
+                continue;
+            }
+
+            spawn_projectile(
+                &mut commands,
+                &mut attack_rng.0,
+                transform.translation,
+                context.target_pos,
+                Projectile {
+                    source: creature.template,
+                    from_enemy: is_enemy,
+                    magic: false,
+                    damage,
+                    velocity: Vec2::ZERO,
+                    remaining_distance: ability.range * PROJECTILE_RANGE_MULTIPLIER,
+                },
+            );
+        } else if let Ok((entity, mut target_stats)) = stats_query.get_mut(context.target.unwrap())
+        {
+            target_stats.hp -= damage;
+            target_stats.last_attacker = Some(creature.template);
+            ew_damage_taken.send(DamageTakenEvent {
+                target: entity,
+                source: creature.template,
+                damage,
+            });
+        } else {
+            continue;
+        }
+
+        *stats_query.get_mut(entity).unwrap().1 = stats;
+        ew_ability_used.send(AbilityUsedEvent {
+            creature: entity,
+            template: creature.template,
+            ability: UsedAbility::Physical(index),
+        });
+    }
+}
+
+fn death_system(
+    mut commands: Commands,
+    hp_query: Query<(Entity, &BattleCreature, &BattleCreatureStats, &Transform)>,
+    mut population_query: Query<&mut PopulationSize>,
+    enemy_query: Query<&Enemy>,
+    mut ew_creature_die: EventWriter<CreatureDieEvent>,
+    mut ew_population_changed: EventWriter<PopulationChangedEvent>,
+) {
+    let mut entities_to_die = Vec::new();
+
+    for (entity, creature, stats, transform) in hp_query.iter() {
+        if stats.hp <= 0.0 {
+            let mut population = population_query.get_mut(creature.template).unwrap();
+            population.0 -= 1;
+            ew_population_changed.send(PopulationChangedEvent);
+
+            entities_to_die.push(entity);
+            ew_creature_die.send(CreatureDieEvent {
+                entity,
+                template: creature.template,
+                killer: stats.last_attacker,
+                pos: transform.translation.xy(),
+                is_enemy: enemy_query.get(entity).is_ok(),
+            });
+        }
+    }
+
+    for entity in entities_to_die {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn stats_recovery(
+    mut query: Query<(&mut BattleCreatureStats, &BattleCreature)>,
+    time: Res<BattleTime>,
+) {
+    for (mut stats, creature) in query.iter_mut() {
+        stats.cooldown -= time.delta_seconds();
+        if stats.cooldown < 0.0 {
+            stats.cooldown = 0.0;
+        }
+
+        stats.stamina += creature.stamina_regen * time.delta_seconds();
+        if stats.stamina > creature.max_stamina {
+            stats.stamina = creature.max_stamina;
+        }
+
+        stats.spell_cooldown = (stats.spell_cooldown - time.delta_seconds()).max(0.0);
+        stats.mana =
+            (stats.mana + creature.mana_regen * time.delta_seconds()).min(creature.max_mana);
+
+        stats.damage_bonus_duration -= time.delta_seconds();
+        if stats.damage_bonus_duration <= 0.0 {
+            stats.damage_bonus_duration = 0.0;
+            stats.damage_bonus = 0.0;
+        }
+    }
+}
+
+/// Picks the first magic ability which the creature can afford and which has a valid target.
+fn choose_spell(
+    mut caster_query: Query<(
+        Entity,
+        &BattleCreature,
+        &BattleCreatureStats,
+        &mut BehaviorTreeContext,
+        &Transform,
+        Has<Enemy>,
+    )>,
+    target_query: Query<(&BattleCreature, &BattleCreatureStats)>,
+    index: Res<SpatialIndex>,
+) {
+    for (entity, creature, stats, mut context, transform, is_enemy) in caster_query.iter_mut() {
+        if stats.spell_cooldown > 0.0 {
+            context.spell = None;
+            continue;
+        }
+
+        let position = transform.translation.xy();
+        let spell = creature
+            .magic_abilities
+            .iter()
+            .enumerate()
+            .filter(|(_, ability)| ability.mana_cost <= stats.mana)
+            .find_map(|(i, ability)| {
+                let enemy_in_range =
+                    context.distance_squared_to_target <= ability.range * ability.range;
+
+                let (target, pos) = match ability.kind {
+                    MagicKind::RangedDamage | MagicKind::AreaDamage => (
+                        context.target.filter(|_| enemy_in_range)?,
+                        context.target_pos,
+                    ),
+                    MagicKind::Heal => index
+                        .side(is_enemy)
+                        .within_radius(position, ability.range)
+                        .filter_map(|(ally, ally_pos)| {
+                            let (ally_creature, ally_stats) = target_query.get(ally).ok()?;
+                            let missing_hp = ally_creature.max_hp - ally_stats.hp;
+
+                            (ally_stats.hp > 0.0 && missing_hp >= ability.power / 2.0)
+                                .then_some((ally, ally_pos, missing_hp))
+                        })
+                        .max_by(|(.., a), (.., b)| a.total_cmp(b))
+                        .map(|(ally, ally_pos, _)| (ally, ally_pos))?,
+                    MagicKind::Buff if enemy_in_range && stats.damage_bonus_duration <= 0.0 => {
+                        (entity, position)
+                    }
+                    MagicKind::Buff => return None,
+                };
+
+                Some(SpellTarget {
+                    ability: i,
+                    target,
+                    pos,
+                })
+            });
+
+        context.spell = spell;
+    }
+}
+
+#[delegate_node(delegate)]
+struct CastSpellTask {
+    delegate: TaskBridge,
+}
+
+impl CastSpellTask {
+    pub fn new() -> Self {
+        let checker = move |In(entity): In<Entity>, param: Query<&BehaviorTreeContext>| match param
+            .get(entity)
+            .map(|context| context.spell.is_some())
+        {
+            Ok(true) => TaskStatus::Running,
+            _ => TaskStatus::Complete(NodeResult::Failure),
+        };
+        let task = TaskBridge::new(checker).insert_while_running(CastSpell);
+
+        Self { delegate: task }
+    }
+}
+
+#[derive(Clone, Component, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct CastSpell;
+
+fn cast_spell(
+    mut commands: Commands,
+    mut caster_query: Query<
+        (
+            Entity,
+            &BattleCreature,
+            &mut BehaviorTreeContext,
+            &Transform,
+            Has<Enemy>,
+        ),
+        With<CastSpell>,
+    >,
+    mut target_query: Query<(&BattleCreature, &mut BattleCreatureStats)>,
+    index: Res<SpatialIndex>,
+    mut attack_rng: ResMut<AttackRng>,
+    (mut ew_damage_taken, mut ew_spell_cast, mut ew_ability_used): (
+        EventWriter<DamageTakenEvent>,
+        EventWriter<SpellCastEvent>,
+        EventWriter<AbilityUsedEvent>,
+    ),
+) {
+    for (entity, creature, mut context, transform, is_enemy) in caster_query.iter_mut() {
+        let Some(spell) = context.spell.take() else {
+            continue;
+        };
+        let ability = &creature.magic_abilities[spell.ability];
+        let position = transform.translation.xy();
+
+        let (_, mut stats) = target_query.get_mut(entity).unwrap();
+        stats.mana -= ability.mana_cost;
+        stats.spell_cooldown = ability.cooldown;
+
+        let radius = match ability.kind {
+            MagicKind::RangedDamage => {
+                spawn_projectile(
+                    &mut commands,
+                    &mut attack_rng.0,
+                    transform.translation,
+                    spell.pos,
+                    Projectile {
+                        source: creature.template,
+                        from_enemy: is_enemy,
+                        magic: true,
+                        damage: ability.power,
+                        velocity: Vec2::ZERO,
+                        remaining_distance: ability.range * PROJECTILE_RANGE_MULTIPLIER,
+                    },
+                );
+
+                0.0
+            }
+            MagicKind::AreaDamage => {
+                for (target, _) in index
+                    .opponents(is_enemy)
+                    .within_radius(spell.pos, AREA_DAMAGE_RADIUS)
+                {
+                    if let Ok((_, mut target_stats)) = target_query.get_mut(target) {
+                        target_stats.hp -= ability.power;
+                        target_stats.last_attacker = Some(creature.template);
+                        ew_damage_taken.send(DamageTakenEvent {
+                            target,
+                            source: creature.template,
+                            damage: ability.power,
+                        });
+                    }
+                }
+
+                AREA_DAMAGE_RADIUS
+            }
+            MagicKind::Heal => {
+                if let Ok((target, mut target_stats)) = target_query.get_mut(spell.target) {
+                    target_stats.hp = (target_stats.hp + ability.power).min(target.max_hp);
+                }
+
+                0.0
+            }
+            MagicKind::Buff => {
+                for (target, _) in index.side(is_enemy).within_radius(position, ability.range) {
+                    if let Ok((_, mut target_stats)) = target_query.get_mut(target) {
+                        target_stats.damage_bonus = target_stats.damage_bonus.max(ability.power);
+                        target_stats.damage_bonus_duration = BUFF_DURATION;
+                    }
+                }
+
+                ability.range
+            }
+        };
+
+        ew_spell_cast.send(SpellCastEvent {
+            kind: ability.kind,
+            target_pos: spell.pos,
+            radius,
+        });
+        ew_ability_used.send(AbilityUsedEvent {
+            creature: entity,
+            template: creature.template,
+            ability: UsedAbility::Magic(spell.ability),
+        });
+    }
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform), Without<BattleCreature>>,
+    mut stats_query: Query<&mut BattleCreatureStats>,
+    index: Res<SpatialIndex>,
+    mut ew_damage_taken: EventWriter<DamageTakenEvent>,
+    time: Res<BattleTime>,
+) {
+    for (entity, mut projectile, mut transform) in projectile_query.iter_mut() {
+        let step = projectile.velocity * time.delta_seconds();
+        transform.translation += step.extend(0.0);
+        projectile.remaining_distance -= step.length();
+
+        let position = transform.translation.xy();
+        let hit = index
+            .opponents(projectile.from_enemy)
+            .within_radius(position, PROJECTILE_HIT_RADIUS)
+            .find(|&(target, _)| {
+                stats_query
+                    .get(target)
+                    .is_ok_and(|target_stats| target_stats.hp > 0.0)
+            });
+
+        if let Some((target, _)) = hit {
+            let mut target_stats = stats_query.get_mut(target).unwrap();
+            target_stats.hp -= projectile.damage;
+            target_stats.last_attacker = Some(projectile.source);
+            ew_damage_taken.send(DamageTakenEvent {
+                target,
+                source: projectile.source,
+                damage: projectile.damage,
+            });
+            commands.entity(entity).despawn_recursive();
+        } else if projectile.remaining_distance <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
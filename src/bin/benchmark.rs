@@ -0,0 +1,124 @@
+//! Measures how the battle targeting queries scale with the number of creatures.
+//!
+//! Usage: `cargo run --release --bin benchmark`
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tiny_legion::{
+    generate_creature_stats,
+    simulation::{Army, BattleSimulation, BattleSimulationSettings},
+    spatial::SpatialGrid,
+    Balance, PopulationSize, Stance, WINDOW_SIZE,
+};
+
+const QUERY_COUNTS: [usize; 6] = [250, 500, 1000, 2000, 4000, 8000];
+const BATTLE_COUNTS: [u32; 4] = [250, 500, 1000, 2000];
+const BATTLE_SPECIES: u32 = 5;
+const BATTLE_STEPS: u32 = 60;
+const CELL_SIZE: f32 = 64.0;
+
+fn random_points(rng: &mut StdRng, count: usize) -> Vec<(Entity, Vec2)> {
+    (0..count)
+        .map(|i| {
+            let pos = Vec2::new(
+                rng.gen_range(-WINDOW_SIZE.x / 2.0..WINDOW_SIZE.x / 2.0),
+                rng.gen_range(-WINDOW_SIZE.y / 2.0..WINDOW_SIZE.y / 2.0),
+            );
+
+            (Entity::from_raw(i as u32), pos)
+        })
+        .collect()
+}
+
+fn brute_force_nearest(points: &[(Entity, Vec2)], pos: Vec2) -> Option<(Entity, Vec2)> {
+    points.iter().copied().min_by(|(_, a), (_, b)| {
+        pos.distance_squared(*a)
+            .total_cmp(&pos.distance_squared(*b))
+    })
+}
+
+/// Finds the nearest enemy of every ally, once by checking every enemy and once with a grid which
+/// is rebuilt for the query like it is in every battle frame.
+fn benchmark_nearest(rng: &mut StdRng, count: usize) -> (Duration, Duration) {
+    let allies = random_points(rng, count);
+    let enemies = random_points(rng, count);
+
+    let start = Instant::now();
+    let brute_force = allies
+        .iter()
+        .map(|&(_, pos)| brute_force_nearest(&enemies, pos))
+        .collect::<Vec<_>>();
+    let brute_force_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut grid = SpatialGrid::new(CELL_SIZE);
+    grid.rebuild(enemies.iter().copied());
+    let nearest = allies
+        .iter()
+        .map(|&(_, pos)| grid.nearest(pos))
+        .collect::<Vec<_>>();
+    let grid_time = start.elapsed();
+
+    for ((&(_, pos), a), b) in allies.iter().zip(&brute_force).zip(&nearest) {
+        let distance = |found: &Option<(Entity, Vec2)>| found.map(|(_, point)| pos.distance(point));
+        assert_eq!(
+            distance(a),
+            distance(b),
+            "grid found a different nearest point"
+        );
+    }
+
+    (brute_force_time, grid_time)
+}
+
+fn generate_army(rng: &mut StdRng, count: u32) -> Army {
+    (0..BATTLE_SPECIES)
+        .map(|generation| {
+            (
+                generate_creature_stats(&Balance::default(), rng, 1, generation as u64),
+                PopulationSize(count / BATTLE_SPECIES),
+                Stance::default(),
+            )
+        })
+        .collect()
+}
+
+/// Average duration of a battle step with `count` creatures on each side.
+fn benchmark_battle(rng: &mut StdRng, count: u32) -> Duration {
+    let allies = generate_army(rng, count);
+    let enemies = generate_army(rng, count);
+    let mut simulation = BattleSimulation::new(&allies, &enemies, Default::default());
+
+    // the first step spawns the behaviour trees
+    simulation.step();
+
+    let start = Instant::now();
+    for _ in 0..BATTLE_STEPS {
+        if simulation.step().is_some() {
+            break;
+        }
+    }
+
+    start.elapsed() / BATTLE_STEPS
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(BattleSimulationSettings::default().seed);
+
+    println!("nearest enemy of every ally");
+    println!("{:>8} {:>14} {:>14}", "per side", "brute force", "grid");
+    for count in QUERY_COUNTS {
+        let (brute_force, grid) = benchmark_nearest(&mut rng, count);
+        println!("{count:>8} {brute_force:>14.2?} {grid:>14.2?}");
+    }
+
+    println!();
+    println!("battle step ({BATTLE_STEPS} steps averaged)");
+    println!("{:>8} {:>14}", "per side", "step");
+    for count in BATTLE_COUNTS {
+        let step = benchmark_battle(&mut rng, count);
+        println!("{count:>8} {step:>14.2?}");
+    }
+}
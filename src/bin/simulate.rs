@@ -0,0 +1,135 @@
+//! Runs headless battles between randomly generated armies and prints the results.
+//!
+//! Usage: `cargo run --bin simulate -- [--seed N] [--runs N] [--ally-tier N] [--enemy-tier N]
+//! [--ally-species N] [--enemy-species N]`
+
+use std::{env, process};
+
+use rand::{rngs::StdRng, SeedableRng};
+use tiny_legion::{
+    generate_creature_stats, generate_population_size,
+    simulation::{Army, BattleSide, BattleSimulation, BattleSimulationSettings},
+    Balance, Stance,
+};
+
+struct Options {
+    seed: u64,
+    runs: u64,
+    ally_tier: u8,
+    enemy_tier: u8,
+    ally_species: usize,
+    enemy_species: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            runs: 1,
+            ally_tier: 1,
+            enemy_tier: 1,
+            ally_species: 2,
+            enemy_species: 2,
+        }
+    }
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {arg}"))?;
+        let invalid = |_| format!("invalid value for {arg}: {value}");
+
+        match arg.as_str() {
+            "--seed" => options.seed = value.parse().map_err(invalid)?,
+            "--runs" => options.runs = value.parse().map_err(invalid)?,
+            "--ally-tier" => options.ally_tier = value.parse().map_err(invalid)?,
+            "--enemy-tier" => options.enemy_tier = value.parse().map_err(invalid)?,
+            "--ally-species" => options.ally_species = value.parse().map_err(invalid)?,
+            "--enemy-species" => options.enemy_species = value.parse().map_err(invalid)?,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn generate_army(balance: &Balance, rng: &mut StdRng, tier: u8, species: usize) -> Army {
+    (0..species)
+        .map(|generation| {
+            (
+                generate_creature_stats(balance, rng, tier, generation as u64),
+                generate_population_size(balance, rng, 1.0),
+                Stance::default(),
+            )
+        })
+        .collect()
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(1);
+        }
+    };
+
+    let balance = Balance::default();
+    let mut ally_wins = 0;
+    let mut enemy_wins = 0;
+
+    for run in 0..options.runs {
+        let seed = options.seed.wrapping_add(run);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let allies = generate_army(&balance, &mut rng, options.ally_tier, options.ally_species);
+        let enemies = generate_army(
+            &balance,
+            &mut rng,
+            options.enemy_tier,
+            options.enemy_species,
+        );
+
+        let result = BattleSimulation::new(
+            &allies,
+            &enemies,
+            BattleSimulationSettings {
+                seed,
+                size: balance.size.clone(),
+                ..Default::default()
+            },
+        )
+        .run();
+
+        match result.winner {
+            Some(BattleSide::Allies) => ally_wins += 1,
+            Some(BattleSide::Enemies) => enemy_wins += 1,
+            None => {}
+        }
+
+        println!(
+            "seed {seed}: winner {:?} after {:.2}s",
+            result.winner, result.elapsed_seconds
+        );
+        for (side, army, species) in [
+            ("ally", &allies, &result.allies),
+            ("enemy", &enemies, &result.enemies),
+        ] {
+            for (i, ((_, population, _), species)) in army.iter().zip(species).enumerate() {
+                println!(
+                    "  {side} species {i}: {}/{} survived, {:.2} damage dealt",
+                    species.survivors, population.0, species.damage_dealt
+                );
+            }
+        }
+    }
+
+    println!(
+        "allies won {ally_wins}, enemies won {enemy_wins}, draws {}",
+        options.runs - ally_wins - enemy_wins
+    );
+}
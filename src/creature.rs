@@ -1,33 +1,23 @@
+use std::ops::Range;
+
 use bevy::prelude::*;
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    loading::TextureAssets, rounds::RoundOverEvent, screens::new_creature_screen::PlayerCreature,
+    balance::{Balance, MagicRanges, SizeRules, StatRange},
+    battle::Stance,
+    deployment::Reserve,
+    loading::TextureAssets,
+    naming::{generate_name, SpeciesName},
+    rounds::RoundOverEvent,
+    screens::new_creature_screen::PlayerCreature,
+    GameState,
 };
 
-const NUM_TIERS: u8 = 10;
-
-const MIN_MOVEMENT_SPEED: f32 = 100.0;
-const MAX_MOVEMENT_SPEED: f32 = 500.0;
-const MIN_HP: f32 = 50.0;
-const MAX_HP: f32 = 250.0;
-const MIN_STAMINA: f32 = 50.0;
-const MAX_STAMINA: f32 = 250.0;
-const MIN_STAMINA_REGEN: f32 = 1.0;
-const MAX_STAMINA_REGEN: f32 = 25.0;
-
-const MIN_PHYS_DMG: f32 = 5.0;
-const MAX_PHYS_DMG: f32 = 20.0;
-const MIN_PHYS_STAMINA_COST: f32 = 10.0;
-const MAX_PHYS_STAMINA_COST: f32 = 50.0;
-const MIN_PHYS_COOLDOWN: f32 = 0.5;
-const MAX_PHYS_COOLDOWN: f32 = 2.0;
-
-const MIN_POPULATION: u32 = 5;
-const MAX_POPULATION: u32 = 15;
-
-const MUTATION_CHANCE: f64 = 0.25;
+/// Range of melee abilities, ranged abilities reach further.
+pub const MELEE_DISTANCE: f32 = 32.0;
 
 pub const CREATURE_Z: f32 = 10.0;
 pub const CREATURE_SCALE: f32 = 2.5;
@@ -36,6 +26,16 @@ const BODY_PART_TYPES: usize = 6;
 const BODY_PARTS_CELL_SIZE: usize = 32;
 const BODY_PARTS_COUNT: usize = 4;
 const BODY_PARTS_Z: [f32; BODY_PARTS_COUNT] = [10.0, 11.0, 9.0, 8.0];
+/// Tints are pale, so the drawn colors of the body parts still show through.
+const TINT_SATURATION: f32 = 0.6;
+const TINT_LIGHTNESS: f32 = 0.8;
+/// Stat shown by each body part slot, in the order of the rows of `body_parts.png`.
+const BODY_PART_STATS: [BodyPartStat; BODY_PARTS_COUNT] = [
+    BodyPartStat::Hp,
+    BodyPartStat::Ability(0),
+    BodyPartStat::Ability(1),
+    BodyPartStat::MovementSpeed,
+];
 
 pub struct CreaturePlugin;
 
@@ -43,7 +43,9 @@ impl Plugin for CreaturePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GenerateCreatureRng(StdRng::from_entropy()))
             .init_resource::<CreatureGeneration>()
-            .add_systems(Update, (delete_empty_creatures, breed_creatures))
+            .init_resource::<BodyPartAtlas>()
+            .add_systems(OnEnter(GameState::BattleReport), breed_creatures)
+            .add_systems(Update, delete_empty_creatures)
             .add_event::<PopulationChangedEvent>();
     }
 }
@@ -54,57 +56,279 @@ pub struct PopulationChangedEvent;
 #[derive(Component)]
 pub struct BodyPart;
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Serialize, Deserialize)]
 pub struct CreatureGeneration(pub u64);
 
-#[derive(Component)]
+/// Layout of the body parts texture, used to show creatures in the UI.
+#[derive(Resource)]
+pub struct BodyPartAtlas(pub Handle<TextureAtlasLayout>);
+
+impl FromWorld for BodyPartAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::splat(BODY_PARTS_CELL_SIZE as u32),
+            BODY_PART_TYPES as u32,
+            BODY_PARTS_COUNT as u32,
+            None,
+            None,
+        );
+
+        Self(
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(layout),
+        )
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
 pub struct PopulationSize(pub u32);
 
-#[derive(Component, Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Archetype {
+    /// Walks up to its target and fights in melee.
+    Melee,
+    /// Keeps its distance and attacks from range, retreats when enemies come too close.
+    Ranged,
+}
+
+impl Archetype {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Archetype::Melee => "Melee",
+            Archetype::Ranged => "Ranged",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct CreatureStats {
+    pub archetype: Archetype,
     pub movement_speed: f32,
     pub hp: f32,
     pub stamina: f32,
     pub stamina_regen: f32,
+    pub mana: f32,
+    pub mana_regen: f32,
     pub generation: u64,
     pub physical_abilities: Vec<PhysicalAbility>,
+    pub magic_abilities: Vec<MagicAbility>,
+    /// Scale of the sprites relative to the normal size, also changes the HP and movement speed in
+    /// battle.
+    #[serde(default = "default_size")]
+    pub size: f32,
+    /// Hue of the tint of every body part slot, in degrees.
+    #[serde(default = "default_hues")]
+    pub hues: Vec<f32>,
+    /// Hidden second allele of the species, `None` when both alleles are the shown values, like
+    /// for species from the wild and species saved before alleles were carried.
+    #[serde(default)]
+    pub recessive: Option<RecessiveAlleles>,
+}
+
+/// Second allele of the stats a species passes on, it is carried without being shown. Only the
+/// dominant inheritance passes it down, so a weaker trait can skip a generation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecessiveAlleles {
+    pub archetype: Archetype,
+    pub movement_speed: f32,
+    pub hp: f32,
+    pub stamina: f32,
+    pub stamina_regen: f32,
+    pub mana: f32,
+    pub mana_regen: f32,
+    pub size: f32,
+}
+
+/// Size of species saved before sizes were inherited.
+fn default_size() -> f32 {
+    1.0
+}
+
+/// Hues of species saved before colors were inherited.
+pub fn default_hues() -> Vec<f32> {
+    vec![0.0; BODY_PARTS_COUNT]
 }
 
 impl CreatureStats {
-    pub fn mutate(&mut self, rng: &mut StdRng) {
-        self.movement_speed +=
-            Self::get_mutation_change(rng, MIN_MOVEMENT_SPEED, MAX_MOVEMENT_SPEED);
-        self.hp += Self::get_mutation_change(rng, MIN_HP, MAX_HP);
-        self.stamina += Self::get_mutation_change(rng, MIN_STAMINA, MAX_STAMINA);
-        self.stamina_regen += Self::get_mutation_change(rng, MIN_STAMINA_REGEN, MAX_STAMINA_REGEN);
+    /// Hidden alleles of the species, the shown values when it carries none.
+    pub fn recessive_alleles(&self) -> RecessiveAlleles {
+        self.recessive.clone().unwrap_or(RecessiveAlleles {
+            archetype: self.archetype,
+            movement_speed: self.movement_speed,
+            hp: self.hp,
+            stamina: self.stamina,
+            stamina_regen: self.stamina_regen,
+            mana: self.mana,
+            mana_regen: self.mana_regen,
+            size: self.size,
+        })
+    }
+
+    /// HP in battle, bigger species are tougher.
+    pub fn sized_hp(&self, size: &SizeRules) -> f32 {
+        self.hp * (1.0 + (self.size - 1.0) * size.hp_effect)
+    }
+
+    /// Movement speed in battle, bigger species are slower.
+    pub fn sized_movement_speed(&self, size: &SizeRules) -> f32 {
+        self.movement_speed * (1.0 - (self.size - 1.0) * size.speed_effect)
+    }
+
+    pub fn mutate(&mut self, balance: &Balance, rng: &mut StdRng) {
+        let mut change = |range: StatRange| Self::get_mutation_change(balance, rng, range);
+
+        self.movement_speed += change(balance.stats.movement_speed);
+        self.hp += change(balance.stats.hp);
+        self.stamina += change(balance.stats.stamina);
+        self.stamina_regen += change(balance.stats.stamina_regen);
 
         for ability in self.physical_abilities.iter_mut() {
-            ability.damage += Self::get_mutation_change(rng, MIN_PHYS_DMG, MAX_PHYS_DMG);
-            ability.stamina_cost +=
-                Self::get_mutation_change(rng, MIN_PHYS_STAMINA_COST, MAX_PHYS_STAMINA_COST);
-            ability.global_cooldown +=
-                Self::get_mutation_change(rng, MIN_PHYS_COOLDOWN, MAX_PHYS_COOLDOWN);
+            ability.damage += change(balance.physical.damage);
+            ability.stamina_cost += change(balance.physical.stamina_cost);
+            ability.global_cooldown += change(balance.physical.cooldown);
+            if ability.is_ranged() {
+                ability.range += change(balance.physical.range);
+            }
+        }
+
+        self.mana += change(balance.stats.mana);
+        self.mana_regen += change(balance.stats.mana_regen);
+
+        for ability in self.magic_abilities.iter_mut() {
+            ability.power += change(ability.kind.power_range(&balance.magic));
+            ability.mana_cost += change(balance.magic.mana_cost);
+            ability.cooldown += change(balance.magic.cooldown);
+            ability.range += change(balance.magic.range);
+        }
+
+        // the size stays in its range, so sprites never grow out of proportion
+        let size_range = balance.size.range;
+        if rng.gen_bool(balance.mutation.chance) {
+            let spread =
+                balance.mutation.spread * (size_range.max - size_range.min) / balance.tiers as f32;
+            self.size =
+                (self.size + rng.gen_range(-spread..=spread)).clamp(size_range.min, size_range.max);
+        }
+        for hue in self.hues.iter_mut() {
+            if rng.gen_bool(balance.mutation.chance) {
+                let spread = balance.mutation.hue_spread;
+                *hue = (*hue + rng.gen_range(-spread..=spread)).rem_euclid(360.0);
+            }
+        }
+
+        if rng.gen_bool(balance.mutation.archetype_chance) {
+            self.archetype = match self.archetype {
+                Archetype::Melee => Archetype::Ranged,
+                Archetype::Ranged => Archetype::Melee,
+            };
         }
     }
 
-    fn get_mutation_change(rng: &mut StdRng, min: f32, max: f32) -> f32 {
-        if !rng.gen_bool(MUTATION_CHANCE) {
+    fn get_mutation_change(balance: &Balance, rng: &mut StdRng, range: StatRange) -> f32 {
+        if !rng.gen_bool(balance.mutation.chance) {
             return 0.0;
         }
 
-        let range_width = max - min;
-        let subrange_width = range_width / NUM_TIERS as f32;
+        let range_width = range.max - range.min;
+        let subrange_width = range_width / balance.tiers as f32;
+        let spread = balance.mutation.spread;
+
+        (rng.gen_range(-spread..=spread) * subrange_width).max(range.min)
+    }
+
+    /// Smallest and largest change [`CreatureStats::mutate`] can make to a stat with the range,
+    /// including no change at all when the stat does not mutate.
+    pub fn mutation_change_range(balance: &Balance, range: StatRange) -> StatRange {
+        if balance.mutation.chance <= 0.0 {
+            return StatRange { min: 0.0, max: 0.0 };
+        }
+
+        let subrange_width = (range.max - range.min) / balance.tiers as f32;
+        let spread = balance.mutation.spread * subrange_width;
+        let mut change = StatRange {
+            min: (-spread).max(range.min),
+            max: spread.max(range.min),
+        };
+        if balance.mutation.chance < 1.0 {
+            change.min = change.min.min(0.0);
+            change.max = change.max.max(0.0);
+        }
 
-        (rng.gen_range(-1.5..=1.5) * subrange_width).max(min)
+        change
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhysicalAbility {
-    pub name: &'static str,
+    pub name: String,
     pub stamina_cost: f32,
     pub damage: f32,
     pub global_cooldown: f32,
+    pub range: f32,
+}
+
+impl PhysicalAbility {
+    /// Ranged abilities fire projectiles instead of hitting the target directly.
+    pub fn is_ranged(&self) -> bool {
+        self.range > MELEE_DISTANCE
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MagicKind {
+    /// Fires a projectile at the target.
+    RangedDamage,
+    /// Damages every enemy around the target.
+    AreaDamage,
+    /// Heals the most injured ally in range.
+    Heal,
+    /// Increases the damage dealt by allies in range for a while.
+    Buff,
+}
+
+impl MagicKind {
+    pub const ALL: [MagicKind; 4] = [
+        MagicKind::RangedDamage,
+        MagicKind::AreaDamage,
+        MagicKind::Heal,
+        MagicKind::Buff,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MagicKind::RangedDamage => "Fireball",
+            MagicKind::AreaDamage => "Quake",
+            MagicKind::Heal => "Heal",
+            MagicKind::Buff => "Roar",
+        }
+    }
+
+    /// Name of the stat stored in [`MagicAbility::power`].
+    pub fn power_name(&self) -> &'static str {
+        match self {
+            MagicKind::RangedDamage | MagicKind::AreaDamage => "Damage",
+            MagicKind::Heal => "Heal",
+            MagicKind::Buff => "Damage Bonus",
+        }
+    }
+
+    pub fn power_range(&self, magic: &MagicRanges) -> StatRange {
+        match self {
+            MagicKind::Buff => magic.buff_power,
+            _ => magic.power,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicAbility {
+    pub kind: MagicKind,
+    /// Damage, amount of healed HP or damage multiplier bonus, depending on the kind.
+    pub power: f32,
+    pub mana_cost: f32,
+    pub cooldown: f32,
+    pub range: f32,
 }
 
 #[derive(Resource)]
@@ -114,80 +338,256 @@ pub fn generate_creature(
     commands: &mut Commands,
     rng: &mut StdRng,
     textures: &Res<TextureAssets>,
+    balance: &Balance,
     tier: u8,
     generation: u64,
     pop_multiplier: f32,
 ) -> Entity {
-    let creature = CreatureStats {
-        movement_speed: generate_stat_value(
-            MIN_MOVEMENT_SPEED,
-            MAX_MOVEMENT_SPEED,
-            tier,
-            rng,
-            false,
-        ),
-        hp: generate_stat_value(MIN_HP, MAX_HP, tier, rng, false),
-        stamina: generate_stat_value(MIN_STAMINA, MAX_STAMINA, tier, rng, false),
-        stamina_regen: generate_stat_value(MIN_STAMINA_REGEN, MAX_STAMINA_REGEN, tier, rng, false),
-        physical_abilities: vec![
-            generate_physical_ability("Bite", tier, rng),
-            generate_physical_ability("Punch", tier, rng),
-            generate_physical_ability("Kick", tier, rng),
-        ],
-        generation,
-    };
+    let creature = generate_creature_stats(balance, rng, tier, generation);
+    let population = generate_population_size(balance, rng, pop_multiplier);
+    let body_parts = generate_creature_appearance(balance, &creature, rng);
 
-    let population = rng.gen_range(MIN_POPULATION..=MAX_POPULATION) as f32;
-    let population = population * pop_multiplier;
-    let population = PopulationSize(population as u32);
+    spawn_creature(commands, textures, creature, population, &body_parts)
+}
 
+/// Spawns a creature template, `body_parts` contains the body part variant of each slot. The
+/// species is named after its body parts.
+pub fn spawn_creature(
+    commands: &mut Commands,
+    textures: &Res<TextureAssets>,
+    creature: CreatureStats,
+    population: PopulationSize,
+    body_parts: &[usize],
+) -> Entity {
+    let sprites = body_part_sprites(textures, body_parts, &creature.hues);
+    let scale = CREATURE_SCALE * creature.size;
     let entity = commands
         .spawn(SpriteBundle {
             visibility: Visibility::Hidden,
-            transform: Transform::from_scale(Vec2::splat(CREATURE_SCALE).extend(CREATURE_Z)),
+            transform: Transform::from_scale(Vec2::splat(scale).extend(CREATURE_Z)),
             ..default()
         })
         .insert(creature)
         .insert(population)
+        .insert(Stance::default())
+        .insert(SpeciesName(generate_name(body_parts)))
         .id();
-    generate_creature_appearance(commands, textures, rng, entity);
+
+    commands.entity(entity).with_children(|children| {
+        for sprite in sprites {
+            children.spawn((sprite, BodyPart));
+        }
+    });
 
     entity
 }
 
-fn generate_stat_value(min: f32, max: f32, tier: u8, rng: &mut StdRng, inverse: bool) -> f32 {
-    let range_width = max - min;
-    let subrange_width = range_width / NUM_TIERS as f32;
-    let subrange_center = min + (tier as f32 - 0.5) * subrange_width;
+/// Color multiplied onto a body part with the hue.
+pub fn body_part_tint(hue: f32) -> Color {
+    Color::hsl(hue, TINT_SATURATION, TINT_LIGHTNESS)
+}
+
+/// Sprites of the body parts with the given variants and hues, positioned as children of a
+/// creature.
+pub fn body_part_sprites(
+    textures: &TextureAssets,
+    body_parts: &[usize],
+    hues: &[f32],
+) -> Vec<SpriteBundle> {
+    body_parts
+        .iter()
+        .zip(hues)
+        .zip(BODY_PARTS_Z.iter())
+        .enumerate()
+        .map(|(i, ((&variant, &hue), &z))| SpriteBundle {
+            sprite: Sprite {
+                rect: Some(body_part_rect(i, variant)),
+                color: body_part_tint(hue),
+                ..default()
+            },
+            texture: textures.body_parts.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, z),
+            ..default()
+        })
+        .collect()
+}
+
+/// UI images of the body parts with the given variants, each one fills a node of `size` pixels so
+/// they can be stacked inside of it.
+pub fn body_part_images(
+    textures: &TextureAssets,
+    atlas: &BodyPartAtlas,
+    body_parts: &[usize],
+    hues: &[f32],
+    size: f32,
+) -> Vec<(ImageBundle, TextureAtlas)> {
+    body_parts
+        .iter()
+        .zip(hues)
+        .zip(BODY_PARTS_Z.iter())
+        .enumerate()
+        .map(|(i, ((&variant, &hue), &z))| {
+            (
+                ImageBundle {
+                    image: UiImage::new(textures.body_parts.clone())
+                        .with_color(body_part_tint(hue)),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        ..default()
+                    },
+                    z_index: ZIndex::Local(z as i32),
+                    ..default()
+                },
+                TextureAtlas {
+                    layout: atlas.0.clone(),
+                    index: i * BODY_PART_TYPES + variant,
+                },
+            )
+        })
+        .collect()
+}
+
+pub fn generate_creature_stats(
+    balance: &Balance,
+    rng: &mut StdRng,
+    tier: u8,
+    generation: u64,
+) -> CreatureStats {
+    let archetype = if rng.gen_bool(balance.ranged_chance) {
+        Archetype::Ranged
+    } else {
+        Archetype::Melee
+    };
+    let ability_names = match archetype {
+        Archetype::Melee => &balance.ability_templates.melee,
+        Archetype::Ranged => &balance.ability_templates.ranged,
+    };
+    let stats = &balance.stats;
+
+    CreatureStats {
+        archetype,
+        movement_speed: generate_stat_value(balance, stats.movement_speed, tier, rng, false),
+        hp: generate_stat_value(balance, stats.hp, tier, rng, false),
+        stamina: generate_stat_value(balance, stats.stamina, tier, rng, false),
+        stamina_regen: generate_stat_value(balance, stats.stamina_regen, tier, rng, false),
+        physical_abilities: ability_names
+            .iter()
+            .map(|name| generate_physical_ability(balance, name, archetype, tier, rng))
+            .collect(),
+        mana: generate_stat_value(balance, stats.mana, tier, rng, false),
+        mana_regen: generate_stat_value(balance, stats.mana_regen, tier, rng, false),
+        magic_abilities: MagicKind::ALL
+            .choose_multiple(rng, balance.magic.ability_count)
+            .map(|&kind| generate_magic_ability(balance, kind, tier, rng))
+            .collect(),
+        generation,
+        size: rng.gen_range(balance.size.range.min..=balance.size.range.max),
+        hues: (0..BODY_PARTS_COUNT)
+            .map(|_| rng.gen_range(0.0..360.0))
+            .collect(),
+        recessive: None,
+    }
+}
+
+pub fn generate_population_size(
+    balance: &Balance,
+    rng: &mut StdRng,
+    pop_multiplier: f32,
+) -> PopulationSize {
+    let population = rng.gen_range(balance.population.min..=balance.population.max) as f32;
+    let population = population * pop_multiplier;
+
+    PopulationSize(population as u32)
+}
+
+/// Tier whose subranges the base stats of the species fall into on average, an estimate as the
+/// tier of a species is not kept.
+pub fn estimate_tier(balance: &Balance, stats: &CreatureStats) -> f32 {
+    let ranges = &balance.stats;
+    let values = [
+        (stats.movement_speed, ranges.movement_speed),
+        (stats.hp, ranges.hp),
+        (stats.stamina, ranges.stamina),
+        (stats.stamina_regen, ranges.stamina_regen),
+        (stats.mana, ranges.mana),
+        (stats.mana_regen, ranges.mana_regen),
+    ];
+
+    let tier = values
+        .iter()
+        .map(|&(value, range)| {
+            (value - range.min) / (range.max - range.min) * balance.tiers as f32 + 0.5
+        })
+        .sum::<f32>()
+        / values.len() as f32;
+
+    tier.clamp(1.0, balance.tiers as f32)
+}
+
+fn generate_stat_value(
+    balance: &Balance,
+    range: StatRange,
+    tier: u8,
+    rng: &mut StdRng,
+    inverse: bool,
+) -> f32 {
+    let range_width = range.max - range.min;
+    let subrange_width = range_width / balance.tiers as f32;
+    let subrange_center = range.min + (tier as f32 - 0.5) * subrange_width;
     let std_dev = subrange_width / 4.0;
     let normal_dist = Normal::new(subrange_center, std_dev).unwrap();
 
     let mut stat_value = normal_dist.sample(rng);
-    stat_value = stat_value.clamp(min, max);
+    stat_value = stat_value.clamp(range.min, range.max);
 
     if inverse {
-        stat_value = max - (stat_value - min);
+        stat_value = range.max - (stat_value - range.min);
     }
 
     stat_value
 }
 
-fn generate_physical_ability(name: &'static str, tier: u8, rng: &mut StdRng) -> PhysicalAbility {
+fn generate_physical_ability(
+    balance: &Balance,
+    name: &str,
+    archetype: Archetype,
+    tier: u8,
+    rng: &mut StdRng,
+) -> PhysicalAbility {
+    let physical = &balance.physical;
+
     PhysicalAbility {
-        name,
-        stamina_cost: generate_stat_value(
-            MIN_PHYS_STAMINA_COST,
-            MAX_PHYS_STAMINA_COST,
-            tier,
-            rng,
-            false,
-        ),
-        damage: generate_stat_value(MIN_PHYS_DMG, MAX_PHYS_DMG, tier, rng, false),
-        global_cooldown: generate_stat_value(MIN_PHYS_COOLDOWN, MAX_PHYS_COOLDOWN, tier, rng, true),
+        name: name.to_string(),
+        stamina_cost: generate_stat_value(balance, physical.stamina_cost, tier, rng, false),
+        damage: generate_stat_value(balance, physical.damage, tier, rng, false),
+        global_cooldown: generate_stat_value(balance, physical.cooldown, tier, rng, true),
+        range: match archetype {
+            Archetype::Melee => MELEE_DISTANCE,
+            Archetype::Ranged => generate_stat_value(balance, physical.range, tier, rng, false),
+        },
     }
 }
 
-fn delete_empty_creatures(
+fn generate_magic_ability(
+    balance: &Balance,
+    kind: MagicKind,
+    tier: u8,
+    rng: &mut StdRng,
+) -> MagicAbility {
+    let magic = &balance.magic;
+
+    MagicAbility {
+        kind,
+        power: generate_stat_value(balance, kind.power_range(magic), tier, rng, false),
+        mana_cost: generate_stat_value(balance, magic.mana_cost, tier, rng, false),
+        cooldown: generate_stat_value(balance, magic.cooldown, tier, rng, true),
+        range: generate_stat_value(balance, magic.range, tier, rng, false),
+    }
+}
+
+pub fn delete_empty_creatures(
     mut commands: Commands,
     query: Query<(Entity, &PopulationSize)>,
     mut er_population_changed: EventReader<PopulationChangedEvent>,
@@ -201,45 +601,139 @@ fn delete_empty_creatures(
     }
 }
 
-fn breed_creatures(
+pub fn breed_creatures(
     mut er_round_over: EventReader<RoundOverEvent>,
-    mut population_query: Query<&mut PopulationSize, With<PlayerCreature>>,
+    mut population_query: Query<(&mut PopulationSize, Option<&Reserve>), With<PlayerCreature>>,
+    balance: Res<Balance>,
 ) {
     for _ in er_round_over.read() {
-        for mut population in population_query.iter_mut() {
-            population.0 = (population.0 as f32 * 1.5) as u32;
+        for (mut population, reserve) in population_query.iter_mut() {
+            let reserve = reserve.map_or(0, |reserve| reserve.0.min(population.0));
+            let fought = population.0 - reserve;
+            population.0 = (fought as f32 * balance.breeding_multiplier
+                + reserve as f32 * balance.deployment.reserve_breeding_multiplier)
+                as u32;
         }
     }
 }
 
+/// Stat which decides the variant of a body part, later variants belong to higher tiers.
+#[derive(Clone, Copy)]
+enum BodyPartStat {
+    Hp,
+    /// Damage of the physical ability at this position of the ability templates, like the bite
+    /// for the head and the punch for the arms.
+    Ability(usize),
+    MovementSpeed,
+}
+
+impl BodyPartStat {
+    /// Value of the stat and the range it is generated in, `None` when the species lacks the
+    /// ability.
+    fn value(&self, balance: &Balance, stats: &CreatureStats) -> Option<(f32, StatRange)> {
+        match *self {
+            BodyPartStat::Hp => Some((stats.hp, balance.stats.hp)),
+            BodyPartStat::MovementSpeed => {
+                Some((stats.movement_speed, balance.stats.movement_speed))
+            }
+            BodyPartStat::Ability(i) => {
+                let templates = &balance.ability_templates;
+                let names = [templates.melee.get(i), templates.ranged.get(i)];
+
+                stats
+                    .physical_abilities
+                    .iter()
+                    .find(|ability| names.contains(&Some(&ability.name)))
+                    .map(|ability| (ability.damage, balance.physical.damage))
+            }
+        }
+    }
+
+    /// Tier the value of the stat would be generated at, species without the ability get the
+    /// lowest tier.
+    fn tier(&self, balance: &Balance, stats: &CreatureStats) -> u8 {
+        let Some((value, range)) = self.value(balance, stats) else {
+            return 1;
+        };
+        let subrange_width = (range.max - range.min) / balance.tiers as f32;
+        let tier = ((value - range.min) / subrange_width).floor() as i32 + 1;
+
+        tier.clamp(1, balance.tiers as i32) as u8
+    }
+}
+
+/// Body part variants which show the tier.
+fn tier_variants(balance: &Balance, tier: u8) -> Range<usize> {
+    let tiers = balance.tiers as usize;
+    let start = (tier as usize - 1) * BODY_PART_TYPES / tiers;
+    let end = (tier as usize * BODY_PART_TYPES / tiers).max(start + 1);
+
+    start..end
+}
+
 pub fn generate_creature_appearance(
-    commands: &mut Commands,
-    textures: &Res<TextureAssets>,
+    balance: &Balance,
+    stats: &CreatureStats,
     rng: &mut StdRng,
-    entity: Entity,
-) {
-    commands.entity(entity).with_children(|children| {
-        for (i, &z) in BODY_PARTS_Z.iter().enumerate() {
-            let pos = Vec2::new(
-                (rng.gen_range(0..BODY_PART_TYPES) as f32 + 0.5) * BODY_PARTS_CELL_SIZE as f32,
-                BODY_PARTS_CELL_SIZE as f32 * (i as f32 + 0.5),
-            );
-
-            children.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        rect: Some(Rect::from_center_size(
-                            pos,
-                            Vec2::splat(BODY_PARTS_CELL_SIZE as f32),
-                        )),
-                        ..default()
-                    },
-                    texture: textures.body_parts.clone(),
-                    transform: Transform::from_xyz(0.0, 0.0, z),
-                    ..default()
-                },
-                BodyPart,
-            ));
-        }
-    });
+) -> Vec<usize> {
+    BODY_PART_STATS
+        .iter()
+        .map(|stat| rng.gen_range(tier_variants(balance, stat.tier(balance, stats))))
+        .collect()
+}
+
+/// Body parts of a combined species, every part is passed down together with its stat.
+/// `inherited` are the stats of the child before the mutation and `child` after it, parts whose
+/// stat mutated into another tier are chosen anew.
+pub fn inherit_appearance(
+    balance: &Balance,
+    parents: [(&CreatureStats, &[usize]); 2],
+    inherited: &CreatureStats,
+    child: &CreatureStats,
+    rng: &mut StdRng,
+) -> Vec<usize> {
+    BODY_PART_STATS
+        .iter()
+        .enumerate()
+        .map(|(slot, stat)| {
+            let value = stat.value(balance, inherited).map(|(value, _)| value);
+            let variants = tier_variants(balance, stat.tier(balance, inherited));
+            let parent_variants = parents.map(|(_, body_parts)| body_parts[slot]);
+
+            // the part of the parent the stat came from, blended stats keep a part of a parent
+            // which still fits the tier
+            let from_parent = parents
+                .iter()
+                .position(|(stats, _)| stat.value(balance, stats).map(|(v, _)| v) == value);
+            let fitting = parent_variants
+                .iter()
+                .filter(|variant| variants.contains(variant))
+                .collect::<Vec<_>>();
+            let variant = match (from_parent, fitting.choose(rng)) {
+                (Some(parent), _) => parent_variants[parent],
+                (None, Some(&&variant)) => variant,
+                (None, None) => rng.gen_range(variants),
+            };
+
+            let mutated_variants = tier_variants(balance, stat.tier(balance, child));
+            match mutated_variants.contains(&variant) {
+                true => variant,
+                false => rng.gen_range(mutated_variants),
+            }
+        })
+        .collect()
+}
+
+fn body_part_rect(slot: usize, variant: usize) -> Rect {
+    let pos = Vec2::new(
+        (variant as f32 + 0.5) * BODY_PARTS_CELL_SIZE as f32,
+        BODY_PARTS_CELL_SIZE as f32 * (slot as f32 + 0.5),
+    );
+
+    Rect::from_center_size(pos, Vec2::splat(BODY_PARTS_CELL_SIZE as f32))
+}
+
+/// Returns the body part variant shown by a body part sprite.
+pub fn body_part_variant(sprite: &Sprite) -> usize {
+    (sprite.rect.unwrap().min.x / BODY_PARTS_CELL_SIZE as f32) as usize
 }
@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+
+use crate::{
+    balance::Balance,
+    battle::ARENA_MARGIN,
+    creature::{CreatureStats, PopulationSize},
+    rounds::Difficulty,
+    screens::new_creature_screen::PlayerCreature,
+    GameState, WINDOW_SIZE,
+};
+
+/// Part of the arena the player can place their species in.
+pub const PLACEMENT_ZONE: Rect = Rect {
+    min: Vec2::new(
+        -WINDOW_SIZE.x / 2.0 + ARENA_MARGIN,
+        -WINDOW_SIZE.y / 2.0 + ARENA_MARGIN,
+    ),
+    max: Vec2::new(-WINDOW_SIZE.x / 12.0, WINDOW_SIZE.y / 2.0 - ARENA_MARGIN),
+};
+/// Width of a rank of the formations.
+const RANK_DEPTH: f32 = 140.0;
+/// Height of the two groups of the flanks formation.
+const FLANK_HEIGHT: f32 = 200.0;
+const MAX_WEDGE_HEIGHT: f32 = 160.0;
+const MAX_WEDGE_STEP: f32 = 100.0;
+
+pub struct DeploymentPlugin;
+
+impl Plugin for DeploymentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Formation>()
+            .add_systems(OnEnter(GameState::Deployment), assign_deployment)
+            .add_systems(OnEnter(GameState::Placement), apply_formation)
+            .add_systems(
+                Update,
+                apply_formation
+                    .run_if(in_state(GameState::Placement))
+                    .run_if(resource_changed::<Formation>),
+            );
+    }
+}
+
+/// Number of creatures of a player species the player chose to send into battle, the rest stays in
+/// reserve. Species without a choice are deployed as far as the cap allows.
+#[derive(Component, Clone, Copy)]
+pub struct Deployment(pub u32);
+
+impl Deployment {
+    /// Whether the species sits out the next battle completely.
+    pub fn is_benched(deployment: Option<&Deployment>) -> bool {
+        matches!(deployment, Some(Deployment(0)))
+    }
+
+    /// Splits the choice between a squad which keeps `remaining` creatures and the squad split off
+    /// of it, together they deploy as many creatures as before.
+    pub fn split(self, remaining: u32) -> (Deployment, Deployment) {
+        let kept = self.0.min(remaining);
+
+        (Deployment(kept), Deployment(self.0 - kept))
+    }
+}
+
+/// Number of creatures of a player species sent into the next battle, worked out from the
+/// [`Deployment`] and the cap of the round whenever the deployment step starts.
+#[derive(Component, Clone, Copy)]
+pub struct Deployed(pub u32);
+
+/// Creatures of a player species which stayed out of the last battle, they are safe from its
+/// losses and breed at their own rate.
+#[derive(Component)]
+pub struct Reserve(pub u32);
+
+/// Deploys every player species as far as its population and the cap of the round allow, chosen
+/// deployments first and then the older species.
+pub fn assign_deployment(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &CreatureStats, &PopulationSize, Option<&Deployment>),
+        With<PlayerCreature>,
+    >,
+    difficulty: Res<Difficulty>,
+    balance: Res<Balance>,
+) {
+    let mut species = query.iter().collect::<Vec<_>>();
+    species.sort_by_key(|(_, stats, _, _)| stats.generation);
+
+    let mut remaining = difficulty.deployment_cap(&balance.deployment);
+    // chosen deployments keep their place, new species share what is left
+    let chosen = species
+        .iter()
+        .filter_map(|&(entity, _, &PopulationSize(size), deployment)| {
+            deployment.map(|&Deployment(count)| (entity, count.min(size)))
+        })
+        .collect::<Vec<_>>();
+    for (entity, count) in chosen {
+        let count = count.min(remaining);
+        remaining -= count;
+        commands.entity(entity).insert(Deployed(count));
+    }
+
+    for (entity, _, &PopulationSize(size), deployment) in species {
+        if deployment.is_none() {
+            let count = size.min(remaining);
+            remaining -= count;
+            commands.entity(entity).insert(Deployed(count));
+        }
+    }
+}
+
+/// Region of the arena the creatures of a deployed player species spawn in.
+#[derive(Component, Clone, Copy)]
+pub struct Placement(pub Rect);
+
+/// Preset arrangement of the deployed species, species can be moved on from there.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Formation {
+    /// Every species side by side at the front.
+    #[default]
+    Line,
+    /// The oldest species at the tip, the others falling back to both sides.
+    Wedge,
+    /// Species split between the top and the bottom of the arena.
+    Flanks,
+    /// Half of the species at the front, the other half at the back.
+    ReserveBehind,
+}
+
+impl Formation {
+    pub const ALL: [Formation; 4] = [
+        Formation::Line,
+        Formation::Wedge,
+        Formation::Flanks,
+        Formation::ReserveBehind,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Formation::Line => "Line",
+            Formation::Wedge => "Wedge",
+            Formation::Flanks => "Flanks",
+            Formation::ReserveBehind => "Reserve behind",
+        }
+    }
+
+    /// Regions of `count` species inside of the placement zone, in the order of the species.
+    pub fn regions(self, count: usize) -> Vec<Rect> {
+        let zone = PLACEMENT_ZONE;
+        let front = Rect::new(zone.max.x - RANK_DEPTH, zone.min.y, zone.max.x, zone.max.y);
+
+        match self {
+            Formation::Line => split_rank(front, count, true),
+            Formation::ReserveBehind => {
+                let back = Rect::new(zone.min.x, zone.min.y, zone.min.x + RANK_DEPTH, zone.max.y);
+                let front_count = count.div_ceil(2);
+
+                let mut regions = split_rank(front, front_count, true);
+                regions.extend(split_rank(back, count - front_count, true));
+                regions
+            }
+            Formation::Flanks => {
+                let top = Rect::new(
+                    zone.min.x,
+                    zone.max.y - FLANK_HEIGHT,
+                    zone.max.x,
+                    zone.max.y,
+                );
+                let bottom = Rect::new(
+                    zone.min.x,
+                    zone.min.y,
+                    zone.max.x,
+                    zone.min.y + FLANK_HEIGHT,
+                );
+                let mut top = split_rank(top, count.div_ceil(2), false).into_iter();
+                let mut bottom = split_rank(bottom, count / 2, false).into_iter();
+
+                (0..count)
+                    .filter_map(|i| match i % 2 {
+                        0 => top.next(),
+                        _ => bottom.next(),
+                    })
+                    .collect()
+            }
+            Formation::Wedge => {
+                // species 2i - 1 and 2i form the i-th rank behind the tip
+                let ranks = count / 2;
+                let height = (zone.height() / (2 * ranks + 1) as f32).min(MAX_WEDGE_HEIGHT);
+                let step = ((zone.width() - RANK_DEPTH) / ranks.max(1) as f32).min(MAX_WEDGE_STEP);
+
+                (0..count)
+                    .map(|i| {
+                        let rank = i.div_ceil(2);
+                        let side = match i % 2 {
+                            0 => -1.0,
+                            _ => 1.0,
+                        };
+                        let center = Vec2::new(
+                            zone.max.x - RANK_DEPTH / 2.0 - rank as f32 * step,
+                            zone.center().y + side * rank as f32 * height,
+                        );
+
+                        Rect::from_center_size(center, Vec2::new(RANK_DEPTH, height))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Splits a rank into `count` equal regions from top to bottom or from front to back.
+fn split_rank(rank: Rect, count: usize, vertical: bool) -> Vec<Rect> {
+    (0..count)
+        .map(|i| {
+            let (start, end) = (i as f32 / count as f32, (i + 1) as f32 / count as f32);
+            match vertical {
+                true => Rect::new(
+                    rank.min.x,
+                    rank.max.y - rank.height() * end,
+                    rank.max.x,
+                    rank.max.y - rank.height() * start,
+                ),
+                false => Rect::new(
+                    rank.max.x - rank.width() * end,
+                    rank.min.y,
+                    rank.max.x - rank.width() * start,
+                    rank.max.y,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Places the deployed species in the chosen formation, older species take the front.
+pub fn apply_formation(
+    mut commands: Commands,
+    query: Query<(Entity, &CreatureStats, &Deployed), With<PlayerCreature>>,
+    formation: Res<Formation>,
+) {
+    let mut species = query
+        .iter()
+        .filter(|(_, _, &Deployed(count))| count > 0)
+        .collect::<Vec<_>>();
+    species.sort_by_key(|(_, stats, _)| stats.generation);
+
+    for ((entity, _, _), region) in species.iter().zip(formation.regions(species.len())) {
+        commands.entity(*entity).insert(Placement(region));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::creature::generate_creature_stats;
+
+    fn spawn_species(world: &mut World, generation: u64, population: u32) -> Entity {
+        let balance = world.resource::<Balance>();
+        let stats = generate_creature_stats(balance, &mut StdRng::seed_from_u64(0), 1, generation);
+
+        world
+            .spawn((stats, PopulationSize(population), PlayerCreature))
+            .id()
+    }
+
+    fn deployed(world: &World, entity: Entity) -> u32 {
+        world.get::<Deployed>(entity).unwrap().0
+    }
+
+    #[test]
+    fn species_without_a_choice_grow_with_the_cap() {
+        let mut world = World::new();
+        let mut balance = Balance::default();
+        balance.deployment.start_cap = 20;
+        balance.deployment.cap_increase = 10;
+        world.insert_resource(balance);
+        world.init_resource::<Difficulty>();
+
+        let chosen = spawn_species(&mut world, 0, 15);
+        let oldest = spawn_species(&mut world, 1, 15);
+        let newer = spawn_species(&mut world, 2, 15);
+        world.entity_mut(chosen).insert(Deployment(4));
+
+        world.run_system_once(assign_deployment);
+        assert_eq!(deployed(&world, chosen), 4);
+        assert_eq!(deployed(&world, oldest), 15);
+        assert_eq!(deployed(&world, newer), 1);
+
+        world.resource_mut::<Difficulty>().inc_difficulty();
+        let newest = spawn_species(&mut world, 3, 15);
+
+        world.run_system_once(assign_deployment);
+        assert_eq!(deployed(&world, chosen), 4);
+        assert_eq!(deployed(&world, oldest), 15);
+        assert_eq!(deployed(&world, newer), 11);
+        assert_eq!(deployed(&world, newest), 0);
+        assert!(world.get::<Deployment>(newer).is_none());
+    }
+
+    #[test]
+    fn split_squads_deploy_the_chosen_count() {
+        let mut world = World::new();
+        world.insert_resource(Balance::default());
+        world.init_resource::<Difficulty>();
+
+        // 5 of a species of 10 which deploys 3 are split off
+        let (kept, split_off) = Deployment(3).split(5);
+        let original = spawn_species(&mut world, 0, 5);
+        let squad = spawn_species(&mut world, 0, 5);
+        world.entity_mut(original).insert(kept);
+        world.entity_mut(squad).insert(split_off);
+
+        world.run_system_once(assign_deployment);
+        assert_eq!(deployed(&world, original) + deployed(&world, squad), 3);
+
+        let (Deployment(kept), Deployment(split_off)) = Deployment(8).split(5);
+        assert_eq!((kept, split_off), (5, 3));
+        let (Deployment(kept), Deployment(split_off)) = Deployment(0).split(5);
+        assert_eq!((kept, split_off), (0, 0));
+    }
+}
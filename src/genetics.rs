@@ -0,0 +1,1096 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    balance::{Balance, StatRange},
+    creature::{Archetype, CreatureStats, MagicAbility, PhysicalAbility, RecessiveAlleles},
+};
+
+/// Decides how a combined species inherits the traits of its parents.
+pub trait InheritanceModel: Sync {
+    /// Chance that the first parent passes a trait down whole, `strengths` tells how strong each
+    /// parent is in it.
+    fn first_parent_chance(&self, strengths: [f32; 2], balance: &Balance) -> f64;
+
+    /// Index of the parent which passes a trait down whole.
+    fn choose_parent(&self, strengths: [f32; 2], balance: &Balance, rng: &mut StdRng) -> usize {
+        match rng.gen_bool(self.first_parent_chance(strengths, balance)) {
+            true => 0,
+            false => 1,
+        }
+    }
+
+    /// Value of a single stat of the child.
+    fn stat(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> f32 {
+        gene.values[self.choose_parent(gene.strengths(), balance, rng)]
+    }
+
+    /// Shown and hidden allele of a stat of the child. Models without dominance give the child the
+    /// shown value twice.
+    fn alleles(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> [f32; 2] {
+        let value = self.stat(gene, balance, rng);
+        [value, value]
+    }
+
+    /// Shown and hidden allele of a trait which can't be mixed, like the archetype.
+    fn discrete_alleles(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> [f32; 2] {
+        let value = gene.values[self.choose_parent(gene.strengths(), balance, rng)];
+        [value, value]
+    }
+
+    /// Chances that the shown and the hidden allele of a trait which can't be mixed is `value`.
+    fn discrete_chances(&self, gene: &Gene, value: f32, balance: &Balance) -> [f64; 2] {
+        let first_parent_chance = self.first_parent_chance(gene.strengths(), balance);
+        let chance = [first_parent_chance, 1.0 - first_parent_chance]
+            .iter()
+            .zip(gene.values)
+            .filter(|(_, parent_value)| *parent_value == value)
+            .map(|(chance, _)| chance)
+            .sum();
+
+        [chance, chance]
+    }
+
+    /// Lowest and highest value [`InheritanceModel::stat`] usually gives.
+    fn stat_bounds(&self, gene: &Gene, _balance: &Balance) -> StatRange {
+        gene.bounds()
+    }
+
+    /// Lowest and highest hidden allele [`InheritanceModel::alleles`] usually gives.
+    fn hidden_bounds(&self, gene: &Gene, balance: &Balance) -> StatRange {
+        self.stat_bounds(gene, balance)
+    }
+
+    /// Whether the child carries hidden alleles which differ from its shown values.
+    fn carries_alleles(&self) -> bool {
+        false
+    }
+
+    /// Whether abilities which both parents have are mixed stat by stat, otherwise they are passed
+    /// down whole from one parent.
+    fn mixes_abilities(&self) -> bool {
+        false
+    }
+}
+
+/// Stat of both parents.
+pub struct Gene {
+    pub values: [f32; 2],
+    /// Hidden alleles of both parents, the shown values for stats which carry none.
+    pub hidden: [f32; 2],
+    pub range: StatRange,
+    /// Lower values belong to higher tiers, like for cooldowns.
+    pub inverse: bool,
+}
+
+impl Gene {
+    fn new(values: [f32; 2], range: StatRange, inverse: bool) -> Self {
+        Self {
+            values,
+            hidden: values,
+            range,
+            inverse,
+        }
+    }
+
+    fn carrying(self, hidden: [f32; 2]) -> Self {
+        Self { hidden, ..self }
+    }
+
+    /// Values which a higher tier would generate are stronger.
+    fn strengths(&self) -> [f32; 2] {
+        match self.inverse {
+            true => self.values.map(|value| -value),
+            false => self.values,
+        }
+    }
+
+    fn bounds(&self) -> StatRange {
+        value_bounds(self.values)
+    }
+
+    /// The stronger of two alleles first.
+    fn order(&self, [a, b]: [f32; 2]) -> [f32; 2] {
+        let a_is_stronger = match self.inverse {
+            true => a <= b,
+            false => a >= b,
+        };
+
+        match a_is_stronger {
+            true => [a, b],
+            false => [b, a],
+        }
+    }
+}
+
+fn value_bounds(values: impl IntoIterator<Item = f32>) -> StatRange {
+    values.into_iter().fold(
+        StatRange {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+        },
+        |bounds, value| StatRange {
+            min: bounds.min.min(value),
+            max: bounds.max.max(value),
+        },
+    )
+}
+
+/// Every trait comes from a random parent.
+pub struct CoinFlip;
+
+impl InheritanceModel for CoinFlip {
+    fn first_parent_chance(&self, _strengths: [f32; 2], _balance: &Balance) -> f64 {
+        0.5
+    }
+}
+
+/// Stats are the average of both parents with some noise, abilities are mixed as well.
+pub struct Blend;
+
+impl InheritanceModel for Blend {
+    fn first_parent_chance(&self, strengths: [f32; 2], balance: &Balance) -> f64 {
+        CoinFlip.first_parent_chance(strengths, balance)
+    }
+
+    fn stat(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> f32 {
+        let (mean, std_dev) = Blend::distribution(gene, balance);
+        let normal_dist = Normal::new(mean, std_dev).unwrap();
+
+        normal_dist.sample(rng).max(gene.range.min)
+    }
+
+    /// Noise of more than two standard deviations is rare enough to be left out.
+    fn stat_bounds(&self, gene: &Gene, balance: &Balance) -> StatRange {
+        let (mean, std_dev) = Blend::distribution(gene, balance);
+
+        StatRange {
+            min: (mean - 2.0 * std_dev).max(gene.range.min),
+            max: (mean + 2.0 * std_dev).max(gene.range.min),
+        }
+    }
+
+    fn mixes_abilities(&self) -> bool {
+        true
+    }
+}
+
+impl Blend {
+    /// Mean and standard deviation of a blended stat.
+    fn distribution(gene: &Gene, balance: &Balance) -> (f32, f32) {
+        let mean = (gene.values[0] + gene.values[1]) / 2.0;
+        let subrange_width = (gene.range.max - gene.range.min) / balance.tiers as f32;
+
+        (mean, balance.inheritance.blend_noise * subrange_width)
+    }
+}
+
+/// A coin flip biased towards the parent with the stronger trait, melee counts as stronger than
+/// ranged. Unlike [`Dominant`], a weaker trait which is not passed down can't come back in a later
+/// generation.
+pub struct BiasedFlip;
+
+impl InheritanceModel for BiasedFlip {
+    fn first_parent_chance(&self, strengths: [f32; 2], balance: &Balance) -> f64 {
+        match strengths[1] > strengths[0] {
+            true => 1.0 - balance.inheritance.stronger_chance,
+            false => balance.inheritance.stronger_chance,
+        }
+    }
+}
+
+/// Every parent passes one of its two alleles of a stat on at random. The stronger of the two
+/// alleles the child gets is shown and the weaker one is carried hidden, so a weaker trait can skip
+/// a generation and come back when two carriers are combined. Abilities carry no hidden allele, a
+/// shared ability is passed down from the parent with the stronger one.
+pub struct Dominant;
+
+impl Dominant {
+    /// Every pair of alleles the child can get with the shown allele first, all are equally
+    /// likely.
+    fn pairs(gene: &Gene) -> [[f32; 2]; 4] {
+        let [first, second] = [0, 1].map(|parent| [gene.values[parent], gene.hidden[parent]]);
+
+        [(0, 0), (0, 1), (1, 0), (1, 1)].map(|(a, b)| gene.order([first[a], second[b]]))
+    }
+}
+
+impl InheritanceModel for Dominant {
+    fn first_parent_chance(&self, strengths: [f32; 2], _balance: &Balance) -> f64 {
+        match strengths[1] > strengths[0] {
+            true => 0.0,
+            false => 1.0,
+        }
+    }
+
+    fn stat(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> f32 {
+        self.alleles(gene, balance, rng)[0]
+    }
+
+    fn alleles(&self, gene: &Gene, _balance: &Balance, rng: &mut StdRng) -> [f32; 2] {
+        let passed = [0, 1].map(|parent| match rng.gen_bool(0.5) {
+            true => gene.values[parent],
+            false => gene.hidden[parent],
+        });
+
+        gene.order(passed)
+    }
+
+    fn discrete_alleles(&self, gene: &Gene, balance: &Balance, rng: &mut StdRng) -> [f32; 2] {
+        self.alleles(gene, balance, rng)
+    }
+
+    fn discrete_chances(&self, gene: &Gene, value: f32, _balance: &Balance) -> [f64; 2] {
+        let pairs = Dominant::pairs(gene);
+
+        [0, 1].map(|allele| {
+            pairs.iter().filter(|pair| pair[allele] == value).count() as f64 / pairs.len() as f64
+        })
+    }
+
+    fn stat_bounds(&self, gene: &Gene, _balance: &Balance) -> StatRange {
+        value_bounds(Dominant::pairs(gene).map(|[shown, _]| shown))
+    }
+
+    fn hidden_bounds(&self, gene: &Gene, _balance: &Balance) -> StatRange {
+        value_bounds(Dominant::pairs(gene).map(|[_, hidden]| hidden))
+    }
+
+    fn carries_alleles(&self) -> bool {
+        true
+    }
+}
+
+/// Inheritance model chosen for a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InheritanceMode {
+    #[default]
+    CoinFlip,
+    Blend,
+    /// Called dominance in older saves.
+    #[serde(alias = "Dominance")]
+    BiasedFlip,
+    Dominant,
+}
+
+impl InheritanceMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InheritanceMode::CoinFlip => "coin flip",
+            InheritanceMode::Blend => "blend",
+            InheritanceMode::BiasedFlip => "biased flip",
+            InheritanceMode::Dominant => "dominant",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            InheritanceMode::CoinFlip => InheritanceMode::Blend,
+            InheritanceMode::Blend => InheritanceMode::BiasedFlip,
+            InheritanceMode::BiasedFlip => InheritanceMode::Dominant,
+            InheritanceMode::Dominant => InheritanceMode::CoinFlip,
+        }
+    }
+
+    pub fn model(&self) -> &'static dyn InheritanceModel {
+        match self {
+            InheritanceMode::CoinFlip => &CoinFlip,
+            InheritanceMode::Blend => &Blend,
+            InheritanceMode::BiasedFlip => &BiasedFlip,
+            InheritanceMode::Dominant => &Dominant,
+        }
+    }
+}
+
+/// Stats of a species combined from `parents`, before the mutation.
+pub fn combine_stats(
+    model: &dyn InheritanceModel,
+    parents: [&CreatureStats; 2],
+    balance: &Balance,
+    rng: &mut StdRng,
+    generation: u64,
+) -> CreatureStats {
+    let recessive = parents.map(|p| p.recessive_alleles());
+    let mut alleles = |value: fn(&CreatureStats) -> f32,
+                       hidden: fn(&RecessiveAlleles) -> f32,
+                       range: StatRange| {
+        let gene = Gene::new(parents.map(value), range, false)
+            .carrying([hidden(&recessive[0]), hidden(&recessive[1])]);
+        model.alleles(&gene, balance, rng)
+    };
+    let stats = &balance.stats;
+
+    let [movement_speed, hidden_movement_speed] = alleles(
+        |p| p.movement_speed,
+        |r| r.movement_speed,
+        stats.movement_speed,
+    );
+    let [hp, hidden_hp] = alleles(|p| p.hp, |r| r.hp, stats.hp);
+    let [stamina, hidden_stamina] = alleles(|p| p.stamina, |r| r.stamina, stats.stamina);
+    let [stamina_regen, hidden_stamina_regen] = alleles(
+        |p| p.stamina_regen,
+        |r| r.stamina_regen,
+        stats.stamina_regen,
+    );
+    let [mana, hidden_mana] = alleles(|p| p.mana, |r| r.mana, stats.mana);
+    let [mana_regen, hidden_mana_regen] =
+        alleles(|p| p.mana_regen, |r| r.mana_regen, stats.mana_regen);
+    let size_range = balance.size.range;
+    let [size, hidden_size] = alleles(|p| p.size, |r| r.size, size_range)
+        .map(|size| size.clamp(size_range.min, size_range.max));
+
+    let [archetype, hidden_archetype] = model
+        .discrete_alleles(&archetype_gene(parents), balance, rng)
+        .map(archetype_of);
+    // hues are neither stronger nor blendable on the color wheel, every part gets the color of a
+    // random parent
+    let hues = parents[0]
+        .hues
+        .iter()
+        .zip(parents[1].hues.iter())
+        .map(|(&hue1, &hue2)| match rng.gen_bool(0.5) {
+            true => hue1,
+            false => hue2,
+        })
+        .collect();
+
+    let mut child = CreatureStats {
+        archetype,
+        movement_speed,
+        hp,
+        stamina,
+        stamina_regen,
+        mana,
+        mana_regen,
+        physical_abilities: crossover(
+            parents.map(|p| p.physical_abilities.as_slice()),
+            |ability| ability.name.clone(),
+            |ability| ability.damage,
+            |abilities, rng| combine_physical(model, abilities, balance, rng),
+            model,
+            balance,
+            rng,
+        ),
+        magic_abilities: crossover(
+            parents.map(|p| p.magic_abilities.as_slice()),
+            |ability| ability.kind,
+            |ability| ability.power,
+            |abilities, rng| combine_magic(model, abilities, balance, rng),
+            model,
+            balance,
+            rng,
+        ),
+        generation,
+        size,
+        hues,
+        recessive: None,
+    };
+    let recessive = RecessiveAlleles {
+        archetype: hidden_archetype,
+        movement_speed: hidden_movement_speed,
+        hp: hidden_hp,
+        stamina: hidden_stamina,
+        stamina_regen: hidden_stamina_regen,
+        mana: hidden_mana,
+        mana_regen: hidden_mana_regen,
+        size: hidden_size,
+    };
+    if recessive != child.recessive_alleles() {
+        child.recessive = Some(recessive);
+    }
+
+    child
+}
+
+/// Melee is stronger than ranged.
+fn archetype_strength(archetype: Archetype) -> f32 {
+    match archetype {
+        Archetype::Melee => 1.0,
+        Archetype::Ranged => 0.0,
+    }
+}
+
+fn archetype_of(strength: f32) -> Archetype {
+    match strength > 0.5 {
+        true => Archetype::Melee,
+        false => Archetype::Ranged,
+    }
+}
+
+fn archetype_gene(parents: [&CreatureStats; 2]) -> Gene {
+    let range = StatRange { min: 0.0, max: 1.0 };
+
+    Gene::new(
+        parents.map(|p| archetype_strength(p.archetype)),
+        range,
+        false,
+    )
+    .carrying(parents.map(|p| archetype_strength(p.recessive_alleles().archetype)))
+}
+
+/// Abilities of the child. Abilities which both parents have are inherited unless they are lost,
+/// abilities of a single parent are only sometimes gained. At least one ability is kept when the
+/// parents have any.
+fn crossover<T: Clone, K: PartialEq>(
+    abilities: [&[T]; 2],
+    key: impl Fn(&T) -> K,
+    strength: impl Fn(&T) -> f32,
+    mut mix: impl FnMut([&T; 2], &mut StdRng) -> T,
+    model: &dyn InheritanceModel,
+    balance: &Balance,
+    rng: &mut StdRng,
+) -> Vec<T> {
+    let rules = &balance.inheritance;
+    let mut inherited = Vec::new();
+
+    for (parent, ability) in abilities[0]
+        .iter()
+        .map(|ability| (0, ability))
+        .chain(abilities[1].iter().map(|ability| (1, ability)))
+    {
+        let other = abilities[1 - parent]
+            .iter()
+            .find(|other| key(other) == key(ability));
+
+        match other {
+            // shared abilities are handled once, with the first parent
+            Some(_) if parent == 1 => {}
+            Some(other) => {
+                if rng.gen_bool(rules.ability_loss_chance) {
+                    continue;
+                }
+
+                let pair = [ability, other];
+                inherited.push(match model.mixes_abilities() {
+                    true => mix(pair, rng),
+                    false => {
+                        let chosen = model.choose_parent(pair.map(&strength), balance, rng);
+                        pair[chosen].clone()
+                    }
+                });
+            }
+            None => {
+                if rng.gen_bool(rules.ability_gain_chance) {
+                    inherited.push(ability.clone());
+                }
+            }
+        }
+    }
+
+    if inherited.is_empty() {
+        let all = abilities.concat();
+        if let Some(ability) = all.choose(rng) {
+            inherited.push(ability.clone());
+        }
+    }
+
+    inherited
+}
+
+fn combine_physical(
+    model: &dyn InheritanceModel,
+    [a, b]: [&PhysicalAbility; 2],
+    balance: &Balance,
+    rng: &mut StdRng,
+) -> PhysicalAbility {
+    let physical = &balance.physical;
+    let mut stat = |values: [f32; 2], range: StatRange, inverse: bool| {
+        model.stat(&Gene::new(values, range, inverse), balance, rng)
+    };
+
+    PhysicalAbility {
+        name: a.name.clone(),
+        stamina_cost: stat(
+            [a.stamina_cost, b.stamina_cost],
+            physical.stamina_cost,
+            false,
+        ),
+        damage: stat([a.damage, b.damage], physical.damage, false),
+        global_cooldown: stat(
+            [a.global_cooldown, b.global_cooldown],
+            physical.cooldown,
+            true,
+        ),
+        range: match a.is_ranged() && b.is_ranged() {
+            true => stat([a.range, b.range], physical.range, false),
+            false => a.range,
+        },
+    }
+}
+
+fn combine_magic(
+    model: &dyn InheritanceModel,
+    [a, b]: [&MagicAbility; 2],
+    balance: &Balance,
+    rng: &mut StdRng,
+) -> MagicAbility {
+    let magic = &balance.magic;
+    let mut stat = |values: [f32; 2], range: StatRange, inverse: bool| {
+        model.stat(&Gene::new(values, range, inverse), balance, rng)
+    };
+
+    MagicAbility {
+        kind: a.kind,
+        power: stat([a.power, b.power], a.kind.power_range(magic), false),
+        mana_cost: stat([a.mana_cost, b.mana_cost], magic.mana_cost, false),
+        cooldown: stat([a.cooldown, b.cooldown], magic.cooldown, true),
+        range: stat([a.range, b.range], magic.range, false),
+    }
+}
+
+/// Values a stat of a combined species can take.
+pub struct StatPrediction {
+    pub name: &'static str,
+    pub inherited: StatRange,
+    /// Values once the mutation is applied.
+    pub mutated: StatRange,
+    /// Values of the hidden allele, which does not mutate. `None` when the child carries no
+    /// hidden allele of the stat.
+    pub hidden: Option<StatRange>,
+}
+
+pub struct AbilityPrediction {
+    pub name: String,
+    /// Parents which have the ability.
+    pub holders: [bool; 2],
+    /// Chance that the child has the ability.
+    pub chance: f64,
+    pub stats: Vec<StatPrediction>,
+}
+
+/// Possible outcome of combining two species, see [`combine_stats`].
+pub struct OffspringPrediction {
+    pub melee_chance: f64,
+    /// Chance that the hidden archetype allele is melee, `None` when the child carries none.
+    pub hidden_melee_chance: Option<f64>,
+    pub stats: Vec<StatPrediction>,
+    pub abilities: Vec<AbilityPrediction>,
+}
+
+pub fn predict_offspring(
+    model: &dyn InheritanceModel,
+    parents: [&CreatureStats; 2],
+    balance: &Balance,
+) -> OffspringPrediction {
+    let stats = &balance.stats;
+    let recessive = parents.map(|p| p.recessive_alleles());
+    let stat =
+        |name, value: fn(&CreatureStats) -> f32, hidden: fn(&RecessiveAlleles) -> f32, range| {
+            let gene = Gene::new(parents.map(value), range, false)
+                .carrying([hidden(&recessive[0]), hidden(&recessive[1])]);
+            StatPrediction {
+                hidden: model
+                    .carries_alleles()
+                    .then(|| model.hidden_bounds(&gene, balance)),
+                ..predict_stat(name, model.stat_bounds(&gene, balance), range, balance)
+            }
+        };
+
+    let [melee_chance, hidden_melee_chance] = model.discrete_chances(
+        &archetype_gene(parents),
+        archetype_strength(Archetype::Melee),
+        balance,
+    );
+    let flip_chance = balance.mutation.archetype_chance;
+
+    let mut abilities = ability_slots(parents.map(|p| p.physical_abilities.as_slice()), |a| {
+        a.name.clone()
+    })
+    .into_iter()
+    .map(|slot| predict_physical(model, slot, balance))
+    .collect::<Vec<_>>();
+    abilities.extend(
+        ability_slots(parents.map(|p| p.magic_abilities.as_slice()), |a| a.kind)
+            .into_iter()
+            .map(|slot| predict_magic(model, slot, balance)),
+    );
+
+    OffspringPrediction {
+        melee_chance: melee_chance * (1.0 - flip_chance) + (1.0 - melee_chance) * flip_chance,
+        hidden_melee_chance: model.carries_alleles().then_some(hidden_melee_chance),
+        stats: vec![
+            stat(
+                "Movement Speed",
+                |p| p.movement_speed,
+                |r| r.movement_speed,
+                stats.movement_speed,
+            ),
+            stat("HP", |p| p.hp, |r| r.hp, stats.hp),
+            stat("Stamina", |p| p.stamina, |r| r.stamina, stats.stamina),
+            stat(
+                "Stamina Regen",
+                |p| p.stamina_regen,
+                |r| r.stamina_regen,
+                stats.stamina_regen,
+            ),
+            stat("Mana", |p| p.mana, |r| r.mana, stats.mana),
+            stat(
+                "Mana Regen",
+                |p| p.mana_regen,
+                |r| r.mana_regen,
+                stats.mana_regen,
+            ),
+            predict_size(model, parents, balance),
+        ],
+        abilities,
+    }
+}
+
+fn predict_stat(
+    name: &'static str,
+    inherited: StatRange,
+    range: StatRange,
+    balance: &Balance,
+) -> StatPrediction {
+    let change = CreatureStats::mutation_change_range(balance, range);
+
+    StatPrediction {
+        name,
+        inherited,
+        mutated: StatRange {
+            min: inherited.min + change.min,
+            max: inherited.max + change.max,
+        },
+        hidden: None,
+    }
+}
+
+/// The size mutates inside of its range, unlike other stats.
+fn predict_size(
+    model: &dyn InheritanceModel,
+    parents: [&CreatureStats; 2],
+    balance: &Balance,
+) -> StatPrediction {
+    let range = balance.size.range;
+    let gene = Gene::new(parents.map(|p| p.size), range, false)
+        .carrying(parents.map(|p| p.recessive_alleles().size));
+    let clamp = |bounds: StatRange| StatRange {
+        min: bounds.min,
+        max: bounds.max.min(range.max),
+    };
+    let inherited = clamp(model.stat_bounds(&gene, balance));
+    let spread = match balance.mutation.chance > 0.0 {
+        true => balance.mutation.spread * (range.max - range.min) / balance.tiers as f32,
+        false => 0.0,
+    };
+
+    StatPrediction {
+        name: "Size",
+        inherited,
+        mutated: StatRange {
+            min: (inherited.min - spread).max(range.min),
+            max: (inherited.max + spread).min(range.max),
+        },
+        hidden: model
+            .carries_alleles()
+            .then(|| clamp(model.hidden_bounds(&gene, balance))),
+    }
+}
+
+/// Abilities of both parents in the order [`crossover`] goes through them, paired up by `key`.
+fn ability_slots<T, K: PartialEq>(
+    abilities: [&[T]; 2],
+    key: impl Fn(&T) -> K,
+) -> Vec<[Option<&T>; 2]> {
+    let mut slots = abilities[0]
+        .iter()
+        .map(|ability| {
+            let other = abilities[1].iter().find(|other| key(other) == key(ability));
+            [Some(ability), other]
+        })
+        .collect::<Vec<_>>();
+    slots.extend(
+        abilities[1]
+            .iter()
+            .filter(|ability| !abilities[0].iter().any(|other| key(other) == key(ability)))
+            .map(|ability| [None, Some(ability)]),
+    );
+
+    slots
+}
+
+/// Values of the ability in the slot, abilities of a single parent are passed down unchanged.
+fn ability_stat<T>(
+    model: &dyn InheritanceModel,
+    slot: [Option<&T>; 2],
+    value: impl Fn(&T) -> f32,
+    range: StatRange,
+    inverse: bool,
+    balance: &Balance,
+) -> StatRange {
+    let values = match slot {
+        [Some(a), Some(b)] => [value(a), value(b)],
+        [Some(a), None] | [None, Some(a)] => [value(a); 2],
+        [None, None] => unreachable!("every slot has at least one ability"),
+    };
+    let gene = Gene::new(values, range, inverse);
+
+    match model.mixes_abilities() {
+        true => model.stat_bounds(&gene, balance),
+        false => gene.bounds(),
+    }
+}
+
+fn ability_chance<T>(slot: [Option<&T>; 2], balance: &Balance) -> f64 {
+    match slot {
+        [Some(_), Some(_)] => 1.0 - balance.inheritance.ability_loss_chance,
+        _ => balance.inheritance.ability_gain_chance,
+    }
+}
+
+fn predict_physical(
+    model: &dyn InheritanceModel,
+    slot: [Option<&PhysicalAbility>; 2],
+    balance: &Balance,
+) -> AbilityPrediction {
+    let physical = &balance.physical;
+    let first = slot.into_iter().flatten().next().unwrap();
+    let stat = |name, value: fn(&PhysicalAbility) -> f32, range, inverse| {
+        let inherited = ability_stat(model, slot, value, range, inverse, balance);
+        predict_stat(name, inherited, range, balance)
+    };
+
+    let mut stats = vec![
+        stat("Damage", |a| a.damage, physical.damage, false),
+        stat("Stamina", |a| a.stamina_cost, physical.stamina_cost, false),
+        stat("Cooldown", |a| a.global_cooldown, physical.cooldown, true),
+    ];
+    // mixed abilities only blend their range when both are ranged, otherwise it comes from the
+    // first parent
+    let both_ranged = slot.iter().flatten().all(|ability| ability.is_ranged());
+    let range_slot = match model.mixes_abilities() && !both_ranged {
+        true => [Some(first), None],
+        false => slot,
+    };
+    if range_slot
+        .iter()
+        .flatten()
+        .any(|ability| ability.is_ranged())
+    {
+        let inherited = ability_stat(
+            model,
+            range_slot,
+            |a| a.range,
+            physical.range,
+            false,
+            balance,
+        );
+        stats.push(predict_stat("Range", inherited, physical.range, balance));
+    }
+
+    AbilityPrediction {
+        name: first.name.clone(),
+        holders: slot.map(|ability| ability.is_some()),
+        chance: ability_chance(slot, balance),
+        stats,
+    }
+}
+
+fn predict_magic(
+    model: &dyn InheritanceModel,
+    slot: [Option<&MagicAbility>; 2],
+    balance: &Balance,
+) -> AbilityPrediction {
+    let magic = &balance.magic;
+    let first = slot.into_iter().flatten().next().unwrap();
+    let stat = |name, value: fn(&MagicAbility) -> f32, range, inverse| {
+        let inherited = ability_stat(model, slot, value, range, inverse, balance);
+        predict_stat(name, inherited, range, balance)
+    };
+
+    AbilityPrediction {
+        name: first.kind.name().to_string(),
+        holders: slot.map(|ability| ability.is_some()),
+        chance: ability_chance(slot, balance),
+        stats: vec![
+            stat(
+                first.kind.power_name(),
+                |a| a.power,
+                first.kind.power_range(magic),
+                false,
+            ),
+            stat("Mana", |a| a.mana_cost, magic.mana_cost, false),
+            stat("Cooldown", |a| a.cooldown, magic.cooldown, true),
+            stat("Range", |a| a.range, magic.range, false),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{balance::InheritanceRules, creature::MagicKind};
+
+    const MODES: [InheritanceMode; 4] = [
+        InheritanceMode::CoinFlip,
+        InheritanceMode::Blend,
+        InheritanceMode::BiasedFlip,
+        InheritanceMode::Dominant,
+    ];
+    const CHILDREN: u64 = 200;
+
+    fn balance(ability_gain_chance: f64, ability_loss_chance: f64) -> Balance {
+        let balance = Balance::default();
+
+        Balance {
+            inheritance: InheritanceRules {
+                ability_gain_chance,
+                ability_loss_chance,
+                ..balance.inheritance
+            },
+            ..balance
+        }
+    }
+
+    fn ability(name: &str, damage: f32) -> PhysicalAbility {
+        PhysicalAbility {
+            name: name.to_string(),
+            stamina_cost: 20.0,
+            damage,
+            global_cooldown: 2.0 - damage / 20.0,
+            range: 32.0,
+        }
+    }
+
+    fn species(archetype: Archetype, hp: f32, abilities: &[&str]) -> CreatureStats {
+        CreatureStats {
+            archetype,
+            movement_speed: 2.0 * hp,
+            hp,
+            stamina: 100.0,
+            stamina_regen: 10.0,
+            mana: 50.0,
+            mana_regen: 5.0,
+            generation: 0,
+            physical_abilities: abilities
+                .iter()
+                .map(|&name| ability(name, hp / 10.0))
+                .collect(),
+            magic_abilities: vec![MagicAbility {
+                kind: MagicKind::Heal,
+                power: hp / 5.0,
+                mana_cost: 30.0,
+                cooldown: 5.0,
+                range: 200.0,
+            }],
+            size: 1.0,
+            hues: vec![0.0; 4],
+            recessive: None,
+        }
+    }
+
+    /// Parents which differ in every stat, the second one carries a hidden melee allele.
+    fn parents() -> [CreatureStats; 2] {
+        let mut second = species(Archetype::Ranged, 200.0, &["bite"]);
+        second.recessive = Some(RecessiveAlleles {
+            archetype: Archetype::Melee,
+            hp: 60.0,
+            size: 1.2,
+            ..second.recessive_alleles()
+        });
+
+        [species(Archetype::Melee, 100.0, &["bite"]), second]
+    }
+
+    fn children(
+        mode: InheritanceMode,
+        parents: &[CreatureStats; 2],
+        balance: &Balance,
+    ) -> Vec<CreatureStats> {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        (0..CHILDREN)
+            .map(|generation| {
+                combine_stats(
+                    mode.model(),
+                    [&parents[0], &parents[1]],
+                    balance,
+                    &mut rng,
+                    generation,
+                )
+            })
+            .collect()
+    }
+
+    fn ability_names(child: &CreatureStats) -> Vec<&str> {
+        child
+            .physical_abilities
+            .iter()
+            .map(|ability| ability.name.as_str())
+            .collect()
+    }
+
+    fn assert_inside(name: &str, value: f32, range: StatRange) {
+        assert!(
+            range.min - 1e-3 <= value && value <= range.max + 1e-3,
+            "{name} {value} is outside of {range:?}"
+        );
+    }
+
+    #[test]
+    fn children_stay_inside_the_predicted_bounds() {
+        // blend noise beyond two standard deviations is left out of the prediction, and so is the
+        // ability kept whole once the shared one is lost
+        let mut balance = balance(0.5, 0.0);
+        balance.inheritance.blend_noise = 0.0;
+        let parents = parents();
+
+        for mode in MODES {
+            let prediction = predict_offspring(mode.model(), [&parents[0], &parents[1]], &balance);
+
+            for child in children(mode, &parents, &balance) {
+                let hidden = child.recessive_alleles();
+                let values = [
+                    (child.movement_speed, hidden.movement_speed),
+                    (child.hp, hidden.hp),
+                    (child.stamina, hidden.stamina),
+                    (child.stamina_regen, hidden.stamina_regen),
+                    (child.mana, hidden.mana),
+                    (child.mana_regen, hidden.mana_regen),
+                    (child.size, hidden.size),
+                ];
+                for (stat, (value, hidden_value)) in prediction.stats.iter().zip(values) {
+                    assert_inside(stat.name, value, stat.inherited);
+                    assert_inside(
+                        stat.name,
+                        hidden_value,
+                        stat.hidden.unwrap_or(stat.inherited),
+                    );
+                }
+
+                for ability in child.physical_abilities.iter() {
+                    let predicted = prediction
+                        .abilities
+                        .iter()
+                        .find(|predicted| predicted.name == ability.name)
+                        .unwrap();
+                    assert_inside("Damage", ability.damage, predicted.stats[0].inherited);
+                    assert_inside(
+                        "Cooldown",
+                        ability.global_cooldown,
+                        predicted.stats[2].inherited,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blend_stays_inside_of_the_stat_range() {
+        let mut balance = balance(0.5, 0.1);
+        balance.inheritance.blend_noise = 10.0;
+        let parents = [
+            species(Archetype::Melee, balance.stats.hp.min, &["bite"]),
+            species(Archetype::Melee, balance.stats.hp.min, &["bite"]),
+        ];
+
+        let prediction = predict_offspring(&Blend, [&parents[0], &parents[1]], &balance);
+        assert!(prediction.stats[1].inherited.min >= balance.stats.hp.min);
+        for child in children(InheritanceMode::Blend, &parents, &balance) {
+            assert!(child.hp >= balance.stats.hp.min);
+        }
+    }
+
+    #[test]
+    fn shared_abilities_survive_without_loss_chance() {
+        let balance = balance(0.5, 0.0);
+        let parents = [
+            species(Archetype::Melee, 100.0, &["bite", "claw"]),
+            species(Archetype::Melee, 200.0, &["spit", "bite"]),
+        ];
+
+        for mode in MODES {
+            for child in children(mode, &parents, &balance) {
+                assert!(ability_names(&child).contains(&"bite"));
+                assert!(!child.magic_abilities.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn single_parent_abilities_are_not_gained_without_gain_chance() {
+        let balance = balance(0.0, 0.0);
+        let parents = [
+            species(Archetype::Melee, 100.0, &["bite", "claw"]),
+            species(Archetype::Melee, 200.0, &["spit", "bite"]),
+        ];
+
+        for mode in MODES {
+            for child in children(mode, &parents, &balance) {
+                assert_eq!(ability_names(&child), ["bite"]);
+            }
+        }
+    }
+
+    #[test]
+    fn one_ability_is_kept_when_none_is_inherited() {
+        let balance = balance(0.0, 0.0);
+        let parents = [
+            species(Archetype::Melee, 100.0, &["claw"]),
+            species(Archetype::Melee, 200.0, &["spit"]),
+        ];
+
+        for mode in MODES {
+            let children = children(mode, &parents, &balance);
+            for child in children.iter() {
+                assert_eq!(child.physical_abilities.len(), 1);
+            }
+            // the kept ability is picked from both parents
+            for name in ["claw", "spit"] {
+                assert!(children.iter().any(|child| ability_names(child) == [name]));
+            }
+        }
+    }
+
+    #[test]
+    fn ability_slots_follow_the_crossover_order() {
+        let balance = balance(1.0, 0.0);
+        let parents = [
+            species(Archetype::Melee, 100.0, &["bite", "claw", "kick"]),
+            species(Archetype::Melee, 200.0, &["spit", "kick", "bite"]),
+        ];
+        let slots = ability_slots(
+            parents
+                .each_ref()
+                .map(|parent| parent.physical_abilities.as_slice()),
+            |ability| ability.name.clone(),
+        );
+        let slot_names = slots
+            .iter()
+            .map(|slot| slot.iter().flatten().next().unwrap().name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(slot_names, ["bite", "claw", "kick", "spit"]);
+        for mode in MODES {
+            for child in children(mode, &parents, &balance) {
+                assert_eq!(ability_names(&child), slot_names);
+            }
+        }
+    }
+
+    #[test]
+    fn recessive_archetype_can_skip_a_generation() {
+        let balance = balance(0.5, 0.1);
+        // both parents show melee and carry ranged
+        let carriers = parents().map(|parent| CreatureStats {
+            archetype: Archetype::Melee,
+            recessive: Some(RecessiveAlleles {
+                archetype: Archetype::Ranged,
+                ..parent.recessive_alleles()
+            }),
+            ..parent
+        });
+
+        let gene = archetype_gene([&carriers[0], &carriers[1]]);
+        assert_eq!(
+            Dominant.discrete_chances(&gene, archetype_strength(Archetype::Melee), &balance),
+            [0.75, 0.25]
+        );
+        assert!(children(InheritanceMode::Dominant, &carriers, &balance)
+            .iter()
+            .any(|child| child.archetype == Archetype::Ranged));
+    }
+}
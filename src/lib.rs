@@ -1,30 +1,76 @@
 #![allow(clippy::type_complexity)]
 
 mod audio;
+mod balance;
+mod battle;
 mod creature;
+mod deployment;
+mod genetics;
+mod lineage;
 mod loading;
 mod menu;
+mod naming;
+mod records;
+mod replay;
+mod report;
 mod rounds;
+mod save;
+mod scouting;
 mod screens;
+mod seed;
+mod settings;
+pub mod simulation;
+pub mod spatial;
 mod statistics;
+mod storage;
 mod ui;
 
 use crate::creature::CreaturePlugin;
+use crate::lineage::LineagePlugin;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
+use crate::records::RecordsPlugin;
+use crate::replay::ReplayPlugin;
+use crate::report::ReportPlugin;
 use crate::screens::new_creature_screen::NewCreatureScreenPlugin;
 use crate::ui::UIPlugin;
 
 use audio::InternalAudioPlugin;
+use balance::BalancePlugin;
+use battle::BattlePlugin;
 use bevy::app::App;
 use bevy::prelude::*;
+use deployment::DeploymentPlugin;
 use rounds::RoundsPlugin;
+use save::SavePlugin;
+use scouting::ScoutingPlugin;
+use screens::battle_hud_screen::BattleHudScreenPlugin;
+use screens::battle_report_screen::BattleReportScreenPlugin;
 use screens::battle_screen::BattleScreenPlugin;
 use screens::creature_manager_screen::CreatureManagerScreenPlugin;
+use screens::deployment_screen::DeploymentScreenPlugin;
 use screens::game_over_screen::GameOverScreenPlugin;
+use screens::lineage_screen::LineageScreenPlugin;
+use screens::offspring_preview_screen::OffspringPreviewScreenPlugin;
+use screens::placement_screen::PlacementScreenPlugin;
+use screens::records_screen::RecordsScreenPlugin;
+use screens::replay_screen::ReplayScreenPlugin;
+use screens::replays_screen::ReplaysScreenPlugin;
+use screens::roster_screen::RosterScreenPlugin;
+use screens::scouting_screen::ScoutingScreenPlugin;
+use screens::settings_screen::SettingsScreenPlugin;
 use screens::tutorial_screen::TutorialScreenPlugin;
+use seed::SeedPlugin;
+use settings::SettingsPlugin;
 use statistics::StatisticsPlugin;
 
+pub use balance::Balance;
+pub use battle::Stance;
+pub use creature::{
+    generate_creature_stats, generate_population_size, Archetype, CreatureStats, PhysicalAbility,
+    PopulationSize,
+};
+
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
 enum GameState {
     #[default]
@@ -32,9 +78,16 @@ enum GameState {
     Menu,
     NewCreature,
     Battle,
+    BattleReport,
     CreatureManager,
+    Deployment,
+    Placement,
     GameOver(GameResult),
     Tutorial,
+    Replays,
+    Replay,
+    Records,
+    Settings,
 }
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
@@ -50,20 +103,48 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>().add_plugins((
-            LoadingPlugin,
-            MenuPlugin,
-            UIPlugin,
-            NewCreatureScreenPlugin,
-            CreaturePlugin,
-            BattleScreenPlugin,
-            CreatureManagerScreenPlugin,
-            GameOverScreenPlugin,
-            RoundsPlugin,
-            InternalAudioPlugin,
-            StatisticsPlugin,
-            TutorialScreenPlugin,
-        ));
+        app.init_state::<GameState>()
+            .add_plugins((
+                LoadingPlugin,
+                MenuPlugin,
+                UIPlugin,
+                NewCreatureScreenPlugin,
+                CreaturePlugin,
+                BattlePlugin,
+                BattleScreenPlugin,
+                CreatureManagerScreenPlugin,
+                GameOverScreenPlugin,
+                RoundsPlugin,
+                InternalAudioPlugin,
+                StatisticsPlugin,
+                TutorialScreenPlugin,
+                SeedPlugin,
+                SavePlugin,
+            ))
+            .add_plugins((
+                BalancePlugin,
+                ReplayPlugin,
+                ReplayScreenPlugin,
+                RecordsPlugin,
+                RecordsScreenPlugin,
+                LineagePlugin,
+                LineageScreenPlugin,
+                OffspringPreviewScreenPlugin,
+                SettingsPlugin,
+                SettingsScreenPlugin,
+                BattleHudScreenPlugin,
+                ReportPlugin,
+                BattleReportScreenPlugin,
+                ScoutingPlugin,
+                ScoutingScreenPlugin,
+            ))
+            .add_plugins((
+                RosterScreenPlugin,
+                DeploymentPlugin,
+                DeploymentScreenPlugin,
+                PlacementScreenPlugin,
+                ReplaysScreenPlugin,
+            ));
 
         app.add_systems(Startup, setup_camera);
     }
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    creature::{body_part_variant, default_hues, Archetype, BodyPart, CreatureStats},
+    rounds::GameStartedEvent,
+    screens::new_creature_screen::PlayerCreature,
+};
+
+pub struct LineagePlugin;
+
+impl Plugin for LineagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lineage>().add_systems(
+            Update,
+            (
+                reset_lineage.run_if(on_event::<GameStartedEvent>()),
+                register_species,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Where an inherited trait of a combined species came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraitOrigin {
+    FirstParent,
+    SecondParent,
+    /// Mixed from both parents.
+    Blend,
+    /// Neither parent had the trait.
+    New,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InheritedTrait {
+    pub name: String,
+    pub origin: TraitOrigin,
+    pub mutated: bool,
+    /// Passed down from the hidden allele of the parent, which did not show the trait.
+    #[serde(default)]
+    pub recessive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesLineage {
+    /// Generations of the combined species, `None` for species which joined the legion from the
+    /// wild.
+    pub parents: Option<[u64; 2]>,
+    pub archetype: Archetype,
+    pub body_parts: Vec<usize>,
+    #[serde(default = "default_hues")]
+    pub hues: Vec<f32>,
+    /// Traits of a combined species in the order they are listed in the stats window.
+    pub traits: Vec<InheritedTrait>,
+    /// Traits with a hidden allele which differs from the shown value.
+    #[serde(default)]
+    pub carried: Vec<String>,
+}
+
+/// Ancestry of every player species of the run, keyed by [`CreatureStats::generation`]. Species
+/// stay in here after they die out, so the whole family tree can be traced.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct Lineage {
+    species: BTreeMap<u64, SpeciesLineage>,
+}
+
+impl Lineage {
+    pub fn get(&self, generation: u64) -> Option<&SpeciesLineage> {
+        self.species.get(&generation)
+    }
+
+    /// Records a species combined from `parents`. `inherited` are the stats of the child before
+    /// the mutation and `child` after it.
+    pub fn record_combination(
+        &mut self,
+        parents: [&CreatureStats; 2],
+        inherited: &CreatureStats,
+        child: &CreatureStats,
+        body_parts: Vec<usize>,
+    ) {
+        let parent_traits = parents.map(stat_traits);
+        let parent_hidden_traits = parents.map(hidden_traits);
+        let traits = stat_traits(inherited)
+            .into_iter()
+            .zip(stat_traits(child))
+            .map(|((name, values), (_, mutated_values))| {
+                let from = |traits: &[(String, Vec<f32>)]| {
+                    traits.iter().any(|(parent_name, parent_values)| {
+                        *parent_name == name && *parent_values == values
+                    })
+                };
+                let known = |traits: &[(String, Vec<f32>)]| {
+                    traits.iter().any(|(parent_name, _)| *parent_name == name)
+                };
+
+                let (origin, recessive) = if from(&parent_traits[0]) {
+                    (TraitOrigin::FirstParent, false)
+                } else if from(&parent_traits[1]) {
+                    (TraitOrigin::SecondParent, false)
+                } else if from(&parent_hidden_traits[0]) {
+                    (TraitOrigin::FirstParent, true)
+                } else if from(&parent_hidden_traits[1]) {
+                    (TraitOrigin::SecondParent, true)
+                } else if known(&parent_traits[0]) || known(&parent_traits[1]) {
+                    (TraitOrigin::Blend, false)
+                } else {
+                    (TraitOrigin::New, false)
+                };
+
+                InheritedTrait {
+                    name,
+                    origin,
+                    mutated: values != mutated_values,
+                    recessive,
+                }
+            })
+            .collect();
+        let carried = hidden_traits(inherited)
+            .into_iter()
+            .zip(stat_traits(inherited))
+            .filter(|((_, hidden_values), (_, values))| hidden_values != values)
+            .map(|((name, _), _)| name)
+            .collect();
+
+        self.species.insert(
+            child.generation,
+            SpeciesLineage {
+                parents: Some(parents.map(|parent| parent.generation)),
+                archetype: child.archetype,
+                body_parts,
+                hues: child.hues.clone(),
+                traits,
+                carried,
+            },
+        );
+    }
+}
+
+fn archetype_value(archetype: Archetype) -> f32 {
+    match archetype {
+        Archetype::Melee => 0.0,
+        Archetype::Ranged => 1.0,
+    }
+}
+
+/// Named groups of the stat values which are passed down together.
+fn stat_traits(stats: &CreatureStats) -> Vec<(String, Vec<f32>)> {
+    let mut traits = vec![
+        (
+            "Archetype".to_string(),
+            vec![archetype_value(stats.archetype)],
+        ),
+        ("Movement Speed".to_string(), vec![stats.movement_speed]),
+        ("HP".to_string(), vec![stats.hp]),
+        ("Stamina".to_string(), vec![stats.stamina]),
+        ("Stamina Regen".to_string(), vec![stats.stamina_regen]),
+        ("Mana".to_string(), vec![stats.mana]),
+        ("Mana Regen".to_string(), vec![stats.mana_regen]),
+        ("Size".to_string(), vec![stats.size]),
+        ("Colors".to_string(), stats.hues.clone()),
+    ];
+
+    traits.extend(stats.physical_abilities.iter().map(|ability| {
+        (
+            ability.name.clone(),
+            vec![
+                ability.damage,
+                ability.stamina_cost,
+                ability.global_cooldown,
+                ability.range,
+            ],
+        )
+    }));
+    traits.extend(stats.magic_abilities.iter().map(|ability| {
+        (
+            ability.kind.name().to_string(),
+            vec![
+                ability.power,
+                ability.mana_cost,
+                ability.cooldown,
+                ability.range,
+            ],
+        )
+    }));
+
+    traits
+}
+
+/// Hidden alleles named like [`stat_traits`], in the same order.
+fn hidden_traits(stats: &CreatureStats) -> Vec<(String, Vec<f32>)> {
+    let hidden = stats.recessive_alleles();
+
+    vec![
+        (
+            "Archetype".to_string(),
+            vec![archetype_value(hidden.archetype)],
+        ),
+        ("Movement Speed".to_string(), vec![hidden.movement_speed]),
+        ("HP".to_string(), vec![hidden.hp]),
+        ("Stamina".to_string(), vec![hidden.stamina]),
+        ("Stamina Regen".to_string(), vec![hidden.stamina_regen]),
+        ("Mana".to_string(), vec![hidden.mana]),
+        ("Mana Regen".to_string(), vec![hidden.mana_regen]),
+        ("Size".to_string(), vec![hidden.size]),
+    ]
+}
+
+fn reset_lineage(mut lineage: ResMut<Lineage>) {
+    *lineage = Lineage::default();
+}
+
+/// Adds the species which joined the legion without being combined.
+fn register_species(
+    mut lineage: ResMut<Lineage>,
+    creature_query: Query<(&CreatureStats, &Children), Added<PlayerCreature>>,
+    body_part_query: Query<&Sprite, With<BodyPart>>,
+) {
+    for (stats, children) in creature_query.iter() {
+        lineage
+            .species
+            .entry(stats.generation)
+            .or_insert_with(|| SpeciesLineage {
+                parents: None,
+                archetype: stats.archetype,
+                body_parts: children
+                    .iter()
+                    .filter_map(|&child| body_part_query.get(child).ok())
+                    .map(body_part_variant)
+                    .collect(),
+                hues: stats.hues.clone(),
+                traits: Vec::new(),
+                carried: Vec::new(),
+            });
+    }
+}
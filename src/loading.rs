@@ -1,4 +1,4 @@
-use crate::GameState;
+use crate::{balance::Balance, GameState};
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::AudioSource;
@@ -10,12 +10,20 @@ impl Plugin for LoadingPlugin {
         app.add_loading_state(
             LoadingState::new(GameState::Loading)
                 .continue_to_state(GameState::Menu)
+                .load_collection::<BalanceAssets>()
                 .load_collection::<AudioAssets>()
                 .load_collection::<TextureAssets>(),
         );
     }
 }
 
+/// Keeps the balance asset loaded, so changes to it are hot reloaded.
+#[derive(AssetCollection, Resource)]
+pub struct BalanceAssets {
+    #[asset(path = "game.balance.ron")]
+    pub balance: Handle<Balance>,
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct AudioAssets {
     #[asset(path = "audio/base_soundtrack.ogg")]
@@ -33,12 +41,13 @@ pub struct AudioAssets {
     #[asset(path = "audio/die.ogg")]
     pub die: Handle<AudioSource>,
     #[asset(path = "audio/spell.ogg")]
-    pub _spell: Handle<AudioSource>,
+    pub spell: Handle<AudioSource>,
     #[asset(path = "audio/victory.ogg")]
     pub victory: Handle<AudioSource>,
 }
 
 #[derive(AssetCollection, Resource)]
+#[cfg_attr(test, derive(Default))]
 pub struct TextureAssets {
     #[asset(path = "textures/bevy.png")]
     pub bevy: Handle<Image>,
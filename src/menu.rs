@@ -1,17 +1,34 @@
+use crate::genetics::InheritanceMode;
 use crate::loading::TextureAssets;
+use crate::replay::ReplayIndex;
 use crate::rounds::GameSettings;
+use crate::save::{save_exists, ContinueGameEvent};
+use crate::seed::RunSeed;
 use crate::{GameState, WINDOW_SIZE};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
 use bevy::prelude::*;
 
 const BACKGROUND_Z: f32 = -20.0;
 const TITLE_Z: f32 = 0.0;
+// Longest decimal number which always fits into u64.
+const MAX_SEED_DIGITS: usize = 19;
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Menu), setup_menu)
-            .add_systems(Update, click_play_button.run_if(in_state(GameState::Menu)))
+            .add_systems(
+                Update,
+                (
+                    update_button_colors,
+                    handle_menu_actions,
+                    handle_seed_input,
+                    handle_inheritance_input,
+                )
+                    .run_if(in_state(GameState::Menu)),
+            )
             .add_systems(OnExit(GameState::Menu), cleanup_menu);
     }
 }
@@ -34,10 +51,31 @@ impl Default for ButtonColors {
 #[derive(Component)]
 struct Menu;
 
+/// What a menu button does when it is pressed.
 #[derive(Component)]
-struct InfinityModeButton;
+enum MenuAction {
+    /// Starts a new run with the seed and the genetics chosen in the menu.
+    NewRun {
+        infinity_mode: bool,
+    },
+    /// Restores the saved run.
+    Continue,
+    /// Opens a screen which leads back to the menu.
+    Open(GameState),
+    OpenLink(&'static str),
+}
+
+#[derive(Component, Default)]
+struct SeedInput(String);
+
+#[derive(Component, Default)]
+struct InheritanceInput(InheritanceMode);
 
-fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
+fn setup_menu(
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    replay_index: Res<ReplayIndex>,
+) {
     // background
     commands.spawn((
         SpriteBundle {
@@ -81,6 +119,38 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
         },
         Menu,
     ));
+    if save_exists() {
+        entity.with_children(|children| {
+            let button_colors = ButtonColors::default();
+            children
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(240.0),
+                            height: Val::Px(80.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(10.0)),
+                            ..Default::default()
+                        },
+                        background_color: button_colors.normal.into(),
+                        ..Default::default()
+                    },
+                    button_colors,
+                    MenuAction::Continue,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Continue",
+                        TextStyle {
+                            font_size: 30.0,
+                            color: Color::linear_rgb(0.9, 0.9, 0.9),
+                            ..default()
+                        },
+                    ));
+                });
+        });
+    }
     entity.with_children(|children| {
         let button_colors = ButtonColors::default();
         children
@@ -97,7 +167,9 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                     ..Default::default()
                 },
                 button_colors,
-                ChangeState(GameState::Tutorial),
+                MenuAction::NewRun {
+                    infinity_mode: false,
+                },
             ))
             .with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
@@ -128,8 +200,9 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                     ..Default::default()
                 },
                 button_colors,
-                ChangeState(GameState::Tutorial),
-                InfinityModeButton,
+                MenuAction::NewRun {
+                    infinity_mode: true,
+                },
             ))
             .with_children(|parent| {
                 parent.spawn(TextBundle::from_section(
@@ -142,6 +215,192 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                 ));
             });
     });
+    // side by side, so that the menu still fits below the title
+    entity.with_children(|children| {
+        children
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                if !replay_index.is_empty() {
+                    let button_colors = ButtonColors::default();
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(160.0),
+                                height: Val::Px(60.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            background_color: button_colors.normal.into(),
+                            ..Default::default()
+                        },
+                        button_colors,
+                        MenuAction::Open(GameState::Replays),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            "Replays",
+                            TextStyle {
+                                font_size: 26.0,
+                                color: Color::linear_rgb(0.9, 0.9, 0.9),
+                                ..default()
+                            },
+                        ));
+                    });
+                }
+
+                let button_colors = ButtonColors::default();
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(160.0),
+                            height: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        background_color: button_colors.normal.into(),
+                        ..Default::default()
+                    },
+                    button_colors,
+                    MenuAction::Open(GameState::Records),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Records",
+                        TextStyle {
+                            font_size: 26.0,
+                            color: Color::linear_rgb(0.9, 0.9, 0.9),
+                            ..default()
+                        },
+                    ));
+                });
+
+                let button_colors = ButtonColors::default();
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(160.0),
+                            height: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        background_color: button_colors.normal.into(),
+                        ..Default::default()
+                    },
+                    button_colors,
+                    MenuAction::Open(GameState::Settings),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Settings",
+                        TextStyle {
+                            font_size: 26.0,
+                            color: Color::linear_rgb(0.9, 0.9, 0.9),
+                            ..default()
+                        },
+                    ));
+                });
+            });
+    });
+    entity.with_children(|children| {
+        children
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(320.0),
+                        height: Val::Px(50.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::BLACK.with_alpha(0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_sections([
+                            TextSection::new(
+                                "seed: ",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    color: Color::linear_rgb(0.9, 0.9, 0.9),
+                                    ..default()
+                                },
+                            ),
+                            TextSection::new(
+                                "random",
+                                TextStyle {
+                                    font_size: 24.0,
+                                    color: Color::linear_rgb(0.6, 0.6, 0.6),
+                                    ..default()
+                                },
+                            ),
+                        ]),
+                        SeedInput::default(),
+                    ));
+                });
+
+                // inheritance model of new runs, cycles through the models when clicked
+                let button_colors = ButtonColors {
+                    normal: Color::BLACK.with_alpha(0.5),
+                    ..default()
+                };
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(260.0),
+                            height: Val::Px(50.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: button_colors.normal.into(),
+                        ..default()
+                    },
+                    button_colors,
+                    InheritanceInput::default(),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_sections([
+                        TextSection::new(
+                            "genetics: ",
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::linear_rgb(0.9, 0.9, 0.9),
+                                ..default()
+                            },
+                        ),
+                        TextSection::new(
+                            InheritanceMode::default().name(),
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::linear_rgb(0.6, 0.6, 0.6),
+                                ..default()
+                            },
+                        ),
+                    ]));
+                });
+            });
+    });
     commands
         .spawn((
             NodeBundle {
@@ -177,7 +436,7 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                         normal: Color::NONE,
                         ..default()
                     },
-                    OpenLink("https://bevyengine.org"),
+                    MenuAction::OpenLink("https://bevyengine.org"),
                 ))
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
@@ -215,7 +474,7 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
                         normal: Color::NONE,
                         hovered: Color::linear_rgb(0.25, 0.25, 0.25),
                     },
-                    OpenLink("https://github.com/pedryx/tiny-legion"),
+                    MenuAction::OpenLink("https://github.com/pedryx/tiny-legion"),
                 ))
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
@@ -238,41 +497,15 @@ fn setup_menu(mut commands: Commands, textures: Res<TextureAssets>) {
         });
 }
 
-#[derive(Component)]
-struct ChangeState(GameState);
-
-#[derive(Component)]
-struct OpenLink(&'static str);
-
-fn click_play_button(
-    mut next_state: ResMut<NextState<GameState>>,
+fn update_button_colors(
     mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &ButtonColors,
-            Option<&ChangeState>,
-            Option<&OpenLink>,
-            Option<&InfinityModeButton>,
-        ),
+        (&Interaction, &mut BackgroundColor, &ButtonColors),
         (Changed<Interaction>, With<Button>),
     >,
-    mut game_settings: ResMut<GameSettings>,
 ) {
-    for (interaction, mut color, button_colors, change_state, open_link, infinity_mode_button) in
-        &mut interaction_query
-    {
+    for (interaction, mut color, button_colors) in &mut interaction_query {
         match *interaction {
-            Interaction::Pressed => {
-                if let Some(state) = change_state {
-                    game_settings.infinity_mode_on = infinity_mode_button.is_some();
-                    next_state.set(state.0.clone());
-                } else if let Some(link) = open_link {
-                    if let Err(error) = webbrowser::open(link.0) {
-                        warn!("Failed to open link {error:?}");
-                    }
-                }
-            }
+            Interaction::Pressed => {}
             Interaction::Hovered => {
                 *color = button_colors.hovered.into();
             }
@@ -283,6 +516,97 @@ fn click_play_button(
     }
 }
 
+fn handle_menu_actions(
+    action_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<Button>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_settings: ResMut<GameSettings>,
+    mut run_seed: ResMut<RunSeed>,
+    seed_input_query: Query<&SeedInput>,
+    inheritance_input_query: Query<&InheritanceInput>,
+    mut ew_continue_game: EventWriter<ContinueGameEvent>,
+) {
+    for (&interaction, action) in action_query.iter() {
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            &MenuAction::NewRun { infinity_mode } => {
+                game_settings.infinity_mode_on = infinity_mode;
+                *run_seed = match seed_input_query.single().0.parse() {
+                    Ok(seed) => RunSeed(seed),
+                    Err(_) => RunSeed::default(),
+                };
+                game_settings.inheritance = inheritance_input_query.single().0;
+                next_state.set(GameState::Tutorial);
+            }
+            MenuAction::Continue => {
+                ew_continue_game.send(ContinueGameEvent);
+                next_state.set(GameState::CreatureManager);
+            }
+            MenuAction::Open(state) => next_state.set(state.clone()),
+            MenuAction::OpenLink(link) => {
+                if let Err(error) = webbrowser::open(link) {
+                    warn!("Failed to open link {error:?}");
+                }
+            }
+        }
+    }
+}
+
+fn handle_seed_input(
+    mut er_keyboard_input: EventReader<KeyboardInput>,
+    mut query: Query<(&mut Text, &mut SeedInput)>,
+) {
+    let (mut text, mut seed_input) = query.single_mut();
+
+    for event in er_keyboard_input.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(character) => {
+                for digit in character.chars().filter(char::is_ascii_digit) {
+                    if seed_input.0.len() < MAX_SEED_DIGITS {
+                        seed_input.0.push(digit);
+                    }
+                }
+            }
+            Key::Backspace => {
+                seed_input.0.pop();
+            }
+            _ => {}
+        }
+    }
+
+    if seed_input.is_changed() {
+        text.sections[1].value = if seed_input.0.is_empty() {
+            "random".to_string()
+        } else {
+            seed_input.0.clone()
+        };
+    }
+}
+
+fn handle_inheritance_input(
+    mut input_query: Query<(&Interaction, &mut InheritanceInput, &Children), Changed<Interaction>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, mut inheritance_input, children) in input_query.iter_mut() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        inheritance_input.0 = inheritance_input.0.next();
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[1].value = inheritance_input.0.name().to_string();
+            }
+        }
+    }
+}
+
 fn cleanup_menu(mut commands: Commands, menu: Query<Entity, With<Menu>>) {
     for entity in menu.iter() {
         commands.entity(entity).despawn_recursive();
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+/// Longest name a species can carry, generated or typed.
+pub const MAX_NAME_LENGTH: usize = 16;
+
+/// Syllables of the head, body, arms and legs in the order they are spoken, indexed by the body
+/// part variant. Later variants belong to higher tiers and sound harsher.
+const SYLLABLES: [(usize, [&str; 6]); 4] = [
+    (1, ["Mi", "Pa", "Bo", "Gri", "Kru", "Vex"]),
+    (0, ["m", "l", "b", "nd", "rr", "th"]),
+    (2, ["i", "e", "a", "o", "u", "au"]),
+    (3, ["n", "s", "k", "x", "ra", "dor"]),
+];
+
+/// Name of a species, shown next to its generation.
+#[derive(Component, Clone)]
+pub struct SpeciesName(pub String);
+
+/// Name which sounds like the body parts, `body_parts` contains the body part variant of each
+/// slot.
+pub fn generate_name(body_parts: &[usize]) -> String {
+    SYLLABLES
+        .iter()
+        .map(|(slot, syllables)| {
+            let variant = body_parts.get(*slot).copied().unwrap_or_default();
+            syllables[variant.min(syllables.len() - 1)]
+        })
+        .collect()
+}
+
+/// Front half of the first name joined with the back half of the second one.
+pub fn combine_names(first: &str, second: &str) -> String {
+    let front = first.chars().take(first.chars().count().div_ceil(2));
+    let back = second
+        .chars()
+        .skip(second.chars().count() / 2)
+        .flat_map(char::to_lowercase);
+
+    front.chain(back).take(MAX_NAME_LENGTH).collect()
+}
@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    creature::{Archetype, CreatureStats, PopulationSize},
+    naming::SpeciesName,
+    rounds::GameSettings,
+    screens::new_creature_screen::PlayerCreature,
+    seed::RunSeed,
+    statistics::GameStatistics,
+    storage, GameResult, GameState,
+};
+
+/// Increase whenever the format of [`RunHistory`] changes, older histories are then ignored.
+const HISTORY_VERSION: u32 = 1;
+const HISTORY_KEY: &str = "history.ron";
+
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(read_history().unwrap_or_default())
+            .init_resource::<FieldedLegion>()
+            .add_systems(OnEnter(GameState::Battle), remember_legion)
+            .add_systems(
+                OnEnter(GameState::GameOver(GameResult::Victory)),
+                record_run,
+            )
+            .add_systems(OnEnter(GameState::GameOver(GameResult::Defeat)), record_run);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunMode {
+    Normal,
+    Infinity,
+}
+
+impl RunMode {
+    pub const ALL: [RunMode; 2] = [RunMode::Normal, RunMode::Infinity];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunMode::Normal => "Normal Mode",
+            RunMode::Infinity => "Infinity Mode",
+        }
+    }
+}
+
+/// Completed run, the legion is the one which was fielded in the last battle.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub mode: RunMode,
+    pub victory: bool,
+    pub seed: u64,
+    pub survived_rounds: usize,
+    pub ally_kills: usize,
+    pub ally_deaths: usize,
+    pub combination_count: usize,
+    pub elapsed_seconds: f32,
+    pub legion: Vec<RecordedSpecies>,
+}
+
+impl RunRecord {
+    pub fn play_time(&self) -> String {
+        let seconds = self.elapsed_seconds as usize % 60;
+        let minutes = self.elapsed_seconds as usize / 60;
+
+        format!("{}:{:02}", minutes, seconds)
+    }
+
+    pub fn legion_size(&self) -> u32 {
+        self.legion.iter().map(|species| species.population).sum()
+    }
+
+    /// Orders the better run first, runs which survived more rounds are better and ties are broken
+    /// by the faster run.
+    fn rank(&self, other: &RunRecord) -> Ordering {
+        other
+            .survived_rounds
+            .cmp(&self.survived_rounds)
+            .then(self.elapsed_seconds.total_cmp(&other.elapsed_seconds))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecordedSpecies {
+    /// Empty for runs recorded before species had names.
+    #[serde(default)]
+    pub name: String,
+    pub generation: u64,
+    pub archetype: Archetype,
+    pub population: u32,
+}
+
+/// Every completed run in the order they were played.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct RunHistory {
+    version: u32,
+    runs: Vec<RunRecord>,
+}
+
+impl Default for RunHistory {
+    fn default() -> Self {
+        Self {
+            version: HISTORY_VERSION,
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl RunHistory {
+    /// Runs of the mode from the best to the worst.
+    pub fn best_runs(&self, mode: RunMode) -> Vec<&RunRecord> {
+        let mut runs = self
+            .runs
+            .iter()
+            .filter(|run| run.mode == mode)
+            .collect::<Vec<_>>();
+        runs.sort_by(|a, b| a.rank(b));
+
+        runs
+    }
+
+    pub fn last_run(&self) -> Option<&RunRecord> {
+        self.runs.last()
+    }
+
+    /// Whether the most recent run beats every earlier run of its mode.
+    pub fn last_run_is_record(&self) -> bool {
+        let Some((last, earlier)) = self.runs.split_last() else {
+            return false;
+        };
+
+        earlier
+            .iter()
+            .filter(|run| run.mode == last.mode)
+            .all(|run| last.rank(run) == Ordering::Less)
+    }
+}
+
+#[derive(Deserialize)]
+struct HistoryHeader {
+    version: u32,
+}
+
+/// Player species at the start of the current battle, the legion is usually gone once the run is
+/// lost.
+#[derive(Resource, Default)]
+pub struct FieldedLegion(Vec<RecordedSpecies>);
+
+fn read_history() -> Option<RunHistory> {
+    let data = storage::read(HISTORY_KEY)?;
+
+    let version = ron::from_str::<HistoryHeader>(&data).map(|header| header.version);
+    if version != Ok(HISTORY_VERSION) {
+        warn!("Ignoring run history with version {version:?}, expected version {HISTORY_VERSION}");
+        return None;
+    }
+
+    match ron::from_str::<RunHistory>(&data) {
+        Ok(history) => Some(history),
+        Err(error) => {
+            warn!("Failed to deserialize run history {error:?}");
+            None
+        }
+    }
+}
+
+fn remember_legion(
+    mut fielded_legion: ResMut<FieldedLegion>,
+    creature_query: Query<(&CreatureStats, &PopulationSize, &SpeciesName), With<PlayerCreature>>,
+) {
+    fielded_legion.0 = creature_query
+        .iter()
+        .filter(|(_, &PopulationSize(size), _)| size > 0)
+        .map(|(stats, &PopulationSize(size), name)| RecordedSpecies {
+            name: name.0.clone(),
+            generation: stats.generation,
+            archetype: stats.archetype,
+            population: size,
+        })
+        .collect();
+}
+
+pub fn record_run(
+    mut history: ResMut<RunHistory>,
+    fielded_legion: Res<FieldedLegion>,
+    game_state: Res<State<GameState>>,
+    game_statistics: Res<GameStatistics>,
+    game_settings: Res<GameSettings>,
+    run_seed: Res<RunSeed>,
+) {
+    history.runs.push(RunRecord {
+        mode: match game_settings.infinity_mode_on {
+            true => RunMode::Infinity,
+            false => RunMode::Normal,
+        },
+        victory: *game_state.get() == GameState::GameOver(GameResult::Victory),
+        seed: run_seed.0,
+        survived_rounds: game_statistics.survived_rounds,
+        ally_kills: game_statistics.ally_kills,
+        ally_deaths: game_statistics.ally_deaths,
+        combination_count: game_statistics.combination_count,
+        elapsed_seconds: game_statistics.elapsed_seconds,
+        legion: fielded_legion.0.clone(),
+    });
+
+    match ron::to_string(&*history) {
+        Ok(data) => storage::write(HISTORY_KEY, &data),
+        Err(error) => warn!("Failed to serialize run history {error:?}"),
+    }
+}
@@ -0,0 +1,445 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battle::{
+        AbilityUsedEvent, BattleCreature, BattleSet, BattleStep, BattleTime, BehaviorTreeContext,
+        CreatureDieEvent, DamageTakenEvent, Enemy, UsedAbility,
+    },
+    creature::{body_part_variant, BodyPart, CreatureStats},
+    rounds::Round,
+    seed::RunSeed,
+    storage, GameState,
+};
+
+/// Increase whenever the format of [`Replay`] changes, older replays are then ignored.
+const REPLAY_VERSION: u32 = 3;
+/// Increase whenever the format of [`ReplayIndex`] changes, older indexes are then ignored.
+const INDEX_VERSION: u32 = 1;
+const INDEX_KEY: &str = "replays.ron";
+/// The oldest replays are deleted beyond this, the local storage of the web build only holds a few
+/// megabytes.
+const MAX_REPLAYS: usize = 50;
+/// Battle seconds between two recorded frames, positions in between are interpolated.
+const FRAME_INTERVAL: f32 = 0.1;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(read_index().unwrap_or_default())
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<SelectedReplay>()
+            .add_systems(OnEnter(GameState::Battle), start_recording)
+            .add_systems(BattleStep, record_battle_step.after(BattleSet::Death))
+            .add_systems(OnExit(GameState::Battle), save_replay);
+    }
+}
+
+/// Compact log of a battle, positions are rounded to whole pixels and creatures are referred to
+/// by their index in the order they were spawned.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub round: u32,
+    pub species: Vec<ReplaySpecies>,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.time)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReplaySpecies {
+    pub stats: CreatureStats,
+    pub body_parts: Vec<usize>,
+    pub is_enemy: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub time: f32,
+    /// Positions of the living creatures sorted by creature.
+    pub positions: Vec<(u32, i16, i16)>,
+    /// Events which happened since the previous frame.
+    pub events: Vec<ReplayEvent>,
+}
+
+impl ReplayFrame {
+    pub fn position(&self, creature: u32) -> Option<Vec2> {
+        self.positions
+            .binary_search_by_key(&creature, |&(id, ..)| id)
+            .ok()
+            .map(|i| Vec2::new(self.positions[i].1 as f32, self.positions[i].2 as f32))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum ReplayEvent {
+    Spawn {
+        creature: u32,
+        species: u16,
+        pos: (i16, i16),
+    },
+    Target {
+        creature: u32,
+        target: u32,
+    },
+    Ability {
+        creature: u32,
+        ability: UsedAbility,
+    },
+    Damage {
+        target: u32,
+        damage: f32,
+    },
+    Die {
+        creature: u32,
+        pos: (i16, i16),
+    },
+}
+
+#[derive(Deserialize)]
+struct ReplayHeader {
+    version: u32,
+}
+
+/// Battle of a run, every recorded battle is stored under its own key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBattle {
+    pub seed: u64,
+    pub round: u32,
+}
+
+impl RecordedBattle {
+    fn key(&self) -> String {
+        format!("replays/{}-{}.ron", self.seed, self.round)
+    }
+}
+
+/// Recorded battles in the order they were fought.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct ReplayIndex {
+    version: u32,
+    battles: Vec<RecordedBattle>,
+}
+
+impl Default for ReplayIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            battles: Vec::new(),
+        }
+    }
+}
+
+impl ReplayIndex {
+    pub fn is_empty(&self) -> bool {
+        self.battles.is_empty()
+    }
+
+    /// Rounds of the recorded battles grouped by the seed of their run, the most recently played
+    /// run first.
+    pub fn runs(&self) -> Vec<(u64, Vec<u32>)> {
+        let mut runs: Vec<(u64, Vec<u32>)> = Vec::new();
+        for battle in self.battles.iter().rev() {
+            match runs.iter_mut().find(|(seed, _)| *seed == battle.seed) {
+                Some((_, rounds)) => rounds.push(battle.round),
+                None => runs.push((battle.seed, vec![battle.round])),
+            }
+        }
+        for (_, rounds) in runs.iter_mut() {
+            rounds.sort_unstable();
+        }
+
+        runs
+    }
+
+    /// Adds the battle as the most recent one, returns the battles which no longer fit.
+    fn record(&mut self, battle: RecordedBattle) -> Vec<RecordedBattle> {
+        // a run with the same seed replaces the replays of its rounds
+        self.battles.retain(|&recorded| recorded != battle);
+        self.battles.push(battle);
+
+        let excess = self.battles.len().saturating_sub(MAX_REPLAYS);
+        self.battles.drain(..excess).collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct IndexHeader {
+    version: u32,
+}
+
+/// Battle picked in the list of replays.
+#[derive(Resource, Default)]
+pub struct SelectedReplay(pub Option<RecordedBattle>);
+
+#[derive(Resource, Default)]
+struct ReplayRecorder {
+    replay: Replay,
+    elapsed: f32,
+    next_frame: f32,
+    events: Vec<ReplayEvent>,
+    creatures: HashMap<Entity, u32>,
+    species: HashMap<Entity, u16>,
+    targets: HashMap<Entity, Entity>,
+}
+
+impl ReplayRecorder {
+    fn push_frame(&mut self, positions: Vec<(u32, i16, i16)>) {
+        self.replay.frames.push(ReplayFrame {
+            time: self.elapsed,
+            positions,
+            events: std::mem::take(&mut self.events),
+        });
+    }
+}
+
+fn read_index() -> Option<ReplayIndex> {
+    let data = storage::read(INDEX_KEY)?;
+
+    let version = ron::from_str::<IndexHeader>(&data).map(|header| header.version);
+    if version != Ok(INDEX_VERSION) {
+        warn!("Ignoring replay index with version {version:?}, expected version {INDEX_VERSION}");
+        return None;
+    }
+
+    match ron::from_str::<ReplayIndex>(&data) {
+        Ok(index) => Some(index),
+        Err(error) => {
+            warn!("Failed to deserialize replay index {error:?}");
+            None
+        }
+    }
+}
+
+pub fn read_replay(battle: RecordedBattle) -> Option<Replay> {
+    let data = storage::read(&battle.key())?;
+
+    let version = ron::from_str::<ReplayHeader>(&data).map(|header| header.version);
+    if version != Ok(REPLAY_VERSION) {
+        warn!("Ignoring replay with version {version:?}, expected version {REPLAY_VERSION}");
+        return None;
+    }
+
+    match ron::from_str::<Replay>(&data) {
+        Ok(replay) => Some(replay),
+        Err(error) => {
+            warn!("Failed to deserialize replay {error:?}");
+            None
+        }
+    }
+}
+
+fn pixel_position(pos: Vec2) -> (i16, i16) {
+    (pos.x.round() as i16, pos.y.round() as i16)
+}
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    round: Res<Round>,
+    run_seed: Res<RunSeed>,
+) {
+    *recorder = ReplayRecorder {
+        replay: Replay {
+            version: REPLAY_VERSION,
+            seed: run_seed.0,
+            round: round.0,
+            ..default()
+        },
+        ..default()
+    };
+}
+
+fn record_battle_step(
+    mut recorder: ResMut<ReplayRecorder>,
+    spawned_query: Query<(Entity, &BattleCreature, &Transform, Has<Enemy>), Added<BattleCreature>>,
+    creature_query: Query<(Entity, &BehaviorTreeContext, &Transform), With<BattleCreature>>,
+    template_query: Query<(&CreatureStats, &Children)>,
+    body_part_query: Query<&Sprite, With<BodyPart>>,
+    (mut er_damage_taken, mut er_creature_die, mut er_ability_used): (
+        EventReader<DamageTakenEvent>,
+        EventReader<CreatureDieEvent>,
+        EventReader<AbilityUsedEvent>,
+    ),
+    time: Res<BattleTime>,
+) {
+    let recorder = &mut *recorder;
+
+    for (entity, creature, transform, is_enemy) in spawned_query.iter() {
+        let template = creature.template();
+        let species = match recorder.species.get(&template) {
+            Some(&species) => species,
+            None => {
+                let Ok((stats, children)) = template_query.get(template) else {
+                    continue;
+                };
+                let species = recorder.replay.species.len() as u16;
+
+                recorder.replay.species.push(ReplaySpecies {
+                    stats: stats.clone(),
+                    body_parts: children
+                        .iter()
+                        .filter_map(|&child| body_part_query.get(child).ok())
+                        .map(body_part_variant)
+                        .collect(),
+                    is_enemy,
+                });
+                recorder.species.insert(template, species);
+
+                species
+            }
+        };
+
+        let creature = recorder.creatures.len() as u32;
+        recorder.creatures.insert(entity, creature);
+        recorder.events.push(ReplayEvent::Spawn {
+            creature,
+            species,
+            pos: pixel_position(transform.translation.xy()),
+        });
+    }
+
+    for (entity, context, _) in creature_query.iter() {
+        let Some(target) = context.target() else {
+            continue;
+        };
+        if recorder.targets.insert(entity, target) == Some(target) {
+            continue;
+        }
+
+        if let (Some(&creature), Some(&target)) = (
+            recorder.creatures.get(&entity),
+            recorder.creatures.get(&target),
+        ) {
+            recorder
+                .events
+                .push(ReplayEvent::Target { creature, target });
+        }
+    }
+
+    for event in er_ability_used.read() {
+        if let Some(&creature) = recorder.creatures.get(&event.creature) {
+            recorder.events.push(ReplayEvent::Ability {
+                creature,
+                ability: event.ability,
+            });
+        }
+    }
+
+    for event in er_damage_taken.read() {
+        if let Some(&target) = recorder.creatures.get(&event.target) {
+            recorder.events.push(ReplayEvent::Damage {
+                target,
+                damage: event.damage,
+            });
+        }
+    }
+
+    for event in er_creature_die.read() {
+        if let Some(&creature) = recorder.creatures.get(&event.entity) {
+            recorder.targets.remove(&event.entity);
+            recorder.events.push(ReplayEvent::Die {
+                creature,
+                pos: pixel_position(event.pos),
+            });
+        }
+    }
+
+    if recorder.elapsed >= recorder.next_frame {
+        let mut positions = creature_query
+            .iter()
+            .filter_map(|(entity, _, transform)| {
+                let (x, y) = pixel_position(transform.translation.xy());
+                recorder
+                    .creatures
+                    .get(&entity)
+                    .map(|&creature| (creature, x, y))
+            })
+            .collect::<Vec<_>>();
+        positions.sort_unstable_by_key(|&(creature, ..)| creature);
+
+        recorder.push_frame(positions);
+        recorder.next_frame += FRAME_INTERVAL;
+    }
+
+    recorder.elapsed += time.delta_seconds();
+}
+
+fn save_replay(mut recorder: ResMut<ReplayRecorder>, mut index: ResMut<ReplayIndex>) {
+    if recorder.replay.frames.is_empty() {
+        return;
+    }
+
+    // keeps the events of the last steps, without the creatures which died in them
+    let died = recorder
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            ReplayEvent::Die { creature, .. } => Some(*creature),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let positions = recorder.replay.frames.last().unwrap().positions.clone();
+    recorder.push_frame(
+        positions
+            .into_iter()
+            .filter(|(creature, ..)| !died.contains(creature))
+            .collect(),
+    );
+
+    let battle = RecordedBattle {
+        seed: recorder.replay.seed,
+        round: recorder.replay.round,
+    };
+    match ron::to_string(&recorder.replay) {
+        Ok(data) => storage::write(&battle.key(), &data),
+        Err(error) => {
+            warn!("Failed to serialize replay {error:?}");
+            return;
+        }
+    }
+
+    for removed in index.record(battle) {
+        storage::delete(&removed.key());
+    }
+    match ron::to_string(&*index) {
+        Ok(data) => storage::write(INDEX_KEY, &data),
+        Err(error) => warn!("Failed to serialize replay index {error:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battle(seed: u64, round: u32) -> RecordedBattle {
+        RecordedBattle { seed, round }
+    }
+
+    #[test]
+    fn battles_are_grouped_by_run() {
+        let mut index = ReplayIndex::default();
+        for recorded in [battle(7, 1), battle(7, 2), battle(3, 1), battle(7, 1)] {
+            index.record(recorded);
+        }
+
+        assert_eq!(index.runs(), [(7, vec![1, 2]), (3, vec![1])]);
+    }
+
+    #[test]
+    fn oldest_battles_are_removed() {
+        let mut index = ReplayIndex::default();
+        for round in 0..MAX_REPLAYS as u32 {
+            assert!(index.record(battle(1, round)).is_empty());
+        }
+
+        assert_eq!(index.record(battle(2, 0)), [battle(1, 0)]);
+        assert_eq!(index.battles.len(), MAX_REPLAYS);
+    }
+}
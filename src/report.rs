@@ -0,0 +1,159 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    battle::{
+        AbilityUsedEvent, BattleCreature, BattleSet, BattleStep, CreatureDieEvent,
+        DamageTakenEvent, Enemy, UsedAbility,
+    },
+    creature::CreatureStats,
+    naming::SpeciesName,
+    GameResult, GameState,
+};
+
+pub struct ReportPlugin;
+
+impl Plugin for ReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BattleReport>()
+            .add_systems(OnEnter(GameState::Battle), reset_report)
+            .add_systems(BattleStep, record_battle_report.after(BattleSet::Death));
+    }
+}
+
+/// What every species did in the last battle, in the order the species joined it.
+#[derive(Resource, Default)]
+pub struct BattleReport {
+    pub species: Vec<SpeciesReport>,
+    /// Set when the battle ended the run, the report then leads to the game over screen.
+    pub run_result: Option<GameResult>,
+    /// Species index of every creature in the battle.
+    creatures: HashMap<Entity, usize>,
+    /// Species index of every template.
+    templates: HashMap<Entity, usize>,
+}
+
+pub struct SpeciesReport {
+    pub name: String,
+    pub archetype: &'static str,
+    pub generation: u64,
+    pub is_enemy: bool,
+    pub fielded: u32,
+    pub lost: u32,
+    pub kills: u32,
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    /// Name and number of uses of every ability, physical abilities first.
+    pub ability_uses: Vec<(String, u32)>,
+    physical_count: usize,
+}
+
+impl BattleReport {
+    pub fn side(&self, is_enemy: bool) -> impl Iterator<Item = &SpeciesReport> {
+        self.species
+            .iter()
+            .filter(move |species| species.is_enemy == is_enemy)
+    }
+
+    fn template_species(&mut self, template: Entity) -> Option<&mut SpeciesReport> {
+        let &index = self.templates.get(&template)?;
+        Some(&mut self.species[index])
+    }
+
+    fn creature_species(&mut self, creature: Entity) -> Option<&mut SpeciesReport> {
+        let &index = self.creatures.get(&creature)?;
+        Some(&mut self.species[index])
+    }
+}
+
+fn reset_report(mut report: ResMut<BattleReport>) {
+    *report = BattleReport::default();
+}
+
+fn record_battle_report(
+    mut report: ResMut<BattleReport>,
+    spawned_query: Query<(Entity, &BattleCreature, Has<Enemy>), Added<BattleCreature>>,
+    template_query: Query<(&CreatureStats, &SpeciesName)>,
+    mut er_damage_taken: EventReader<DamageTakenEvent>,
+    mut er_creature_die: EventReader<CreatureDieEvent>,
+    mut er_ability_used: EventReader<AbilityUsedEvent>,
+) {
+    let report = &mut *report;
+
+    for (entity, creature, is_enemy) in spawned_query.iter() {
+        let template = creature.template();
+        let index = match report.templates.get(&template) {
+            Some(&index) => index,
+            None => {
+                let Ok((stats, name)) = template_query.get(template) else {
+                    continue;
+                };
+
+                report.species.push(SpeciesReport {
+                    name: name.0.clone(),
+                    archetype: stats.archetype.name(),
+                    generation: stats.generation,
+                    is_enemy,
+                    fielded: 0,
+                    lost: 0,
+                    kills: 0,
+                    damage_dealt: 0.0,
+                    damage_taken: 0.0,
+                    ability_uses: stats
+                        .physical_abilities
+                        .iter()
+                        .map(|ability| ability.name.clone())
+                        .chain(
+                            stats
+                                .magic_abilities
+                                .iter()
+                                .map(|ability| ability.kind.name().to_string()),
+                        )
+                        .map(|name| (name, 0))
+                        .collect(),
+                    physical_count: stats.physical_abilities.len(),
+                });
+                report.templates.insert(template, report.species.len() - 1);
+
+                report.species.len() - 1
+            }
+        };
+
+        report.species[index].fielded += 1;
+        report.creatures.insert(entity, index);
+    }
+
+    for event in er_damage_taken.read() {
+        if let Some(species) = report.template_species(event.source) {
+            species.damage_dealt += event.damage;
+        }
+        if let Some(species) = report.creature_species(event.target) {
+            species.damage_taken += event.damage;
+        }
+    }
+
+    for event in er_ability_used.read() {
+        let Some(species) = report.template_species(event.template) else {
+            continue;
+        };
+        let index = match event.ability {
+            UsedAbility::Physical(index) => index,
+            UsedAbility::Magic(index) => species.physical_count + index,
+        };
+
+        if let Some((_, uses)) = species.ability_uses.get_mut(index) {
+            *uses += 1;
+        }
+    }
+
+    for event in er_creature_die.read() {
+        if let Some(species) = report.template_species(event.template) {
+            species.lost += 1;
+        }
+        if let Some(species) = event
+            .killer
+            .and_then(|killer| report.template_species(killer))
+        {
+            species.kills += 1;
+        }
+    }
+}
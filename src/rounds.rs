@@ -1,18 +1,13 @@
 use bevy::prelude::*;
 use rand::{rngs::StdRng, SeedableRng};
 use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
 
-const MIN_ENEMY_TIER: f32 = 1.0;
-const MIN_ENEMY_COUNT: f32 = 1.0;
-const MIN_ENEMY_POP_MULT: f32 = 0.5;
-
-const ENEMY_TIER_INC: f32 = 0.7;
-const ENEMY_COUNT_INC: f32 = 0.8;
-const ENEMY_POP_INC: f32 = 0.5;
-
-const ENEMY_TIER_STD_DEV: f32 = 1.0;
-const ENEMY_COUNT_STD_DEV: f32 = 0.5;
-const ENEMY_POP_MULT_STD_DEV: f32 = 0.3;
+use crate::{
+    balance::{DeploymentRules, DifficultyCurve},
+    genetics::InheritanceMode,
+    GameState,
+};
 
 pub struct RoundsPlugin;
 
@@ -21,27 +16,60 @@ impl Plugin for RoundsPlugin {
         app.init_resource::<Round>()
             .init_resource::<Difficulty>()
             .init_resource::<GameSettings>()
+            .init_resource::<RoundPrepared>()
             .add_event::<RoundOverEvent>()
             .add_event::<GameStartedEvent>()
-            .add_event::<GameEndedEvent>();
+            .add_event::<GameEndedEvent>()
+            .configure_sets(
+                OnEnter(GameState::CreatureManager),
+                PrepareRound.run_if(round_unprepared),
+            )
+            .add_systems(
+                OnEnter(GameState::CreatureManager),
+                mark_round_prepared.after(PrepareRound),
+            )
+            .add_systems(OnEnter(GameState::Battle), reset_round_prepared);
     }
 }
 
+/// Systems which start a new round when the creature manager is entered: the autosave, the new
+/// species and the enemy army. They are skipped when the player comes back to the creature manager
+/// before the battle.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrepareRound;
+
+#[derive(Resource, Default)]
+struct RoundPrepared(bool);
+
+fn round_unprepared(prepared: Res<RoundPrepared>) -> bool {
+    !prepared.0
+}
+
+fn mark_round_prepared(mut prepared: ResMut<RoundPrepared>) {
+    prepared.0 = true;
+}
+
+fn reset_round_prepared(mut prepared: ResMut<RoundPrepared>) {
+    prepared.0 = false;
+}
+
 #[derive(Event)]
 pub struct GameStartedEvent;
 
 #[derive(Event)]
 pub struct GameEndedEvent;
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Serialize, Deserialize)]
 pub struct GameSettings {
     pub infinity_mode_on: bool,
+    #[serde(default)]
+    pub inheritance: InheritanceMode,
 }
 
 #[derive(Event)]
 pub struct RoundOverEvent;
 
-#[derive(Resource)]
+#[derive(Resource, Serialize, Deserialize)]
 pub struct Round(pub u32);
 
 impl Default for Round {
@@ -50,38 +78,58 @@ impl Default for Round {
     }
 }
 
-#[derive(Resource)]
+/// Number of won rounds the enemies have grown stronger for, the actual values follow the
+/// difficulty curve of the balance.
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct Difficulty {
-    enemy_tier: f32,
-    enemy_count: f32,
-    enemy_pop_mult: f32,
+    level: u32,
+    #[serde(skip, default = "StdRng::from_entropy")]
     rng: StdRng,
 }
 
 impl Difficulty {
     pub fn inc_difficulty(&mut self) {
-        self.enemy_tier += ENEMY_TIER_INC;
-        self.enemy_count += ENEMY_COUNT_INC;
-        self.enemy_pop_mult += ENEMY_POP_INC;
+        self.level += 1;
+    }
+
+    pub fn set_rng(&mut self, rng: StdRng) {
+        self.rng = rng;
     }
 
-    pub fn enemy_tier(&mut self) -> u8 {
-        self.gen_value(self.enemy_tier, ENEMY_TIER_STD_DEV, MIN_ENEMY_TIER) as u8
+    pub fn enemy_tier(&mut self, curve: &DifficultyCurve) -> u8 {
+        self.gen_value(
+            curve.start.enemy_tier,
+            curve.increase.enemy_tier,
+            curve.std_dev.enemy_tier,
+            curve.min.enemy_tier,
+        ) as u8
     }
 
-    pub fn enemy_count(&mut self) -> u8 {
-        self.gen_value(self.enemy_count, ENEMY_COUNT_STD_DEV, MIN_ENEMY_COUNT) as u8
+    pub fn enemy_count(&mut self, curve: &DifficultyCurve) -> u8 {
+        self.gen_value(
+            curve.start.enemy_count,
+            curve.increase.enemy_count,
+            curve.std_dev.enemy_count,
+            curve.min.enemy_count,
+        ) as u8
     }
 
-    pub fn enemy_pop_mult(&mut self) -> f32 {
+    pub fn enemy_pop_mult(&mut self, curve: &DifficultyCurve) -> f32 {
         self.gen_value(
-            self.enemy_pop_mult,
-            ENEMY_POP_MULT_STD_DEV,
-            MIN_ENEMY_POP_MULT,
+            curve.start.enemy_pop_mult,
+            curve.increase.enemy_pop_mult,
+            curve.std_dev.enemy_pop_mult,
+            curve.min.enemy_pop_mult,
         )
     }
 
-    fn gen_value(&mut self, mean: f32, std_dev: f32, min: f32) -> f32 {
+    /// Number of creatures the player can send into the next battle.
+    pub fn deployment_cap(&self, rules: &DeploymentRules) -> u32 {
+        rules.start_cap + rules.cap_increase * self.level
+    }
+
+    fn gen_value(&mut self, start: f32, increase: f32, std_dev: f32, min: f32) -> f32 {
+        let mean = start + increase * self.level as f32;
         let normal_dist = Normal::new(mean, std_dev).unwrap();
         let result = normal_dist.sample(&mut self.rng);
 
@@ -92,10 +140,46 @@ impl Difficulty {
 impl Default for Difficulty {
     fn default() -> Self {
         Self {
-            enemy_tier: 1.0,
-            enemy_count: 1.0,
-            enemy_pop_mult: 1.0,
+            level: 0,
             rng: StdRng::from_entropy(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Prepared(u32);
+
+    fn enter(app: &mut App, state: GameState) {
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+        app.update();
+    }
+
+    #[test]
+    fn round_is_prepared_once_before_its_battle() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, RoundsPlugin))
+            .init_state::<GameState>()
+            .init_resource::<Prepared>()
+            .add_systems(
+                OnEnter(GameState::CreatureManager),
+                (|mut prepared: ResMut<Prepared>| prepared.0 += 1).in_set(PrepareRound),
+            );
+
+        enter(&mut app, GameState::CreatureManager);
+        enter(&mut app, GameState::Deployment);
+        enter(&mut app, GameState::CreatureManager);
+        assert_eq!(app.world().resource::<Prepared>().0, 1);
+
+        enter(&mut app, GameState::Battle);
+        enter(&mut app, GameState::CreatureManager);
+        assert_eq!(app.world().resource::<Prepared>().0, 2);
+    }
+}
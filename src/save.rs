@@ -0,0 +1,395 @@
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battle::Stance,
+    creature::{
+        body_part_variant, spawn_creature, BodyPart, CreatureGeneration, CreatureStats,
+        PopulationSize,
+    },
+    deployment::Deployment,
+    lineage::Lineage,
+    loading::TextureAssets,
+    naming::{generate_name, SpeciesName},
+    rounds::{Difficulty, GameEndedEvent, GameSettings, PrepareRound, Round},
+    scouting::generate_enemy_army,
+    screens::{
+        creature_manager_screen::generate_new_creature, new_creature_screen::PlayerCreature,
+    },
+    seed::{reseed_rngs, RunSeed},
+    statistics::GameStatistics,
+    storage, GameState,
+};
+
+/// Version written into new saves. Fields added to [`SaveData`] get a `#[serde(default)]`, so older
+/// saves keep loading, the version only records which format a save was written with.
+const SAVE_VERSION: u32 = 11;
+/// Oldest version which can still be loaded, raise it only for changes which can't be defaulted.
+/// Saves before version 5 kept the difficulty as enemy stats instead of a level.
+const MIN_SAVE_VERSION: u32 = 5;
+const SAVE_KEY: &str = "save.ron";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ContinueGameEvent>()
+            .add_systems(
+                OnEnter(GameState::CreatureManager),
+                // before anything of the new round is drawn from the freshly reseeded RNGs, so a
+                // continued run regenerates the same enemies and new species
+                capture_save
+                    .pipe(write_save)
+                    .in_set(PrepareRound)
+                    .before(generate_enemy_army)
+                    .before(generate_new_creature),
+            )
+            .add_systems(
+                OnExit(GameState::Menu),
+                (read_save.pipe(restore_save), reseed_rngs)
+                    .chain()
+                    .run_if(on_event::<ContinueGameEvent>()),
+            )
+            .add_systems(Update, delete_save.run_if(on_event::<GameEndedEvent>()));
+    }
+}
+
+/// Restores the saved run when leaving the menu.
+#[derive(Event)]
+pub struct ContinueGameEvent;
+
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+    version: u32,
+    seed: RunSeed,
+    round: Round,
+    difficulty: Difficulty,
+    creature_generation: CreatureGeneration,
+    statistics: GameStatistics,
+    settings: GameSettings,
+    #[serde(default)]
+    lineage: Lineage,
+    creatures: Vec<SavedCreature>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedCreature {
+    /// Empty in saves from before species were named, they are named after their body parts then.
+    #[serde(default)]
+    name: String,
+    stats: CreatureStats,
+    population: u32,
+    stance: Stance,
+    body_parts: Vec<usize>,
+    /// Chosen deployment, none while the species is deployed as far as the cap allows.
+    #[serde(default)]
+    deployment: Option<u32>,
+}
+
+/// Only reads the version of the save, so the menu can offer to continue without loading the run.
+pub fn save_exists() -> bool {
+    storage::read(SAVE_KEY).is_some_and(|data| is_supported(&data))
+}
+
+fn is_supported(data: &str) -> bool {
+    let version = ron::from_str::<SaveHeader>(data).map(|header| header.version);
+    let supported = matches!(version, Ok(MIN_SAVE_VERSION..=SAVE_VERSION));
+    if !supported {
+        warn!(
+            "Ignoring save with version {version:?}, supported versions are {MIN_SAVE_VERSION} to \
+            {SAVE_VERSION}"
+        );
+    }
+
+    supported
+}
+
+/// Resources which make up the progress of a run, the creatures are saved separately.
+#[derive(SystemParam)]
+struct RunProgress<'w> {
+    seed: ResMut<'w, RunSeed>,
+    round: ResMut<'w, Round>,
+    difficulty: ResMut<'w, Difficulty>,
+    creature_generation: ResMut<'w, CreatureGeneration>,
+    statistics: ResMut<'w, GameStatistics>,
+    settings: ResMut<'w, GameSettings>,
+    lineage: ResMut<'w, Lineage>,
+}
+
+/// The enemy army is not saved, it is generated again from the reseeded RNGs after loading.
+fn capture_save(
+    creature_query: Query<
+        (
+            &CreatureStats,
+            &PopulationSize,
+            &Stance,
+            &Children,
+            Option<&Deployment>,
+            &SpeciesName,
+        ),
+        With<PlayerCreature>,
+    >,
+    body_part_query: Query<&Sprite, With<BodyPart>>,
+    progress: RunProgress,
+) -> SaveData {
+    let creatures = creature_query
+        .iter()
+        .filter(|(_, &PopulationSize(size), _, _, _, _)| size > 0)
+        .map(
+            |(stats, &PopulationSize(size), &stance, children, deployment, name)| SavedCreature {
+                name: name.0.clone(),
+                stats: stats.clone(),
+                population: size,
+                stance,
+                body_parts: children
+                    .iter()
+                    .filter_map(|&child| body_part_query.get(child).ok())
+                    .map(body_part_variant)
+                    .collect(),
+                deployment: deployment.map(|&Deployment(count)| count),
+            },
+        )
+        .collect();
+
+    SaveData {
+        version: SAVE_VERSION,
+        seed: *progress.seed,
+        round: Round(progress.round.0),
+        difficulty: progress.difficulty.clone(),
+        creature_generation: CreatureGeneration(progress.creature_generation.0),
+        statistics: progress.statistics.clone(),
+        settings: GameSettings {
+            infinity_mode_on: progress.settings.infinity_mode_on,
+            inheritance: progress.settings.inheritance,
+        },
+        lineage: progress.lineage.clone(),
+        creatures,
+    }
+}
+
+fn write_save(In(save_data): In<SaveData>) {
+    match ron::to_string(&save_data) {
+        Ok(data) => storage::write(SAVE_KEY, &data),
+        Err(error) => warn!("Failed to serialize save {error:?}"),
+    }
+}
+
+fn restore_save(
+    In(save_data): In<Option<SaveData>>,
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    mut progress: RunProgress,
+) {
+    let Some(save_data) = save_data else {
+        return;
+    };
+
+    *progress.seed = save_data.seed;
+    *progress.round = save_data.round;
+    *progress.difficulty = save_data.difficulty;
+    *progress.creature_generation = save_data.creature_generation;
+    *progress.statistics = save_data.statistics;
+    *progress.settings = save_data.settings;
+    *progress.lineage = save_data.lineage;
+
+    for creature in save_data.creatures {
+        let deployment = creature.deployment;
+        let mut entity = load_creature(&mut commands, &textures, creature);
+        entity.insert(PlayerCreature);
+        if let Some(count) = deployment {
+            entity.insert(Deployment(count));
+        }
+    }
+}
+
+fn load_creature<'a>(
+    commands: &'a mut Commands,
+    textures: &Res<TextureAssets>,
+    creature: SavedCreature,
+) -> EntityCommands<'a> {
+    let entity = spawn_creature(
+        commands,
+        textures,
+        creature.stats,
+        PopulationSize(creature.population),
+        &creature.body_parts,
+    );
+    let name = if creature.name.is_empty() {
+        generate_name(&creature.body_parts)
+    } else {
+        creature.name
+    };
+    let mut entity = commands.entity(entity);
+    entity.insert((creature.stance, SpeciesName(name)));
+
+    entity
+}
+
+fn read_save() -> Option<SaveData> {
+    let data = storage::read(SAVE_KEY)?;
+    if !is_supported(&data) {
+        return None;
+    }
+
+    match ron::from_str::<SaveData>(&data) {
+        Ok(save_data) => Some(save_data),
+        Err(error) => {
+            warn!("Failed to deserialize save {error:?}");
+            None
+        }
+    }
+}
+
+fn delete_save() {
+    storage::delete(SAVE_KEY);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        balance::Balance,
+        battle::AttackRng,
+        creature::GenerateCreatureRng,
+        scouting::EnemyCreature,
+        screens::{
+            battle_screen::{BattleVisualsRng, CreaturePositionRng},
+            creature_manager_screen::CombinationRng,
+        },
+    };
+
+    fn new_world(seed: u64) -> World {
+        let mut world = World::new();
+        world.insert_resource(RunSeed(seed));
+        world.init_resource::<Round>();
+        world.init_resource::<Difficulty>();
+        world.init_resource::<CreatureGeneration>();
+        world.init_resource::<GameStatistics>();
+        world.init_resource::<GameSettings>();
+        world.init_resource::<Lineage>();
+        world.init_resource::<TextureAssets>();
+        world.init_resource::<Balance>();
+        world.insert_resource(GenerateCreatureRng(StdRng::from_entropy()));
+        world.insert_resource(CreaturePositionRng(StdRng::from_entropy()));
+        world.insert_resource(AttackRng(StdRng::from_entropy()));
+        world.insert_resource(BattleVisualsRng(StdRng::from_entropy()));
+        world.insert_resource(CombinationRng(StdRng::from_entropy()));
+        world.run_system_once(reseed_rngs);
+
+        world
+    }
+
+    /// Runs the systems which draw from the RNGs when the creature manager is entered.
+    fn start_round(world: &mut World) {
+        world.run_system_once(generate_enemy_army);
+        world.run_system_once(generate_new_creature);
+    }
+
+    /// Wins the battle of the current round without fighting it.
+    fn win_battle(world: &mut World) {
+        let mut query = world.query_filtered::<&mut PopulationSize, With<EnemyCreature>>();
+        for mut population in query.iter_mut(world) {
+            population.0 = 0;
+        }
+        world.resource_mut::<Round>().0 += 1;
+        world.resource_mut::<Difficulty>().inc_difficulty();
+        world.run_system_once(reseed_rngs);
+    }
+
+    /// Every species of the world, sorted so worlds can be compared.
+    fn species(world: &mut World) -> Vec<String> {
+        let mut query = world.query::<(&CreatureStats, &PopulationSize, Has<EnemyCreature>)>();
+        let mut species: Vec<_> = query
+            .iter(world)
+            .filter(|(_, &PopulationSize(size), _)| size > 0)
+            .map(|(stats, population, is_enemy)| {
+                ron::to_string(&(stats, population.0, is_enemy)).unwrap()
+            })
+            .collect();
+        species.sort();
+
+        species
+    }
+
+    #[test]
+    fn continued_run_matches_uninterrupted_run() {
+        let mut uninterrupted = new_world(42);
+        start_round(&mut uninterrupted);
+        win_battle(&mut uninterrupted);
+
+        let save_data = uninterrupted.run_system_once(capture_save);
+        let save_data = ron::from_str(&ron::to_string(&save_data).unwrap()).unwrap();
+        start_round(&mut uninterrupted);
+
+        let mut continued = new_world(7);
+        continued.run_system_once_with(Some(save_data), restore_save);
+        continued.run_system_once(reseed_rngs);
+        start_round(&mut continued);
+
+        assert_eq!(species(&mut uninterrupted), species(&mut continued));
+    }
+
+    #[test]
+    fn oldest_supported_save_still_loads() {
+        let data = r#"(
+            version: 5,
+            seed: (3),
+            round: (4),
+            difficulty: (level: 3),
+            creature_generation: (2),
+            statistics: (
+                is_game_on: true,
+                elapsed_seconds: 90.0,
+                survived_rounds: 3,
+                ally_kills: 12,
+                ally_deaths: 4,
+                combination_count: 1,
+            ),
+            settings: (infinity_mode_on: false),
+            creatures: [(
+                stats: (
+                    archetype: Melee,
+                    movement_speed: 150.0,
+                    hp: 100.0,
+                    stamina: 80.0,
+                    stamina_regen: 10.0,
+                    mana: 0.0,
+                    mana_regen: 0.0,
+                    generation: 1,
+                    physical_abilities: [],
+                    magic_abilities: [],
+                ),
+                population: 6,
+                stance: HoldLine,
+                body_parts: [0, 1, 2, 3],
+            )],
+        )"#;
+        assert!(is_supported(data));
+        assert!(!is_supported(&data.replace("version: 5", "version: 4")));
+
+        let save_data: SaveData = ron::from_str(data).unwrap();
+        let mut world = new_world(0);
+        world.run_system_once_with(Some(save_data), restore_save);
+
+        assert_eq!(world.resource::<Round>().0, 4);
+        assert_eq!(world.resource::<GameSettings>().inheritance, default());
+        let mut query = world.query::<(&CreatureStats, &PopulationSize, &Stance, &SpeciesName)>();
+        let (stats, population, stance, name) = query.single(&world);
+        assert_eq!(stats.size, 1.0);
+        assert_eq!(stats.hues, vec![0.0; 4]);
+        assert_eq!(population.0, 6);
+        assert_eq!(*stance, Stance::HoldLine);
+        assert_eq!(name.0, generate_name(&[0, 1, 2, 3]));
+    }
+}
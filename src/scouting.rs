@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::{
+    balance::Balance,
+    creature::{generate_creature, GenerateCreatureRng, PopulationSize},
+    loading::TextureAssets,
+    rounds::{Difficulty, PrepareRound},
+    screens::creature_manager_screen::generate_new_creature,
+    GameState,
+};
+
+pub struct ScoutingPlugin;
+
+impl Plugin for ScoutingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::CreatureManager),
+            // both draw from the same RNG, so the order has to be fixed for a seed to be repeatable
+            generate_enemy_army
+                .in_set(PrepareRound)
+                .before(generate_new_creature),
+        );
+    }
+}
+
+/// Template of a species of the enemy army of the next battle.
+#[derive(Component)]
+pub struct EnemyCreature;
+
+/// Generates the army of the next battle at the start of the round, so it can be scouted. The
+/// army is kept when the creature manager is entered again before the battle.
+pub fn generate_enemy_army(
+    mut commands: Commands,
+    enemy_query: Query<(Entity, &PopulationSize), With<EnemyCreature>>,
+    mut generate_creature_rng: ResMut<GenerateCreatureRng>,
+    textures: Res<TextureAssets>,
+    mut difficulty: ResMut<Difficulty>,
+    balance: Res<Balance>,
+) {
+    if enemy_query
+        .iter()
+        .any(|(_, &PopulationSize(size))| size > 0)
+    {
+        return;
+    }
+
+    // species without any creatures are not removed by the battle
+    for (entity, _) in enemy_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let curve = &balance.difficulty;
+    for _ in 0..difficulty.enemy_count(curve) {
+        let tier = difficulty.enemy_tier(curve);
+        let pop_multiplier = difficulty.enemy_pop_mult(curve);
+        let entity = generate_creature(
+            &mut commands,
+            &mut generate_creature_rng.0,
+            &textures,
+            &balance,
+            tier,
+            0,
+            pop_multiplier,
+        );
+        commands.entity(entity).insert(EnemyCreature);
+    }
+}
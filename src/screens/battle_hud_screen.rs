@@ -0,0 +1,395 @@
+use bevy::{prelude::*, sprite::Anchor, utils::HashMap};
+
+use crate::{
+    battle::{
+        BattleCreature, BattleCreatureStats, BattleSet, BattleStep, BattleTime, DamageTakenEvent,
+        Enemy,
+    },
+    creature::CreatureStats,
+    settings::DisplaySettings,
+    GameState,
+};
+
+use super::battle_screen::{ALLY_COLOR, ENEMY_COLOR};
+
+/// Bars are children of the creature sprite, so sizes are in body part pixels.
+const CREATURE_BAR_WIDTH: f32 = 20.0;
+const CREATURE_BAR_HEIGHT: f32 = 1.5;
+const CREATURE_BAR_Y: f32 = 17.0;
+const CREATURE_BAR_Z: f32 = 45.0;
+const HP_COLOR: Color = Color::srgb(0.2, 0.85, 0.2);
+const STAMINA_COLOR: Color = Color::srgb(0.95, 0.8, 0.2);
+const BAR_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+const SPECIES_BAR_SIZE: Vec2 = Vec2::new(120.0, 10.0);
+const SPECIES_LABEL_WIDTH: f32 = 100.0;
+const SPECIES_FONT_SIZE: f32 = 14.0;
+/// Distance of the species columns from the top left corner, the enemies start in the middle.
+const SPECIES_COLUMN_OFFSET: Vec2 = Vec2::new(10.0, 10.0);
+
+const DAMAGE_NUMBER_DURATION: f32 = 0.8;
+const DAMAGE_NUMBER_RISE_SPEED: f32 = 40.0;
+const DAMAGE_NUMBER_OFFSET: f32 = 24.0;
+const DAMAGE_NUMBER_Z: f32 = 60.0;
+const DAMAGE_NUMBER_FONT_SIZE: f32 = 14.0;
+const DEALT_DAMAGE_COLOR: Color = Color::srgb(1.0, 0.95, 0.6);
+const TAKEN_DAMAGE_COLOR: Color = Color::srgb(1.0, 0.35, 0.3);
+
+/// Optional battle information drawn over the battlefield, see [`DisplaySettings`].
+pub struct BattleHudScreenPlugin;
+
+impl Plugin for BattleHudScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Battle), create_species_columns)
+            .add_systems(
+                BattleStep,
+                (spawn_damage_numbers, update_damage_numbers)
+                    .chain()
+                    .in_set(BattleSet::React),
+            )
+            .add_systems(
+                Update,
+                (
+                    add_creature_bars,
+                    update_creature_bars,
+                    add_species_bars,
+                    update_species_bars,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Battle)),
+            )
+            .add_systems(OnExit(GameState::Battle), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct BattleHudItem;
+
+#[derive(Component, Clone, Copy)]
+enum CreatureBar {
+    Hp,
+    Stamina,
+}
+
+/// Column of the species bars of one team.
+#[derive(Component)]
+struct SpeciesColumn {
+    is_enemy: bool,
+}
+
+/// Total HP of the living creatures of a species compared to the HP they started the battle with.
+#[derive(Component)]
+struct SpeciesBar {
+    template: Entity,
+    max_hp: f32,
+    count: u32,
+    fill: Entity,
+    label: Entity,
+}
+
+#[derive(Component)]
+struct DamageNumber {
+    elapsed: f32,
+}
+
+fn create_species_columns(mut commands: Commands, settings: Res<DisplaySettings>) {
+    if !settings.species_bars {
+        return;
+    }
+
+    for is_enemy in [false, true] {
+        let left = match is_enemy {
+            true => Val::Percent(50.0),
+            false => Val::Px(SPECIES_COLUMN_OFFSET.x),
+        };
+
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left,
+                    top: Val::Px(SPECIES_COLUMN_OFFSET.y),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                ..default()
+            },
+            SpeciesColumn { is_enemy },
+            BattleHudItem,
+        ));
+    }
+}
+
+fn add_creature_bars(
+    mut commands: Commands,
+    query: Query<Entity, Added<BattleCreature>>,
+    settings: Res<DisplaySettings>,
+) {
+    if !settings.creature_bars {
+        return;
+    }
+
+    for entity in query.iter() {
+        commands.entity(entity).with_children(|children| {
+            children.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: BAR_BACKGROUND_COLOR,
+                    custom_size: Some(Vec2::new(
+                        CREATURE_BAR_WIDTH + 1.0,
+                        CREATURE_BAR_HEIGHT * 2.0 + 1.0,
+                    )),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, CREATURE_BAR_Y, CREATURE_BAR_Z),
+                ..default()
+            });
+
+            for (bar, color, offset) in [
+                (CreatureBar::Hp, HP_COLOR, 0.5),
+                (CreatureBar::Stamina, STAMINA_COLOR, -0.5),
+            ] {
+                children.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color,
+                            custom_size: Some(Vec2::new(CREATURE_BAR_WIDTH, CREATURE_BAR_HEIGHT)),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(
+                            -CREATURE_BAR_WIDTH / 2.0,
+                            CREATURE_BAR_Y + offset * CREATURE_BAR_HEIGHT,
+                            CREATURE_BAR_Z + 1.0,
+                        ),
+                        ..default()
+                    },
+                    bar,
+                ));
+            }
+        });
+    }
+}
+
+fn update_creature_bars(
+    creature_query: Query<(&BattleCreature, &BattleCreatureStats, &Children)>,
+    mut bar_query: Query<(&CreatureBar, &mut Transform)>,
+) {
+    for (creature, stats, children) in creature_query.iter() {
+        for &child in children.iter() {
+            let Ok((bar, mut transform)) = bar_query.get_mut(child) else {
+                continue;
+            };
+
+            let fraction = match bar {
+                CreatureBar::Hp => stats.hp() / creature.max_hp(),
+                CreatureBar::Stamina => stats.stamina() / creature.max_stamina(),
+            };
+            transform.scale.x = fraction.clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Adds the creatures which joined the battle to the bar of their species, species get a bar when
+/// their first creature joins.
+fn add_species_bars(
+    mut commands: Commands,
+    creature_query: Query<(&BattleCreature, Has<Enemy>), Added<BattleCreature>>,
+    column_query: Query<(Entity, &SpeciesColumn)>,
+    mut species_bar_query: Query<&mut SpeciesBar>,
+    template_query: Query<&CreatureStats>,
+) {
+    if column_query.is_empty() {
+        return;
+    }
+
+    let mut joined = HashMap::<Entity, (bool, f32, u32)>::new();
+    for (creature, is_enemy) in creature_query.iter() {
+        let species = joined
+            .entry(creature.template())
+            .or_insert((is_enemy, 0.0, 0));
+        species.1 += creature.max_hp();
+        species.2 += 1;
+    }
+
+    for (template, (is_enemy, max_hp, count)) in joined {
+        if let Some(mut bar) = species_bar_query
+            .iter_mut()
+            .find(|bar| bar.template == template)
+        {
+            bar.max_hp += max_hp;
+            bar.count += count;
+            continue;
+        }
+
+        let Some((column, _)) = column_query
+            .iter()
+            .find(|(_, column)| column.is_enemy == is_enemy)
+        else {
+            continue;
+        };
+        let name = template_query
+            .get(template)
+            .map_or("", |stats| stats.archetype.name());
+        let color = match is_enemy {
+            true => ENEMY_COLOR,
+            false => ALLY_COLOR,
+        };
+
+        let label = commands
+            .spawn(TextBundle {
+                text: Text::from_section(
+                    name,
+                    TextStyle {
+                        font_size: SPECIES_FONT_SIZE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    width: Val::Px(SPECIES_LABEL_WIDTH),
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        let fill = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: color.into(),
+                ..default()
+            })
+            .id();
+        let background = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(SPECIES_BAR_SIZE.x),
+                    height: Val::Px(SPECIES_BAR_SIZE.y),
+                    ..default()
+                },
+                background_color: BAR_BACKGROUND_COLOR.into(),
+                ..default()
+            })
+            .add_child(fill)
+            .id();
+
+        let row = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                SpeciesBar {
+                    template,
+                    max_hp,
+                    count,
+                    fill,
+                    label,
+                },
+            ))
+            .push_children(&[label, background])
+            .id();
+        commands.entity(column).add_child(row);
+    }
+}
+
+fn update_species_bars(
+    creature_query: Query<(&BattleCreature, &BattleCreatureStats)>,
+    bar_query: Query<&SpeciesBar>,
+    template_query: Query<&CreatureStats>,
+    mut style_query: Query<&mut Style>,
+    mut text_query: Query<&mut Text>,
+) {
+    let mut living = HashMap::<Entity, (f32, u32)>::new();
+    for (creature, stats) in creature_query.iter() {
+        let species = living.entry(creature.template()).or_default();
+        species.0 += stats.hp().max(0.0);
+        species.1 += 1;
+    }
+
+    for bar in bar_query.iter() {
+        let (hp, alive) = living.get(&bar.template).copied().unwrap_or_default();
+
+        if let Ok(mut style) = style_query.get_mut(bar.fill) {
+            style.width = Val::Percent((hp / bar.max_hp * 100.0).clamp(0.0, 100.0));
+        }
+        if let Ok(mut text) = text_query.get_mut(bar.label) {
+            let name = template_query
+                .get(bar.template)
+                .map_or("", |stats| stats.archetype.name());
+            text.sections[0].value = format!("{name} {alive}/{}", bar.count);
+        }
+    }
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut er_damage_taken: EventReader<DamageTakenEvent>,
+    target_query: Query<(&Transform, Has<Enemy>)>,
+    settings: Res<DisplaySettings>,
+) {
+    for event in er_damage_taken.read() {
+        if !settings.damage_numbers {
+            continue;
+        }
+        let Ok((transform, is_enemy)) = target_query.get(event.target) else {
+            continue;
+        };
+
+        let position = transform.translation.xy() + Vec2::Y * DAMAGE_NUMBER_OFFSET;
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{:.0}", event.damage),
+                    TextStyle {
+                        font_size: DAMAGE_NUMBER_FONT_SIZE,
+                        color: match is_enemy {
+                            true => DEALT_DAMAGE_COLOR,
+                            false => TAKEN_DAMAGE_COLOR,
+                        },
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(DAMAGE_NUMBER_Z)),
+                ..default()
+            },
+            DamageNumber { elapsed: 0.0 },
+            BattleHudItem,
+        ));
+    }
+}
+
+/// Damage numbers rise and fade out.
+fn update_damage_numbers(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut DamageNumber, &mut Transform, &mut Text)>,
+    time: Res<BattleTime>,
+) {
+    for (entity, mut number, mut transform, mut text) in query.iter_mut() {
+        number.elapsed += time.delta_seconds();
+        if number.elapsed >= DAMAGE_NUMBER_DURATION {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_NUMBER_RISE_SPEED * time.delta_seconds();
+        let alpha = 1.0 - number.elapsed / DAMAGE_NUMBER_DURATION;
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<BattleHudItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
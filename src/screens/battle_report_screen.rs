@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+
+use crate::{
+    loading::TextureAssets,
+    report::{BattleReport, SpeciesReport},
+    rounds::Round,
+    ui::create_change_state_button,
+    GameResult, GameState, WINDOW_SIZE,
+};
+
+const BACKGROUND_Z: f32 = -20.0;
+const COLUMN_WIDTH: f32 = 620.0;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const LOSS_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
+
+pub struct BattleReportScreenPlugin;
+
+impl Plugin for BattleReportScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::BattleReport), setup)
+            .add_systems(
+                Update,
+                handle_report_keys.run_if(in_state(GameState::BattleReport)),
+            )
+            .add_systems(OnExit(GameState::BattleReport), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct BattleReportScreenItem;
+
+fn setup(
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    report: Res<BattleReport>,
+    round: Res<Round>,
+) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.menu_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        BattleReportScreenItem,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::top(Val::Px(32.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            BattleReportScreenItem,
+        ))
+        .with_children(|parent| {
+            let title = match report.run_result {
+                Some(GameResult::Defeat) => format!("Round {} lost", round.0),
+                // the round is already increased when the battle is won
+                _ => format!("Round {} won", round.0 - 1),
+            };
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 96.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(32.0),
+                        margin: UiRect::top(Val::Px(16.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (title, is_enemy) in [("Your army", false), ("Enemy army", true)] {
+                        spawn_side_column(parent, title, report.side(is_enemy));
+                    }
+                });
+        });
+
+    let entity = create_change_state_button(
+        &mut commands,
+        "Continue",
+        WINDOW_SIZE * Vec2::new(0.5, 0.9),
+        next_state(&report),
+    );
+    commands.entity(entity).insert(BattleReportScreenItem);
+}
+
+/// The next round is prepared after the report, unless the battle ended the run.
+fn next_state(report: &BattleReport) -> GameState {
+    match &report.run_result {
+        Some(result) => GameState::GameOver(result.clone()),
+        None => GameState::CreatureManager,
+    }
+}
+
+fn spawn_side_column<'a>(
+    parent: &mut ChildBuilder,
+    title: &str,
+    species: impl Iterator<Item = &'a SpeciesReport>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(COLUMN_WIDTH),
+                max_height: Val::Px(WINDOW_SIZE.y * 0.65),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            let species = species.collect::<Vec<_>>();
+            let fielded = species.iter().map(|species| species.fielded).sum::<u32>();
+            let lost = species.iter().map(|species| species.lost).sum::<u32>();
+
+            parent.spawn(TextBundle::from_sections([
+                TextSection::new(
+                    title,
+                    TextStyle {
+                        font_size: 40.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                TextSection::new(
+                    format!(
+                        "  {fielded} fielded in {} species, {lost} lost",
+                        species.len()
+                    ),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: DETAIL_COLOR,
+                        ..default()
+                    },
+                ),
+            ]));
+
+            for species in species {
+                spawn_species_row(parent, species);
+            }
+        });
+}
+
+fn spawn_species_row(parent: &mut ChildBuilder, species: &SpeciesReport) {
+    let abilities = species
+        .ability_uses
+        .iter()
+        .map(|(name, uses)| format!("{name} {uses}x"))
+        .collect::<Vec<_>>();
+    let abilities = match abilities.is_empty() {
+        true => "no abilities".to_string(),
+        false => abilities.join(", "),
+    };
+
+    parent.spawn(TextBundle::from_sections([
+        TextSection::new(
+            species.name.clone(),
+            TextStyle {
+                font_size: 24.0,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        ),
+        TextSection::new(
+            format!(
+                " #{} {}  {} fielded, ",
+                species.generation, species.archetype, species.fielded
+            ),
+            TextStyle {
+                font_size: 18.0,
+                color: DETAIL_COLOR,
+                ..default()
+            },
+        ),
+        TextSection::new(
+            format!("{} lost", species.lost),
+            TextStyle {
+                font_size: 18.0,
+                color: match species.lost {
+                    0 => DETAIL_COLOR,
+                    _ => LOSS_COLOR,
+                },
+                ..default()
+            },
+        ),
+        TextSection::new(
+            format!(
+                "\nkills {}, damage dealt {:.0}, damage taken {:.0}\n{abilities}",
+                species.kills, species.damage_dealt, species.damage_taken,
+            ),
+            TextStyle {
+                font_size: 18.0,
+                color: DETAIL_COLOR,
+                ..default()
+            },
+        ),
+    ]));
+}
+
+fn handle_report_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    report: Res<BattleReport>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Enter) {
+        next_game_state.set(next_state(&report));
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<BattleReportScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
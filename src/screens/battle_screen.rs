@@ -1,24 +1,33 @@
-use core::f32;
 use std::{f32::consts::PI, time::Duration};
 
-use bevior_tree::{
-    node::NodeResult,
-    prelude::{delegate_node, ConditionalLoop, Sequence},
-    task::{TaskBridge, TaskStatus},
-    BehaviorTreeBundle, BehaviorTreePlugin,
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::Instant,
 };
-use bevy::prelude::*;
 use bevy_kira_audio::{Audio, AudioControl, AudioInstance, AudioTween};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
     audio::{Soundtracks, SOUND_EFFECTS_GLOBAL_VOLUME},
-    creature::{
-        generate_creature, BodyPart, CreatureStats, GenerateCreatureRng, PhysicalAbility,
-        PopulationChangedEvent, PopulationSize,
+    balance::Balance,
+    battle::{
+        random_position_in, random_spawn_position, BattleCreature, BattleCreatureBundle, BattleSet,
+        BattleStep, BattleTime, CreatureDieEvent, DamageTakenEvent, Enemy, Projectile,
+        SpellCastEvent, Stance,
     },
+    creature::{BodyPart, CreatureStats, MagicKind, PopulationSize},
+    deployment::{Deployed, Placement, Reserve},
     loading::{AudioAssets, TextureAssets},
+    report::BattleReport,
     rounds::{Difficulty, GameSettings, Round, RoundOverEvent},
+    scouting::EnemyCreature,
+    ui::create_small_button,
     GameResult, GameState, WINDOW_SIZE,
 };
 
@@ -26,50 +35,84 @@ use super::new_creature_screen::PlayerCreature;
 
 const CREATURE_Z: f32 = 1.0;
 const CREATURE_SCALE: f32 = 1.3;
-const MELEE_DISTANCE: f32 = 32.0;
 const DAMAGE_EFFECT_DURATION: f32 = 0.1;
 const DAMAGE_EFFECT_Z: f32 = 40.0;
 const BACKGROUND_Z: f32 = -20.0;
 const BLOOD_PUDDLE_Z: f32 = -10.0;
+const SPELL_Z: f32 = 30.0;
+const SPELL_EFFECT_DURATION: f32 = 0.3;
+const HEAL_EFFECT_RADIUS: f32 = 24.0;
+const PROJECTILE_RADIUS: f32 = 6.0;
+const ARROW_RADIUS: f32 = 4.0;
+/// Size of the ring under the feet of every creature, in body part pixels.
+const RING_SIZE: UVec2 = UVec2::new(24, 10);
+/// Inner edge of the ring relative to its outer edge.
+const RING_INNER: f32 = 0.65;
+const RING_OFFSET: Vec2 = Vec2::new(0.0, -11.0);
+const RING_Z: f32 = 1.0;
+/// Colors of the teams, used for the rings and the health bars.
+pub const ALLY_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+pub const ENEMY_COLOR: Color = Color::srgb(1.0, 0.3, 0.25);
 
 const VOLUME_TRANSITION: f32 = 0.5;
 
+/// Longest battle time consumed by one step, faster speeds run several steps per frame.
+const MAX_STEP_SECONDS: f32 = 1.0 / 30.0;
+/// Upper limit of steps per frame, so a long frame does not cause an even longer one.
+const MAX_STEPS_PER_FRAME: u32 = 16;
+/// Upper limit of real time spent on battle steps per frame when skipping to the result.
+const SKIP_FRAME_BUDGET: Duration = Duration::from_millis(12);
+const SPEED_BUTTON_SIZE: Vec2 = Vec2::new(56.0, 28.0);
+const SPEED_BUTTON_SPACING: f32 = 60.0;
+const SPEED_FONT_SIZE: f32 = 18.0;
+const SPEED_SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const SPEED_NORMAL_COLOR: Color = Color::linear_rgb(0.9, 0.9, 0.9);
+
 pub struct BattleScreenPlugin;
 
 impl Plugin for BattleScreenPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(BehaviorTreePlugin::default())
-            .insert_resource(CreaturePositionRng(StdRng::from_entropy()))
-            .insert_resource(AttackRng(StdRng::from_entropy()))
+        app.insert_resource(CreaturePositionRng(StdRng::from_entropy()))
             .insert_resource(BattleVisualsRng(StdRng::from_entropy()))
-            .add_event::<DamageTakenEvent>()
-            .add_event::<CreatureDieEvent>()
+            .init_resource::<BattleSpeed>()
+            .init_resource::<TeamRing>()
             .add_systems(
                 OnEnter(GameState::Battle),
                 (
-                    (
-                        setup_player_creatures,
-                        generate_enemy_creatures,
-                        setup_enemy_creatures,
-                    )
-                        .chain(),
+                    (setup_player_creatures, setup_enemy_creatures).chain(),
                     setup_environment,
                     create_round_counter,
+                    (reset_battle_speed, create_speed_controls).chain(),
                 ),
             )
             .add_systems(OnExit(GameState::Battle), cleanup)
             .add_systems(
-                Update,
+                BattleStep,
                 (
-                    find_nearest_enemy,
-                    go_to_nearest_enemy,
-                    stats_recovery,
-                    attack_enemy,
-                    play_battle_sounds,
+                    spawn_spell_effects,
+                    handle_spell_effects,
                     handle_damage_effect,
-                    death_system,
-                    spawn_blood_puddle,
-                    handle_battle_over,
+                )
+                    .chain()
+                    .in_set(BattleSet::React),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        handle_speed_buttons,
+                        handle_speed_keys,
+                        update_speed_controls,
+                    )
+                        .chain(),
+                    (
+                        run_battle_steps,
+                        add_projectile_visuals,
+                        play_battle_sounds,
+                        spawn_blood_puddle,
+                        handle_battle_over,
+                    )
+                        .chain(),
                 )
                     .chain()
                     .run_if(in_state(GameState::Battle)),
@@ -78,66 +121,139 @@ impl Plugin for BattleScreenPlugin {
 }
 
 #[derive(Resource)]
-struct BattleVisualsRng(StdRng);
+pub struct BattleVisualsRng(pub StdRng);
 
-#[derive(Event)]
-struct DamageTakenEvent(Entity);
+/// Ring drawn under every creature in the color of its team.
+#[derive(Resource)]
+pub struct TeamRing(Handle<Image>);
+
+impl FromWorld for TeamRing {
+    fn from_world(world: &mut World) -> Self {
+        let size = RING_SIZE.as_vec2();
+        let mut data = Vec::new();
+        for y in 0..RING_SIZE.y {
+            for x in 0..RING_SIZE.x {
+                let pos = (Vec2::new(x as f32, y as f32) + 0.5) / size * 2.0 - 1.0;
+                let alpha = match (RING_INNER..=1.0).contains(&pos.length()) {
+                    true => 255,
+                    false => 0,
+                };
+                data.extend_from_slice(&[255, 255, 255, alpha]);
+            }
+        }
+
+        let image = Image::new(
+            Extent3d {
+                width: RING_SIZE.x,
+                height: RING_SIZE.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
 
-#[derive(Event)]
-pub struct CreatureDieEvent {
-    pub pos: Vec2,
-    pub is_enemy: bool,
+        Self(world.resource_mut::<Assets<Image>>().add(image))
+    }
 }
 
-#[derive(Component, Default)]
-struct DamageEffect {
-    elapsed: f32,
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum SpeedSetting {
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+    /// Runs the battle as fast as possible until it is over.
+    Skip,
 }
 
-#[derive(Component)]
-struct BattleScreenItem;
+impl SpeedSetting {
+    /// Battle seconds per real second, `None` when skipping.
+    fn multiplier(&self) -> Option<f32> {
+        match self {
+            SpeedSetting::Normal => Some(1.0),
+            SpeedSetting::Double => Some(2.0),
+            SpeedSetting::Quadruple => Some(4.0),
+            SpeedSetting::Skip => None,
+        }
+    }
+}
 
-#[derive(Component)]
-struct BattleCreature {
-    template: Entity,
-    movement_speed: f32,
-    stamina_regen: f32,
-    max_stamina: f32,
-    physical_abilities: Vec<PhysicalAbility>,
+#[derive(Resource, Clone, Copy, Default)]
+struct BattleSpeed {
+    setting: SpeedSetting,
+    paused: bool,
 }
 
-#[derive(Component, Clone)]
-struct BattleCreatureStats {
-    hp: f32,
-    stamina: f32,
-    cooldown: f32,
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SpeedAction {
+    TogglePause,
+    Set(SpeedSetting),
 }
 
-#[derive(Component)]
-struct Enemy;
+impl SpeedAction {
+    const ALL: [SpeedAction; 5] = [
+        SpeedAction::TogglePause,
+        SpeedAction::Set(SpeedSetting::Normal),
+        SpeedAction::Set(SpeedSetting::Double),
+        SpeedAction::Set(SpeedSetting::Quadruple),
+        SpeedAction::Set(SpeedSetting::Skip),
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SpeedAction::TogglePause => "Pause",
+            SpeedAction::Set(SpeedSetting::Normal) => "1x",
+            SpeedAction::Set(SpeedSetting::Double) => "2x",
+            SpeedAction::Set(SpeedSetting::Quadruple) => "4x",
+            SpeedAction::Set(SpeedSetting::Skip) => "Skip",
+        }
+    }
 
-#[derive(Component)]
-struct BehaviorTreeContext {
-    nearest_enemy_pos: Vec2,
-    distance_squared_to_nearest_enemy: f32,
-    nearest_enemy: Option<Entity>,
-}
+    fn key(&self) -> KeyCode {
+        match self {
+            SpeedAction::TogglePause => KeyCode::Space,
+            SpeedAction::Set(SpeedSetting::Normal) => KeyCode::Digit1,
+            SpeedAction::Set(SpeedSetting::Double) => KeyCode::Digit2,
+            SpeedAction::Set(SpeedSetting::Quadruple) => KeyCode::Digit4,
+            SpeedAction::Set(SpeedSetting::Skip) => KeyCode::KeyS,
+        }
+    }
 
-impl Default for BehaviorTreeContext {
-    fn default() -> Self {
-        Self {
-            nearest_enemy_pos: Default::default(),
-            distance_squared_to_nearest_enemy: f32::INFINITY,
-            nearest_enemy: Default::default(),
+    fn apply(&self, speed: &mut BattleSpeed) {
+        match *self {
+            SpeedAction::TogglePause => speed.paused = !speed.paused,
+            SpeedAction::Set(setting) => {
+                speed.setting = setting;
+                speed.paused = false;
+            }
+        }
+    }
+
+    fn is_selected(&self, speed: &BattleSpeed) -> bool {
+        match *self {
+            SpeedAction::TogglePause => speed.paused,
+            SpeedAction::Set(setting) => !speed.paused && speed.setting == setting,
         }
     }
 }
 
-#[derive(Resource)]
-struct AttackRng(StdRng);
+#[derive(Component, Default)]
+struct DamageEffect {
+    elapsed: f32,
+}
+
+#[derive(Component, Default)]
+struct SpellEffect {
+    elapsed: f32,
+}
+
+#[derive(Component)]
+struct BattleScreenItem;
 
 #[derive(Resource)]
-struct CreaturePositionRng(StdRng);
+pub struct CreaturePositionRng(pub StdRng);
 
 fn create_round_counter(mut commands: Commands, textures: Res<TextureAssets>, round: Res<Round>) {
     commands
@@ -201,73 +317,89 @@ fn create_population(
     commands: &mut Commands,
     body_part_query: &Query<(&Sprite, &Handle<Image>, &Transform), With<BodyPart>>,
     rng: &mut StdRng,
-    entity: Entity,
-    components: (&CreatureStats, &Children),
-    count: u32,
+    (balance, ring): (&Balance, &TeamRing),
+    species: (Entity, &CreatureStats, &Stance, &Children),
+    (count, region): (u32, Option<Rect>),
     is_enemy: bool,
 ) {
-    let (stats, entity_children) = components;
+    let (entity, stats, &stance, entity_children) = species;
+    let body_parts = entity_children
+        .iter()
+        .map(|&child| {
+            let (sprite, texture, transform) = body_part_query.get(child).unwrap();
+
+            SpriteBundle {
+                sprite: sprite.clone(),
+                texture: texture.clone(),
+                transform: *transform,
+                ..default()
+            }
+        })
+        .collect::<Vec<_>>();
 
     for _ in 0..count {
-        let mut position = Vec3::new(
-            rng.gen_range(-WINDOW_SIZE.x / 2.0..-WINDOW_SIZE.x / 6.0),
-            rng.gen_range(-WINDOW_SIZE.y / 2.0..WINDOW_SIZE.y / 2.0),
-            CREATURE_Z,
+        let position = match region {
+            Some(region) => random_position_in(rng, region),
+            None => random_spawn_position(rng, is_enemy),
+        };
+
+        let bundle = BattleCreatureBundle::new(entity, stats, stance, &balance.size);
+        let mut entity = spawn_creature_sprite(
+            commands,
+            ring,
+            &body_parts,
+            (position, stats.size),
+            is_enemy,
         );
+        entity.insert((bundle, BattleScreenItem));
 
         if is_enemy {
-            position.x *= -1.0;
+            entity.insert(Enemy);
+        }
+    }
+}
+
+/// Spawns the sprite of a creature of the given size on the battlefield, facing the other side and
+/// standing on the ring of its team.
+pub fn spawn_creature_sprite<'a>(
+    commands: &'a mut Commands,
+    ring: &TeamRing,
+    body_parts: &[SpriteBundle],
+    (position, size): (Vec2, f32),
+    is_enemy: bool,
+) -> EntityCommands<'a> {
+    let mut entity = commands.spawn(SpriteBundle {
+        transform: Transform::from_translation(position.extend(CREATURE_Z))
+            .with_scale(Vec2::splat(CREATURE_SCALE * size).extend(1.0)),
+        sprite: Sprite {
+            flip_x: is_enemy,
+            ..default()
+        },
+        ..default()
+    });
+    entity.with_children(|children| {
+        for body_part in body_parts {
+            let mut body_part = body_part.clone();
+            body_part.sprite.flip_x = is_enemy;
+
+            children.spawn((body_part, BodyPart));
         }
 
-        let mut entity = commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_translation(position)
-                    .with_scale(Vec2::splat(CREATURE_SCALE).extend(1.0)),
-                sprite: Sprite {
-                    flip_x: is_enemy,
-                    ..default()
+        children.spawn(SpriteBundle {
+            texture: ring.0.clone(),
+            sprite: Sprite {
+                color: match is_enemy {
+                    true => ENEMY_COLOR,
+                    false => ALLY_COLOR,
                 },
                 ..default()
             },
-            BattleCreature {
-                template: entity,
-                movement_speed: stats.movement_speed,
-                physical_abilities: stats.physical_abilities.clone(),
-                max_stamina: stats.stamina,
-                stamina_regen: stats.stamina_regen,
-            },
-            BattleCreatureStats {
-                hp: stats.hp,
-                stamina: stats.stamina,
-                cooldown: 0.0,
-            },
-            BattleScreenItem,
-            BehaviorTreeContext::default(),
-            create_melee_behavior_tree(),
-        ));
-        entity.with_children(|children| {
-            for &child in entity_children.iter() {
-                let (sprite, texture, transform) = body_part_query.get(child).unwrap();
-
-                let mut sprite = sprite.clone();
-                sprite.flip_x = is_enemy;
-
-                children.spawn((
-                    SpriteBundle {
-                        sprite,
-                        texture: texture.clone(),
-                        transform: *transform,
-                        ..default()
-                    },
-                    BodyPart,
-                ));
-            }
+            transform: Transform::from_translation(RING_OFFSET.extend(RING_Z)),
+            ..default()
         });
+    });
 
-        if is_enemy {
-            entity.insert(Enemy);
-        }
-    }
+    entity
 }
 
 fn setup_player_creatures(
@@ -279,60 +411,69 @@ fn setup_player_creatures(
             &mut Visibility,
             &PopulationSize,
             &CreatureStats,
+            &Stance,
             &Children,
+            Option<&Deployed>,
+            Option<&Placement>,
         ),
         With<PlayerCreature>,
     >,
     body_part_query: Query<(&Sprite, &Handle<Image>, &Transform), With<BodyPart>>,
+    balance: Res<Balance>,
+    ring: Res<TeamRing>,
 ) {
-    for (entity, mut visibility, &PopulationSize(population_size), stats, children) in
-        query.iter_mut()
+    for (
+        entity,
+        mut visibility,
+        &PopulationSize(population_size),
+        stats,
+        stance,
+        children,
+        deployed,
+        placement,
+    ) in query.iter_mut()
     {
         *visibility = Visibility::Hidden;
+
+        let deployed = deployed.map_or(population_size, |deployed| deployed.0.min(population_size));
+        commands
+            .entity(entity)
+            .insert(Reserve(population_size - deployed));
+        if deployed == 0 {
+            continue;
+        }
+
         create_population(
             &mut commands,
             &body_part_query,
             &mut creature_position_rng.0,
-            entity,
-            (stats, children),
-            population_size,
+            (&balance, &ring),
+            (entity, stats, stance, children),
+            (deployed, placement.map(|&Placement(region)| region)),
             false,
         );
     }
 }
 
-fn generate_enemy_creatures(
-    mut commands: Commands,
-    mut generate_creature_rng: ResMut<GenerateCreatureRng>,
-    textures: Res<TextureAssets>,
-    mut difficulty: ResMut<Difficulty>,
-) {
-    for _ in 0..difficulty.enemy_count() {
-        generate_creature(
-            &mut commands,
-            &mut generate_creature_rng.0,
-            &textures,
-            difficulty.enemy_tier(),
-            0,
-            difficulty.enemy_pop_mult(),
-        );
-    }
-}
-
 fn setup_enemy_creatures(
     mut commands: Commands,
     mut creature_position_rng: ResMut<CreaturePositionRng>,
-    mut query: Query<(Entity, &PopulationSize, &CreatureStats, &Children), Without<PlayerCreature>>,
+    mut query: Query<
+        (Entity, &PopulationSize, &CreatureStats, &Stance, &Children),
+        With<EnemyCreature>,
+    >,
     body_part_query: Query<(&Sprite, &Handle<Image>, &Transform), With<BodyPart>>,
+    balance: Res<Balance>,
+    ring: Res<TeamRing>,
 ) {
-    for (entity, &PopulationSize(population_size), stats, children) in query.iter_mut() {
+    for (entity, &PopulationSize(population_size), stats, stance, children) in query.iter_mut() {
         create_population(
             &mut commands,
             &body_part_query,
             &mut creature_position_rng.0,
-            entity,
-            (stats, children),
-            population_size,
+            (&balance, &ring),
+            (entity, stats, stance, children),
+            (population_size, None),
             true,
         );
     }
@@ -364,217 +505,101 @@ fn cleanup(
         )));
 }
 
-fn find_nearest_enemy(
-    mut ally_query: Query<
-        (Entity, &mut BehaviorTreeContext, &Transform),
-        (With<BattleCreature>, Without<Enemy>),
-    >,
-    mut enemy_query: Query<
-        (Entity, &mut BehaviorTreeContext, &Transform),
-        (With<BattleCreature>, With<Enemy>),
-    >,
-) {
-    for (_, mut context, ally_transform) in ally_query.iter_mut() {
-        let position = ally_transform.translation.xy();
-        context.distance_squared_to_nearest_enemy = f32::INFINITY;
-
-        for (entity, _, enemy_transform) in enemy_query.iter() {
-            let distance_squared = position.distance_squared(enemy_transform.translation.xy());
-
-            if distance_squared < context.distance_squared_to_nearest_enemy {
-                context.distance_squared_to_nearest_enemy = distance_squared;
-                context.nearest_enemy_pos = enemy_transform.translation.xy();
-                context.nearest_enemy = Some(entity);
-            }
-        }
+/// Advances the battle by the frame time scaled with the battle speed. Faster speeds are split into
+/// several steps, so creatures and projectiles do not jump further per step than at normal speed.
+fn run_battle_steps(world: &mut World) {
+    let speed = *world.resource::<BattleSpeed>();
+    if speed.paused {
+        return;
     }
 
-    for (_, mut context, enemy_transform) in enemy_query.iter_mut() {
-        let position = enemy_transform.translation.xy();
-        context.distance_squared_to_nearest_enemy = f32::INFINITY;
-
-        for (entity, _, ally_transform) in ally_query.iter() {
-            let distance_squared = position.distance_squared(ally_transform.translation.xy());
+    let start = Instant::now();
+    let (steps, step_seconds) = match speed.setting.multiplier() {
+        Some(multiplier) => {
+            let battle_seconds = world.resource::<Time>().delta_seconds() * multiplier;
+            let steps =
+                ((battle_seconds / MAX_STEP_SECONDS).ceil() as u32).clamp(1, MAX_STEPS_PER_FRAME);
 
-            if distance_squared < context.distance_squared_to_nearest_enemy {
-                context.distance_squared_to_nearest_enemy = distance_squared;
-                context.nearest_enemy_pos = ally_transform.translation.xy();
-                context.nearest_enemy = Some(entity);
-            }
+            (steps, (battle_seconds / steps as f32).min(MAX_STEP_SECONDS))
         }
-    }
-}
+        None => (MAX_STEPS_PER_FRAME, MAX_STEP_SECONDS),
+    };
 
-fn create_melee_behavior_tree() -> BehaviorTreeBundle {
-    BehaviorTreeBundle::from_root(ConditionalLoop::new(
-        Sequence::new(vec![
-            Box::new(GoToNearestEnemyTask::new()),
-            Box::new(AttackEnemyTask::new()),
-        ]),
-        |In(_)| true,
-    ))
-}
-
-#[delegate_node(delegate)]
-struct GoToNearestEnemyTask {
-    delegate: TaskBridge,
-}
-
-impl GoToNearestEnemyTask {
-    pub fn new() -> Self {
-        let checker = move |In(entity): In<Entity>, param: Query<&BehaviorTreeContext>| {
-            let context = param.get(entity).unwrap();
-            let distance_squared = context.distance_squared_to_nearest_enemy;
-
-            match distance_squared <= MELEE_DISTANCE * MELEE_DISTANCE
-                && param.get(context.nearest_enemy.unwrap()).is_ok()
-            {
-                true => TaskStatus::Complete(NodeResult::Success),
-                false => TaskStatus::Running,
-            }
-        };
-        let task = TaskBridge::new(checker).insert_while_running(GoToNearestEnemy);
-
-        Self { delegate: task }
-    }
-}
-
-#[derive(Clone, Component, Reflect)]
-#[component(storage = "SparseSet")]
-struct GoToNearestEnemy;
-
-fn go_to_nearest_enemy(
-    mut query: Query<
-        (&mut Transform, &BehaviorTreeContext, &BattleCreature),
-        With<GoToNearestEnemy>,
-    >,
-    entity_query: Query<Entity>,
-    time: Res<Time>,
-) {
-    for (mut transform, context, creature) in query.iter_mut() {
-        if entity_query.get(context.nearest_enemy.unwrap()).is_err() {
-            continue;
+    for _ in 0..steps {
+        if speed.setting == SpeedSetting::Skip && start.elapsed() > SKIP_FRAME_BUDGET {
+            break;
         }
 
-        let pos = transform.translation.xy();
-
-        transform.translation += ((context.nearest_enemy_pos - pos).normalize_or_zero()
-            * creature.movement_speed
-            * time.delta_seconds())
-        .extend(0.0);
+        world
+            .resource_mut::<BattleTime>()
+            .set_delta_seconds(step_seconds);
+        world.run_schedule(BattleStep);
     }
 }
 
-#[delegate_node(delegate)]
-struct AttackEnemyTask {
-    delegate: TaskBridge,
+fn reset_battle_speed(mut speed: ResMut<BattleSpeed>) {
+    // faster speeds carry over to the next battle, pausing and skipping do not
+    speed.paused = false;
+    if speed.setting == SpeedSetting::Skip {
+        speed.setting = SpeedSetting::Normal;
+    }
 }
 
-impl AttackEnemyTask {
-    pub fn new() -> Self {
-        let checker = move |In(entity): In<Entity>, param: Query<&BehaviorTreeContext>| {
-            let context = param.get(entity);
-
-            if context.is_err() {
-                return TaskStatus::Complete(NodeResult::Success);
-            }
-            let context = context.unwrap();
-
-            match context.distance_squared_to_nearest_enemy <= MELEE_DISTANCE * MELEE_DISTANCE
-                && param.get(context.nearest_enemy.unwrap()).is_ok()
-            {
-                true => TaskStatus::Running,
-                false => TaskStatus::Complete(NodeResult::Success),
-            }
-        };
-        let task = TaskBridge::new(checker).insert_while_running(AttackEnemy);
-
-        Self { delegate: task }
+fn create_speed_controls(mut commands: Commands) {
+    for (i, action) in SpeedAction::ALL.into_iter().rev().enumerate() {
+        let pos = Vec2::new(
+            WINDOW_SIZE.x - SPEED_BUTTON_SPACING / 2.0 - i as f32 * SPEED_BUTTON_SPACING,
+            SPEED_BUTTON_SPACING / 2.0,
+        );
+        let button = create_small_button(
+            &mut commands,
+            action.label(),
+            pos,
+            SPEED_BUTTON_SIZE,
+            SPEED_FONT_SIZE,
+        );
+        commands.entity(button).insert((action, BattleScreenItem));
     }
 }
 
-#[derive(Clone, Component, Reflect)]
-#[component(storage = "SparseSet")]
-struct AttackEnemy;
-
-fn attack_enemy(
-    attacker_query: Query<(Entity, &BattleCreature, &BehaviorTreeContext), With<AttackEnemy>>,
-    mut stats_query: Query<(Entity, &mut BattleCreatureStats)>,
-    mut attack_rng: ResMut<AttackRng>,
-    mut ew_damage_taken: EventWriter<DamageTakenEvent>,
+fn handle_speed_buttons(
+    query: Query<(&Interaction, &SpeedAction), (Changed<Interaction>, With<Button>)>,
+    mut speed: ResMut<BattleSpeed>,
 ) {
-    for (entity, creature, context) in attacker_query.iter() {
-        let (_, stats) = stats_query.get_mut(entity).unwrap();
-        let mut stats = stats.clone();
-        if stats.cooldown > 0.0 {
-            continue;
+    for (&interaction, action) in query.iter() {
+        if interaction == Interaction::Pressed {
+            action.apply(&mut speed);
         }
-
-        let abilities = creature
-            .physical_abilities
-            .iter()
-            .filter(|ability| ability.stamina_cost <= stats.stamina)
-            .collect::<Vec<_>>();
-
-        if abilities.is_empty() {
-            continue;
-        }
-
-        let ability = abilities[attack_rng.0.gen_range(0..abilities.len())];
-        stats.stamina -= ability.stamina_cost;
-        stats.cooldown = ability.global_cooldown;
-
-        if let Ok((entity, mut target_stats)) = stats_query.get_mut(context.nearest_enemy.unwrap())
-        {
-            target_stats.hp -= ability.damage;
-            ew_damage_taken.send(DamageTakenEvent(entity));
-        } else {
-            continue;
-        }
-
-        *stats_query.get_mut(entity).unwrap().1 = stats;
     }
 }
 
-fn death_system(
-    mut commands: Commands,
-    hp_query: Query<(Entity, &BattleCreature, &BattleCreatureStats, &Transform)>,
-    mut population_query: Query<&mut PopulationSize>,
-    enemy_query: Query<&Enemy>,
-    mut ew_creature_die: EventWriter<CreatureDieEvent>,
-    mut ew_population_changed: EventWriter<PopulationChangedEvent>,
-) {
-    let mut entities_to_die = Vec::new();
-
-    for (entity, creature, stats, transform) in hp_query.iter() {
-        if stats.hp <= 0.0 {
-            let mut population = population_query.get_mut(creature.template).unwrap();
-            population.0 -= 1;
-            ew_population_changed.send(PopulationChangedEvent);
-
-            entities_to_die.push(entity);
-            ew_creature_die.send(CreatureDieEvent {
-                pos: transform.translation.xy(),
-                is_enemy: enemy_query.get(entity).is_ok(),
-            });
+fn handle_speed_keys(keys: Res<ButtonInput<KeyCode>>, mut speed: ResMut<BattleSpeed>) {
+    for action in SpeedAction::ALL {
+        if keys.just_pressed(action.key()) {
+            action.apply(&mut speed);
         }
     }
-
-    for entity in entities_to_die {
-        commands.entity(entity).despawn_recursive();
-    }
 }
 
-fn stats_recovery(mut query: Query<(&mut BattleCreatureStats, &BattleCreature)>, time: Res<Time>) {
-    for (mut stats, creature) in query.iter_mut() {
-        stats.cooldown -= time.delta_seconds();
-        if stats.cooldown < 0.0 {
-            stats.cooldown = 0.0;
+fn update_speed_controls(
+    button_query: Query<(Ref<SpeedAction>, &Children)>,
+    mut text_query: Query<&mut Text>,
+    speed: Res<BattleSpeed>,
+) {
+    for (action, children) in button_query.iter() {
+        if !speed.is_changed() && !action.is_added() {
+            continue;
         }
 
-        stats.stamina += creature.stamina_regen * time.delta_seconds();
-        if stats.stamina > creature.max_stamina {
-            stats.stamina = creature.max_stamina;
+        let color = match action.is_selected(&speed) {
+            true => SPEED_SELECTED_COLOR,
+            false => SPEED_NORMAL_COLOR,
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].style.color = color;
+            }
         }
     }
 }
@@ -586,38 +611,46 @@ fn handle_battle_over(
     mut difficulty: ResMut<Difficulty>,
     mut round: ResMut<Round>,
     mut ew_round_over: EventWriter<RoundOverEvent>,
-    game_settings: Res<GameSettings>,
+    (game_settings, mut report): (Res<GameSettings>, ResMut<BattleReport>),
 ) {
     if ally_query.is_empty() {
-        next_game_state.set(GameState::GameOver(GameResult::Defeat));
+        report.run_result = Some(GameResult::Defeat);
+        next_game_state.set(GameState::BattleReport);
     } else if enemy_query.is_empty() {
         round.0 += 1;
+        next_game_state.set(GameState::BattleReport);
 
         if round.0 == 11 && !game_settings.infinity_mode_on {
-            next_game_state.set(GameState::GameOver(GameResult::Victory));
+            report.run_result = Some(GameResult::Victory);
             return;
         }
 
         difficulty.inc_difficulty();
-        next_game_state.set(GameState::CreatureManager);
         ew_round_over.send(RoundOverEvent);
     }
 }
 
 fn handle_damage_effect(
     mut commands: Commands,
-    entity_query: Query<&Children>,
+    entity_query: Query<Option<&Children>>,
     enemy_query: Query<&Enemy>,
     mut effect_query: Query<(Entity, &mut DamageEffect)>,
     mut er_damage_taken: EventReader<DamageTakenEvent>,
     textures: Res<TextureAssets>,
-    time: Res<Time>,
+    time: Res<BattleTime>,
 ) {
-    for &DamageTakenEvent(creature_entity) in er_damage_taken.read() {
-        let children = entity_query
-            .get(creature_entity)
-            .unwrap()
-            .iter()
+    for &DamageTakenEvent {
+        target: creature_entity,
+        ..
+    } in er_damage_taken.read()
+    {
+        // the creature may have died during an earlier step of the same frame
+        let Ok(children) = entity_query.get(creature_entity) else {
+            continue;
+        };
+        let children = children
+            .into_iter()
+            .flatten()
             .find(|&&c| effect_query.get(c).is_ok());
 
         if let Some(effect_entity) = children {
@@ -678,13 +711,94 @@ fn spawn_blood_puddle(
     }
 }
 
+fn add_projectile_visuals(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Projectile, &mut Transform), Added<Projectile>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, projectile, mut transform) in query.iter_mut() {
+        transform.translation.z = SPELL_Z;
+
+        let (radius, color) = if projectile.is_magic() {
+            (PROJECTILE_RADIUS, Color::srgb(1.0, 0.45, 0.1))
+        } else {
+            (ARROW_RADIUS, Color::srgb(0.3, 0.2, 0.1))
+        };
+
+        commands.entity(entity).insert((
+            Mesh2dHandle(meshes.add(Circle::new(radius))),
+            materials.add(color),
+            VisibilityBundle::default(),
+            BattleScreenItem,
+        ));
+    }
+}
+
+fn spawn_spell_effects(
+    mut commands: Commands,
+    mut er_spell_cast: EventReader<SpellCastEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in er_spell_cast.read() {
+        let (color, radius) = match event.kind {
+            MagicKind::RangedDamage => continue,
+            MagicKind::AreaDamage => (Color::srgba(1.0, 0.45, 0.1, 0.4), event.radius),
+            MagicKind::Heal => (Color::srgba(0.2, 1.0, 0.3, 0.4), HEAL_EFFECT_RADIUS),
+            MagicKind::Buff => (Color::srgba(1.0, 0.9, 0.2, 0.2), event.radius),
+        };
+
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(Circle::new(radius))),
+                material: materials.add(color),
+                transform: Transform::from_translation(event.target_pos.extend(SPELL_Z)),
+                ..default()
+            },
+            SpellEffect {
+                elapsed: SPELL_EFFECT_DURATION,
+            },
+            BattleScreenItem,
+        ));
+    }
+}
+
+fn handle_spell_effects(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut SpellEffect)>,
+    time: Res<BattleTime>,
+) {
+    for (entity, mut effect) in query.iter_mut() {
+        effect.elapsed -= time.delta_seconds();
+        if effect.elapsed <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
 fn play_battle_sounds(
+    mut er_spell_cast: EventReader<SpellCastEvent>,
     mut er_damage_taken: EventReader<DamageTakenEvent>,
     mut er_creature_die: EventReader<CreatureDieEvent>,
     mut er_round_over: EventReader<RoundOverEvent>,
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
+    speed: Res<BattleSpeed>,
 ) {
+    // skipping fires far too many sounds per frame, only the victory sound is played
+    if speed.setting == SpeedSetting::Skip {
+        er_spell_cast.clear();
+        er_damage_taken.clear();
+        er_creature_die.clear();
+    }
+
+    for _ in er_spell_cast.read() {
+        audio
+            .play(audio_assets.spell.clone())
+            .with_volume(SOUND_EFFECTS_GLOBAL_VOLUME);
+    }
+
     for _ in er_damage_taken.read() {
         audio
             .play(audio_assets.attack.clone())
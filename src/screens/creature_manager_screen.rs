@@ -1,4 +1,8 @@
 use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
     prelude::*,
     sprite::{MaterialMesh2dBundle, Mesh2dHandle},
     utils::HashMap,
@@ -8,36 +12,68 @@ use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
     audio::SOUND_EFFECTS_GLOBAL_VOLUME,
+    balance::Balance,
+    battle::Stance,
     creature::{
-        generate_creature, BodyPart, CreatureGeneration, CreatureStats, GenerateCreatureRng,
-        PopulationChangedEvent, PopulationSize, CREATURE_SCALE, CREATURE_Z,
+        body_part_variant, delete_empty_creatures, generate_creature, inherit_appearance,
+        spawn_creature, BodyPart, CreatureGeneration, CreatureStats, GenerateCreatureRng,
+        PopulationChangedEvent, PopulationSize,
     },
+    deployment::Deployment,
+    genetics::combine_stats,
+    lineage::Lineage,
     loading::{AudioAssets, TextureAssets},
-    rounds::Round,
-    ui::{create_basic_button, create_change_state_button, create_mini_button},
+    naming::{combine_names, SpeciesName, MAX_NAME_LENGTH},
+    rounds::{GameSettings, PrepareRound, Round},
+    ui::{
+        create_basic_button, create_change_state_button, create_mini_button, create_small_button,
+    },
     GameState, WINDOW_SIZE,
 };
 
-use super::new_creature_screen::{PlayerCreature, MAX_CREATURE_TIER, MIN_CREATURE_TIER};
+use super::{
+    new_creature_screen::{PlayerCreature, MAX_CREATURE_TIER, MIN_CREATURE_TIER},
+    roster_screen::RosterView,
+};
 
 const CREATURES_Z: f32 = 0.0;
 const GRID_SIZE: Vec2 = Vec2::new(8.0, 3.0);
+/// The two right columns of the grid are taken by the stats windows.
+const GRID_COLUMNS: usize = GRID_SIZE.x as usize - 2;
+/// Number of species shown on one page of the roster.
+pub const ROSTER_PAGE_SIZE: usize = GRID_COLUMNS * GRID_SIZE.y as usize;
+/// Height of the roster controls above the grid.
+const ROSTER_BAR_HEIGHT: f32 = 36.0;
 const COUNT_OFFSET: Vec2 = Vec2::new(0.0, 68.0);
+const NAME_OFFSET: Vec2 = Vec2::new(0.0, 52.0);
+const NAME_FONT_SIZE: f32 = 16.0;
+const NAME_COLOR: Color = Color::BLACK;
+const RENAMING_COLOR: Color = Color::srgb(0.1, 0.2, 0.6);
 const CREATURE_BUTTON_SIZE: Vec2 = Vec2::new(96.0, 96.0);
+const STANCE_BUTTON_SIZE: Vec2 = Vec2::new(128.0, 24.0);
+const STANCE_BUTTON_OFFSET: f32 = 96.0;
+const STANCE_FONT_SIZE: f32 = 16.0;
+const SQUAD_BUTTON_SIZE: Vec2 = Vec2::new(96.0, 40.0);
+const SQUAD_FONT_SIZE: f32 = 20.0;
 const BACKGROUND_Z: f32 = -20.0;
 
 pub struct CreatureManagerScreenPlugin;
 
 impl Plugin for CreatureManagerScreenPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SelectedCreaturesCounter>()
+        app.init_resource::<SelectedCreatures>()
+            .init_resource::<Renaming>()
             .insert_resource(CombinationRng(StdRng::from_entropy()))
             .add_event::<CreatureCombinedEvent>()
             .add_event::<CombineButtonPressedEvent>()
             .add_systems(
                 OnEnter(GameState::CreatureManager),
                 (
-                    (generate_new_creature, (setup_ui, create_round_counter)).chain(),
+                    (
+                        generate_new_creature.in_set(PrepareRound),
+                        (setup_ui, layout_roster, create_round_counter),
+                    )
+                        .chain(),
                     setup_stats_windows,
                 ),
             )
@@ -47,11 +83,19 @@ impl Plugin for CreatureManagerScreenPlugin {
                 (
                     handle_inc_dec_buttons,
                     handle_creature_button,
+                    handle_stance_button,
                     handle_combine_button,
                     combine_creatures,
                     show_stats,
                     play_combine_sound,
                     trigger_population_changed,
+                    (
+                        handle_release_button,
+                        handle_split_button,
+                        handle_merge_button,
+                    )
+                        .before(delete_empty_creatures),
+                    (handle_rename_button, handle_rename_input, update_name_texts).chain(),
                 )
                     .run_if(in_state(GameState::CreatureManager)),
             )
@@ -60,6 +104,18 @@ impl Plugin for CreatureManagerScreenPlugin {
                 (partial_cleanup, (setup_ui, create_round_counter))
                     .chain()
                     .run_if(on_event::<CreatureCombinedEvent>()),
+            )
+            .add_systems(
+                Update,
+                layout_roster
+                    .after(partial_cleanup)
+                    .after(delete_empty_creatures)
+                    .run_if(in_state(GameState::CreatureManager))
+                    .run_if(
+                        on_event::<CreatureCombinedEvent>()
+                            .or_else(on_event::<PopulationChangedEvent>())
+                            .or_else(resource_changed::<RosterView>),
+                    ),
             );
     }
 }
@@ -75,14 +131,19 @@ struct CombineButtonPressedEvent {
 pub struct CreatureCombinedEvent;
 
 #[derive(Resource)]
-struct CombinationRng(StdRng);
+pub struct CombinationRng(pub StdRng);
 
+/// Species selected for combining, kept while the roster is paged, sorted or filtered.
 #[derive(Resource, Default)]
-struct SelectedCreaturesCounter(u8);
+pub struct SelectedCreatures(pub Vec<Entity>);
 
 #[derive(Component)]
 struct CreatureManagerScreenItem;
 
+/// Part of the roster page, replaced whenever the roster is laid out again.
+#[derive(Component)]
+struct RosterItem;
+
 #[derive(Component)]
 struct CombineButton;
 
@@ -93,21 +154,58 @@ struct IncButton;
 struct DecButton;
 
 #[derive(Component)]
-struct PopulationText;
+pub struct PopulationText;
+
+#[derive(Component)]
+struct ReleaseButton;
 
 #[derive(Component)]
-struct CreatureButton {
+struct SplitButton;
+
+#[derive(Component)]
+struct MergeButton;
+
+#[derive(Component)]
+struct RenameButton;
+
+/// Species which is being renamed and the name typed so far.
+#[derive(Resource, Default)]
+struct Renaming {
+    species: Option<Entity>,
+    text: String,
+}
+
+/// Population shown under a species in the roster.
+#[derive(Component)]
+pub struct CountText(pub Entity);
+
+/// Name shown above a species in the roster.
+#[derive(Component)]
+struct NameText(Entity);
+
+#[derive(Component)]
+pub struct CreatureButton {
+    pub entity: Entity,
+    pub selected: bool,
+}
+
+#[derive(Component)]
+struct StanceButton {
     entity: Entity,
-    selected: bool,
 }
 
 #[derive(Component, Clone, Copy, PartialEq, Eq)]
 enum StatLabel {
+    Name,
+    Archetype,
     MovementSpeed,
     HP,
     Stamina,
     StaminaRegen,
-    PhysicalAbility,
+    Mana,
+    ManaRegen,
+    Population,
+    Abilities,
 }
 
 #[derive(Component)]
@@ -140,11 +238,12 @@ fn create_round_counter(mut commands: Commands, textures: Res<TextureAssets>, ro
         });
 }
 
-fn generate_new_creature(
+pub fn generate_new_creature(
     mut commands: Commands,
     mut generate_creature_rng: ResMut<GenerateCreatureRng>,
     mut creature_generation: ResMut<CreatureGeneration>,
     textures: Res<TextureAssets>,
+    balance: Res<Balance>,
     round: Res<Round>,
 ) {
     let mut count = 0;
@@ -163,6 +262,7 @@ fn generate_new_creature(
             &mut commands,
             &mut generate_creature_rng.0,
             &textures,
+            &balance,
             tier,
             creature_generation.0,
             1.2,
@@ -176,11 +276,11 @@ fn generate_new_creature(
 const STATS_X: f32 = 0.37;
 const STATS_Y1: f32 = 0.31;
 const STATS_Y2: f32 = -0.05;
-const STATS_SIZE: Vec2 = Vec2::new(240.0, 240.0);
+const STATS_SIZE: Vec2 = Vec2::new(300.0, 240.0);
 const STAT_FONT_SIZE: f32 = 18.0;
 const STAT_LABEL_X: f32 = -STATS_SIZE.x / 2.0 + 10.0;
 const STAT_LABEL_Z: f32 = 20.0;
-const ABILITIES_FONT_SIZE: f32 = 16.0;
+const ABILITIES_FONT_SIZE: f32 = 14.0;
 
 fn setup_stats_windows(
     mut commands: Commands,
@@ -213,10 +313,15 @@ fn setup_stats_windows(
         ))
         .with_children(|children| {
             let labels = [
+                ("Name: ", StatLabel::Name),
+                ("Archetype: ", StatLabel::Archetype),
                 ("Movement Speed: ", StatLabel::MovementSpeed),
                 ("HP: ", StatLabel::HP),
                 ("Stamina: ", StatLabel::Stamina),
                 ("Stamina Regen: ", StatLabel::StaminaRegen),
+                ("Mana: ", StatLabel::Mana),
+                ("Mana Regen: ", StatLabel::ManaRegen),
+                ("Population: ", StatLabel::Population),
             ];
             let value_label = "00.00";
 
@@ -239,7 +344,7 @@ fn setup_stats_windows(
                         text_anchor: bevy::sprite::Anchor::CenterLeft,
                         transform: Transform::from_xyz(
                             STAT_LABEL_X,
-                            STATS_SIZE.y / 2.0 - (i * 2 + 1) as f32 * STAT_FONT_SIZE,
+                            STATS_SIZE.y / 2.0 - (i as f32 * 1.45 + 1.0) * STAT_FONT_SIZE,
                             STAT_LABEL_Z,
                         ),
                         ..default()
@@ -262,72 +367,22 @@ fn setup_stats_windows(
             StatWindow,
         ))
         .with_children(|children| {
-            // There are alway 3 phys abilities.
-            for i in 0..3 {
-                let text_sections = vec![
-                    TextSection {
-                        value: "Name:".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "\n- Damage: ".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "00.00".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "\n- Stamina Cost: ".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "00.00".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "\n- Global Cooldown: ".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                    TextSection {
-                        value: "00.00".to_string(),
-                        style: abilities_text_style.clone(),
-                    },
-                ];
-
-                children
-                    .spawn((Text2dBundle {
-                        text: Text {
-                            sections: text_sections,
-                            ..default()
-                        },
-                        text_anchor: bevy::sprite::Anchor::CenterLeft,
-                        transform: Transform::from_xyz(
-                            STAT_LABEL_X,
-                            STATS_SIZE.y / 2.0 - (((i * 5) as f32) + 2.5) * ABILITIES_FONT_SIZE,
-                            STAT_LABEL_Z,
-                        ),
-                        ..default()
-                    },))
-                    .insert(StatLabel::PhysicalAbility);
-            }
+            children
+                .spawn((Text2dBundle {
+                    text: Text::from_section("", abilities_text_style),
+                    text_anchor: bevy::sprite::Anchor::TopLeft,
+                    transform: Transform::from_xyz(
+                        STAT_LABEL_X,
+                        STATS_SIZE.y / 2.0 - 10.0,
+                        STAT_LABEL_Z,
+                    ),
+                    ..default()
+                },))
+                .insert(StatLabel::Abilities);
         });
 }
 
-fn setup_ui(
-    mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &mut Visibility,
-            &mut Transform,
-            &PopulationSize,
-            &CreatureStats,
-        ),
-        With<PlayerCreature>,
-    >,
-    textures: Res<TextureAssets>,
-) {
+fn setup_ui(mut commands: Commands, textures: Res<TextureAssets>) {
     // background
     commands.spawn((
         SpriteBundle {
@@ -338,83 +393,11 @@ fn setup_ui(
         CreatureManagerScreenItem,
     ));
 
-    let mut generations = query
-        .iter()
-        .filter(|(_, _, _, &PopulationSize(size), _)| size > 0)
-        .map(|(_, _, _, _, stats)| stats.generation)
-        .collect::<Vec<_>>();
-    generations.sort();
-    let order = generations
-        .iter()
-        .enumerate()
-        .map(|(i, g)| (g, i))
-        .collect::<HashMap<_, _>>();
-
-    for (entity, mut visibility, mut transform, &PopulationSize(count), stats) in query.iter_mut() {
-        if count == 0 {
-            continue;
-        }
-
-        *visibility = Visibility::Visible;
-
-        let i = order[&stats.generation];
-        let x = i % (GRID_SIZE.x as usize - 2);
-        let y = i / (GRID_SIZE.x as usize - 2);
-
-        let grid_pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.4);
-        let cell_size = Vec2::new(WINDOW_SIZE.x, WINDOW_SIZE.y * 3.0 / 4.0) / GRID_SIZE;
-        let pos_no_transform = cell_size * grid_pos;
-        let mut pos = pos_no_transform;
-        pos.y = WINDOW_SIZE.y - pos.y;
-        pos -= WINDOW_SIZE / 2.0;
-        transform.translation = pos.extend(CREATURES_Z);
-
-        commands.spawn((
-            ButtonBundle {
-                style: Style {
-                    width: Val::Px(CREATURE_BUTTON_SIZE.x),
-                    height: Val::Px(CREATURE_BUTTON_SIZE.y),
-                    position_type: PositionType::Absolute,
-                    left: Val::Px(pos_no_transform.x - CREATURE_BUTTON_SIZE.x / 2.0),
-                    top: Val::Px(pos_no_transform.y - CREATURE_BUTTON_SIZE.y / 2.0),
-                    border: UiRect::all(Val::Px(4.0)),
-                    ..default()
-                },
-                background_color: Color::NONE.into(),
-                border_radius: BorderRadius::all(Val::Px(9999.0)),
-                border_color: Color::NONE.into(),
-                ..default()
-            },
-            CreatureManagerScreenItem,
-            CreatureButton {
-                entity,
-                selected: false,
-            },
-        ));
-
-        commands.spawn((
-            Text2dBundle {
-                text: Text::from_section(
-                    count.to_string() + "x",
-                    TextStyle {
-                        font_size: 32.0,
-                        color: Color::BLACK,
-                        ..default()
-                    },
-                ),
-                text_anchor: bevy::sprite::Anchor::Center,
-                transform: Transform::from_translation((pos - COUNT_OFFSET).extend(0.0)),
-                ..default()
-            },
-            CreatureManagerScreenItem,
-        ));
-    }
-
     let button = create_change_state_button(
         &mut commands,
         "Continue",
         WINDOW_SIZE * Vec2::new(0.9, 0.86),
-        GameState::Battle,
+        GameState::Deployment,
     );
     commands.entity(button).insert(CreatureManagerScreenItem);
 
@@ -436,6 +419,29 @@ fn setup_ui(
         .entity(button)
         .insert((CreatureManagerScreenItem, DecButton));
 
+    for (title, x) in [
+        ("Split", 0.4),
+        ("Merge", 0.48),
+        ("Release", 0.56),
+        ("Rename", 0.64),
+    ] {
+        let button = create_small_button(
+            &mut commands,
+            title,
+            WINDOW_SIZE * Vec2::new(x, 0.85),
+            SQUAD_BUTTON_SIZE,
+            SQUAD_FONT_SIZE,
+        );
+        let mut button = commands.entity(button);
+        button.insert(CreatureManagerScreenItem);
+        match title {
+            "Split" => button.insert(SplitButton),
+            "Merge" => button.insert(MergeButton),
+            "Release" => button.insert(ReleaseButton),
+            _ => button.insert(RenameButton),
+        };
+    }
+
     let mut pos = WINDOW_SIZE * Vec2::new(0.16, 0.95);
     pos.y = WINDOW_SIZE.y - pos.y;
     pos -= WINDOW_SIZE / 2.0;
@@ -478,6 +484,152 @@ fn setup_ui(
     ));
 }
 
+/// Places the species of the shown roster page on the grid, the other species are hidden.
+fn layout_roster(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &mut Visibility,
+            &mut Transform,
+            &PopulationSize,
+            &CreatureStats,
+            &SpeciesName,
+            &Stance,
+        ),
+        With<PlayerCreature>,
+    >,
+    roster_item_query: Query<Entity, With<RosterItem>>,
+    view: Res<RosterView>,
+    selected: Res<SelectedCreatures>,
+) {
+    for entity in roster_item_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let species = view.arrange(query.iter().map(
+        |(entity, _, _, &PopulationSize(count), stats, name, _)| {
+            (entity, stats, name.0.as_str(), count)
+        },
+    ));
+    let page = view
+        .page
+        .min(species.len().saturating_sub(1) / ROSTER_PAGE_SIZE);
+    let order = species
+        .iter()
+        .skip(page * ROSTER_PAGE_SIZE)
+        .take(ROSTER_PAGE_SIZE)
+        .enumerate()
+        .map(|(i, &entity)| (entity, i))
+        .collect::<HashMap<_, _>>();
+
+    for (entity, mut visibility, mut transform, &PopulationSize(count), _, _, stance) in
+        query.iter_mut()
+    {
+        let Some(&i) = order.get(&entity) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+
+        let x = i % GRID_COLUMNS;
+        let y = i / GRID_COLUMNS;
+
+        let grid_pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.4);
+        let cell_size = Vec2::new(WINDOW_SIZE.x, WINDOW_SIZE.y * 3.0 / 4.0) / GRID_SIZE;
+        let pos_no_transform = cell_size * grid_pos + Vec2::Y * ROSTER_BAR_HEIGHT;
+        let mut pos = pos_no_transform;
+        pos.y = WINDOW_SIZE.y - pos.y;
+        pos -= WINDOW_SIZE / 2.0;
+        transform.translation = pos.extend(CREATURES_Z);
+
+        let is_selected = selected.0.contains(&entity);
+        commands.spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(CREATURE_BUTTON_SIZE.x),
+                    height: Val::Px(CREATURE_BUTTON_SIZE.y),
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(pos_no_transform.x - CREATURE_BUTTON_SIZE.x / 2.0),
+                    top: Val::Px(pos_no_transform.y - CREATURE_BUTTON_SIZE.y / 2.0),
+                    border: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                border_radius: BorderRadius::all(Val::Px(9999.0)),
+                border_color: selection_border(is_selected).into(),
+                ..default()
+            },
+            CreatureManagerScreenItem,
+            RosterItem,
+            CreatureButton {
+                entity,
+                selected: is_selected,
+            },
+        ));
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    count.to_string() + "x",
+                    TextStyle {
+                        font_size: 32.0,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                text_anchor: bevy::sprite::Anchor::Center,
+                transform: Transform::from_translation((pos - COUNT_OFFSET).extend(0.0)),
+                ..default()
+            },
+            CreatureManagerScreenItem,
+            RosterItem,
+            CountText(entity),
+        ));
+
+        // filled in by update_name_texts
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: NAME_FONT_SIZE,
+                        color: NAME_COLOR,
+                        ..default()
+                    },
+                ),
+                text_anchor: bevy::sprite::Anchor::Center,
+                transform: Transform::from_translation((pos + NAME_OFFSET).extend(0.0)),
+                ..default()
+            },
+            CreatureManagerScreenItem,
+            RosterItem,
+            NameText(entity),
+        ));
+
+        let button = create_small_button(
+            &mut commands,
+            stance.name(),
+            pos_no_transform + Vec2::Y * STANCE_BUTTON_OFFSET,
+            STANCE_BUTTON_SIZE,
+            STANCE_FONT_SIZE,
+        );
+        commands.entity(button).insert((
+            CreatureManagerScreenItem,
+            RosterItem,
+            StanceButton { entity },
+        ));
+    }
+}
+
+fn selection_border(selected: bool) -> Color {
+    match selected {
+        true => Color::WHITE.with_alpha(0.2),
+        false => Color::NONE,
+    }
+}
+
 fn partial_cleanup(
     mut commands: Commands,
     query: Query<
@@ -488,23 +640,25 @@ fn partial_cleanup(
             Without<StatLabel>,
         ),
     >,
-    mut selected_creatures_counter: ResMut<SelectedCreaturesCounter>,
+    mut selected: ResMut<SelectedCreatures>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    selected_creatures_counter.0 = 0;
+    selected.0.clear();
 }
 
 fn cleanup(
     mut commands: Commands,
     query: Query<Entity, With<CreatureManagerScreenItem>>,
-    mut selected_creatures_counter: ResMut<SelectedCreaturesCounter>,
+    mut selected: ResMut<SelectedCreatures>,
+    mut renaming: ResMut<Renaming>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
     }
-    selected_creatures_counter.0 = 0;
+    selected.0.clear();
+    *renaming = Renaming::default();
 }
 
 fn handle_inc_dec_buttons(
@@ -537,31 +691,53 @@ fn handle_creature_button(
         (&Interaction, &mut BorderColor, &mut CreatureButton),
         (With<CreatureButton>, Changed<Interaction>),
     >,
-    mut selected_creatures_counter: ResMut<SelectedCreaturesCounter>,
+    mut selected: ResMut<SelectedCreatures>,
 ) {
     for (interaction, mut border_color, mut creature_button) in creature_button_query.iter_mut() {
         if *interaction != Interaction::Pressed {
             continue;
         }
 
-        if !creature_button.selected && selected_creatures_counter.0 >= 2 {
+        if !creature_button.selected && selected.0.len() >= 2 {
             continue;
         }
 
         creature_button.selected = !creature_button.selected;
-        *border_color = if creature_button.selected {
-            selected_creatures_counter.0 += 1;
-            Color::WHITE.with_alpha(0.2).into()
+        if creature_button.selected {
+            selected.0.push(creature_button.entity);
         } else {
-            selected_creatures_counter.0 -= 1;
-            Color::NONE.into()
-        };
+            selected
+                .0
+                .retain(|&entity| entity != creature_button.entity);
+        }
+        *border_color = selection_border(creature_button.selected).into();
+    }
+}
+
+fn handle_stance_button(
+    stance_button_query: Query<(&Interaction, &StanceButton, &Children), Changed<Interaction>>,
+    mut stance_query: Query<&mut Stance>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, stance_button, children) in stance_button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let mut stance = stance_query.get_mut(stance_button.entity).unwrap();
+        *stance = stance.next();
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = stance.name().to_string();
+            }
+        }
     }
 }
 
 fn handle_combine_button(
     combine_button_query: Query<&Interaction, (With<CombineButton>, Changed<Interaction>)>,
-    creature_buttons_query: Query<&CreatureButton>,
+    selected: Res<SelectedCreatures>,
     population_text_query: Query<&Text, With<PopulationText>>,
     mut creature_query: Query<&mut PopulationSize>,
     mut ew_combine_button_pressed: EventWriter<CombineButtonPressedEvent>,
@@ -574,12 +750,7 @@ fn handle_combine_button(
         .value
         .parse::<u32>()
         .unwrap();
-    let entities = creature_buttons_query
-        .iter()
-        .filter(|button| button.selected)
-        .map(|button| button.entity)
-        .collect::<Vec<_>>();
-
+    let entities = &selected.0;
     if entities.len() != 2 {
         return;
     }
@@ -604,93 +775,297 @@ fn handle_combine_button(
     });
 }
 
+/// Dismisses the selected species, as long as another species is left to fight.
+fn handle_release_button(
+    release_button_query: Query<&Interaction, (With<ReleaseButton>, Changed<Interaction>)>,
+    mut selected: ResMut<SelectedCreatures>,
+    mut creature_query: Query<
+        (Entity, &mut PopulationSize, Option<&Deployment>),
+        With<PlayerCreature>,
+    >,
+    mut ew_population_changed: EventWriter<PopulationChangedEvent>,
+) {
+    if release_button_query.is_empty() || *release_button_query.single() != Interaction::Pressed {
+        return;
+    }
+
+    let &[entity] = selected.0.as_slice() else {
+        return;
+    };
+    let fielded = creature_query
+        .iter()
+        .filter(|(other, size, deployment)| {
+            *other != entity && size.0 > 0 && !Deployment::is_benched(*deployment)
+        })
+        .count();
+    if fielded == 0 {
+        return;
+    }
+
+    let (_, mut size, _) = creature_query.get_mut(entity).unwrap();
+    size.0 = 0;
+    selected.0.clear();
+    ew_population_changed.send(PopulationChangedEvent);
+}
+
+/// Moves the selected population of the selected species into a new squad of the same species.
+fn handle_split_button(
+    mut commands: Commands,
+    split_button_query: Query<&Interaction, (With<SplitButton>, Changed<Interaction>)>,
+    (selected, population_text_query): (Res<SelectedCreatures>, Query<&Text, With<PopulationText>>),
+    mut creature_query: Query<
+        (
+            &CreatureStats,
+            &mut PopulationSize,
+            &Stance,
+            &Children,
+            Option<&mut Deployment>,
+            &SpeciesName,
+        ),
+        With<PlayerCreature>,
+    >,
+    sprite_query: Query<&Sprite, With<BodyPart>>,
+    textures: Res<TextureAssets>,
+    mut ew_population_changed: EventWriter<PopulationChangedEvent>,
+) {
+    if split_button_query.is_empty() || *split_button_query.single() != Interaction::Pressed {
+        return;
+    }
+
+    let population = population_text_query.single().sections[0]
+        .value
+        .parse::<u32>()
+        .unwrap();
+    let &[entity] = selected.0.as_slice() else {
+        return;
+    };
+
+    let (stats, mut size, &stance, children, mut deployment, name) =
+        creature_query.get_mut(entity).unwrap();
+    if size.0 <= population {
+        return;
+    }
+    size.0 -= population;
+
+    let body_parts = children
+        .iter()
+        .filter_map(|&child| sprite_query.get(child).ok())
+        .map(body_part_variant)
+        .collect::<Vec<_>>();
+    let squad = spawn_creature(
+        &mut commands,
+        &textures,
+        stats.clone(),
+        PopulationSize(population),
+        &body_parts,
+    );
+    commands
+        .entity(squad)
+        .insert((PlayerCreature, stance, name.clone()));
+    // the chosen deployment is split like the population, so both squads deploy as many as before
+    if let Some(deployment) = deployment.as_deref_mut() {
+        let (kept, split_off) = deployment.split(size.0);
+        *deployment = kept;
+        commands.entity(squad).insert(split_off);
+    }
+
+    ew_population_changed.send(PopulationChangedEvent);
+}
+
+/// Moves the whole population of the second selected squad into the first one, both squads have
+/// to be of the same species.
+fn handle_merge_button(
+    merge_button_query: Query<&Interaction, (With<MergeButton>, Changed<Interaction>)>,
+    mut selected: ResMut<SelectedCreatures>,
+    mut creature_query: Query<(&CreatureStats, &mut PopulationSize), With<PlayerCreature>>,
+    mut ew_population_changed: EventWriter<PopulationChangedEvent>,
+) {
+    if merge_button_query.is_empty() || *merge_button_query.single() != Interaction::Pressed {
+        return;
+    }
+
+    let &[target, source] = selected.0.as_slice() else {
+        return;
+    };
+    let Ok([(target_stats, mut target_size), (source_stats, mut source_size)]) =
+        creature_query.get_many_mut([target, source])
+    else {
+        return;
+    };
+    if target_stats.generation != source_stats.generation {
+        return;
+    }
+
+    target_size.0 += source_size.0;
+    source_size.0 = 0;
+    selected.0.retain(|&entity| entity != source);
+    ew_population_changed.send(PopulationChangedEvent);
+}
+
+/// Starts typing a new name for the selected species.
+fn handle_rename_button(
+    rename_button_query: Query<&Interaction, (With<RenameButton>, Changed<Interaction>)>,
+    selected: Res<SelectedCreatures>,
+    name_query: Query<&SpeciesName>,
+    mut renaming: ResMut<Renaming>,
+    mut view: ResMut<RosterView>,
+) {
+    if rename_button_query.is_empty() || *rename_button_query.single() != Interaction::Pressed {
+        return;
+    }
+
+    let &[entity] = selected.0.as_slice() else {
+        return;
+    };
+    let Ok(name) = name_query.get(entity) else {
+        return;
+    };
+
+    renaming.species = Some(entity);
+    renaming.text.clone_from(&name.0);
+    if view.is_typing() {
+        view.stop_typing();
+    }
+}
+
+/// Enter keeps the typed name, Escape keeps the old one. Typing into the roster search stops the
+/// renaming.
+fn handle_rename_input(
+    mut er_keyboard_input: EventReader<KeyboardInput>,
+    mut renaming: ResMut<Renaming>,
+    mut name_query: Query<&mut SpeciesName>,
+    mut view: ResMut<RosterView>,
+) {
+    let Some(entity) = renaming.species else {
+        er_keyboard_input.clear();
+        return;
+    };
+    if view.is_typing() {
+        *renaming = Renaming::default();
+        return;
+    }
+
+    for event in er_keyboard_input.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let length = renaming.text.chars().count();
+        match &event.logical_key {
+            Key::Character(character) if length + character.chars().count() <= MAX_NAME_LENGTH => {
+                renaming.text.push_str(character);
+            }
+            Key::Space if length < MAX_NAME_LENGTH => renaming.text.push(' '),
+            Key::Backspace => {
+                renaming.text.pop();
+            }
+            Key::Enter => {
+                let name = renaming.text.trim();
+                if !name.is_empty() {
+                    if let Ok(mut species_name) = name_query.get_mut(entity) {
+                        species_name.0 = name.to_string();
+                        // the search may match the new name
+                        view.set_changed();
+                    }
+                }
+                *renaming = Renaming::default();
+                return;
+            }
+            Key::Escape => {
+                *renaming = Renaming::default();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_name_texts(
+    mut text_query: Query<(&NameText, &mut Text)>,
+    name_query: Query<&SpeciesName>,
+    renaming: Res<Renaming>,
+) {
+    for (&NameText(entity), mut text) in text_query.iter_mut() {
+        let (value, color) = match renaming.species == Some(entity) {
+            true => (format!("{}_", renaming.text), RENAMING_COLOR),
+            false => match name_query.get(entity) {
+                Ok(name) => (name.0.clone(), NAME_COLOR),
+                Err(_) => continue,
+            },
+        };
+
+        if text.sections[0].value != value || text.sections[0].style.color != color {
+            text.sections[0].value = value;
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
 fn combine_creatures(
     mut commands: Commands,
-    creature_query: Query<(&CreatureStats, &Children)>,
-    sprite_query: Query<(&Sprite, &Handle<Image>, &Transform), With<BodyPart>>,
-    mut combination_rng: ResMut<CombinationRng>,
-    mut creature_generation: ResMut<CreatureGeneration>,
+    creature_query: Query<(&CreatureStats, &Children, &SpeciesName)>,
+    sprite_query: Query<&Sprite, With<BodyPart>>,
+    (textures, balance, game_settings): (Res<TextureAssets>, Res<Balance>, Res<GameSettings>),
+    (mut combination_rng, mut creature_generation, mut lineage): (
+        ResMut<CombinationRng>,
+        ResMut<CreatureGeneration>,
+        ResMut<Lineage>,
+    ),
     mut ew_creature_created: EventWriter<CreatureCombinedEvent>,
     mut er_combine_button_pressed: EventReader<CombineButtonPressedEvent>,
 ) {
     for event in er_combine_button_pressed.read() {
-        let (parent1, children1) = creature_query.get(event.parent1).unwrap();
-        let (parent2, children2) = creature_query.get(event.parent2).unwrap();
-
-        let mut children_stats = CreatureStats {
-            hp: if combination_rng.0.gen_bool(0.5) {
-                parent1.hp
-            } else {
-                parent2.hp
-            },
-            movement_speed: if combination_rng.0.gen_bool(0.5) {
-                parent1.movement_speed
-            } else {
-                parent2.movement_speed
-            },
-            stamina: if combination_rng.0.gen_bool(0.5) {
-                parent1.stamina
-            } else {
-                parent2.stamina
-            },
-            stamina_regen: if combination_rng.0.gen_bool(0.5) {
-                parent1.stamina_regen
-            } else {
-                parent2.stamina_regen
-            },
-            physical_abilities: Vec::new(),
-            generation: creature_generation.0,
-        };
+        let (parent1, children1, name1) = creature_query.get(event.parent1).unwrap();
+        let (parent2, children2, name2) = creature_query.get(event.parent2).unwrap();
+
+        let mut children_stats = combine_stats(
+            game_settings.inheritance.model(),
+            [parent1, parent2],
+            &balance,
+            &mut combination_rng.0,
+            creature_generation.0,
+        );
         creature_generation.0 += 1;
-        // There are always 3 physical abilities.
-        for i in 0..3 {
-            if combination_rng.0.gen_bool(0.5) {
-                children_stats
-                    .physical_abilities
-                    .push(parent1.physical_abilities[i].clone());
-            } else {
-                children_stats
-                    .physical_abilities
-                    .push(parent2.physical_abilities[i].clone());
-            }
-        }
-        children_stats.mutate(&mut combination_rng.0);
+        let inherited_stats = children_stats.clone();
+        children_stats.mutate(&balance, &mut combination_rng.0);
+
+        let parent_body_parts = [children1, children2].map(|children| {
+            children
+                .iter()
+                .filter_map(|&child| sprite_query.get(child).ok())
+                .map(body_part_variant)
+                .collect::<Vec<_>>()
+        });
+        let body_parts = inherit_appearance(
+            &balance,
+            [
+                (parent1, &parent_body_parts[0]),
+                (parent2, &parent_body_parts[1]),
+            ],
+            &inherited_stats,
+            &children_stats,
+            &mut combination_rng.0,
+        );
 
-        let mut entity = commands.spawn((
-            SpriteBundle {
-                visibility: Visibility::Hidden,
-                transform: Transform::from_scale(Vec2::splat(CREATURE_SCALE).extend(CREATURE_Z)),
-                ..default()
-            },
+        let entity = spawn_creature(
+            &mut commands,
+            &textures,
+            children_stats.clone(),
             // Child are born in pairs.
             PopulationSize(event.population * 2),
+            &body_parts,
+        );
+        commands.entity(entity).insert((
             PlayerCreature,
+            SpeciesName(combine_names(&name1.0, &name2.0)),
         ));
-        entity.insert(children_stats);
-
-        let children = children1.iter().zip(children2).map(|(child1, child2)| {
-            if combination_rng.0.gen_bool(0.5) {
-                child1
-            } else {
-                child2
-            }
-        });
-        for &child in children {
-            let (sprite, texture, transform) = sprite_query.get(child).unwrap();
-
-            entity.with_children(|children| {
-                children.spawn((
-                    SpriteBundle {
-                        sprite: sprite.clone(),
-                        texture: texture.clone(),
-                        transform: *transform,
-                        ..default()
-                    },
-                    BodyPart,
-                ));
-            });
-        }
 
+        lineage.record_combination(
+            [parent1, parent2],
+            &inherited_stats,
+            &children_stats,
+            body_parts,
+        );
         ew_creature_created.send(CreatureCombinedEvent);
     }
 }
@@ -699,7 +1074,7 @@ fn show_stats(
     creature_button_query: Query<(&Interaction, &CreatureButton)>,
     mut stat_window_query: Query<&mut Visibility, With<StatWindow>>,
     mut stat_label_query: Query<(&mut Text, &StatLabel)>,
-    creature_query: Query<&CreatureStats, With<PlayerCreature>>,
+    creature_query: Query<(&CreatureStats, &PopulationSize, &SpeciesName), With<PlayerCreature>>,
 ) {
     let mut hovered_creature = None;
     for (interaction, creature_button) in creature_button_query.iter() {
@@ -719,8 +1094,24 @@ fn show_stats(
     if hovered_creature.is_none() {
         return;
     }
-    let stats = creature_query.get(hovered_creature.unwrap()).unwrap();
+    // the hovered species can be released or merged away in the same frame
+    let Ok((stats, &PopulationSize(population), name)) =
+        creature_query.get(hovered_creature.unwrap())
+    else {
+        return;
+    };
+
+    let (mut text, _) = stat_label_query
+        .iter_mut()
+        .find(|&(_, &label)| label == StatLabel::Name)
+        .unwrap();
+    text.sections[1].value.clone_from(&name.0);
 
+    let (mut text, _) = stat_label_query
+        .iter_mut()
+        .find(|&(_, &label)| label == StatLabel::Archetype)
+        .unwrap();
+    text.sections[1].value = format!("{}, size {:.2}", stats.archetype.name(), stats.size);
     let (mut text, _) = stat_label_query
         .iter_mut()
         .find(|&(_, &label)| label == StatLabel::HP)
@@ -742,24 +1133,54 @@ fn show_stats(
         .unwrap();
     text.sections[1].value = format!("{:.2}", stats.stamina_regen);
 
-    let phys_ability_texts = stat_label_query
+    let (mut text, _) = stat_label_query
         .iter_mut()
-        .filter(|&(_, &label)| label == StatLabel::PhysicalAbility)
-        .map(|(text, _)| text);
-    for (ability_index, mut text) in phys_ability_texts.enumerate() {
-        assert!(ability_index < 3);
-
-        text.sections[0].value = stats.physical_abilities[ability_index].name.to_string();
-        text.sections[2].value = format!("{:.2}", stats.physical_abilities[ability_index].damage);
-        text.sections[4].value = format!(
-            "{:.2}",
-            stats.physical_abilities[ability_index].stamina_cost
-        );
-        text.sections[6].value = format!(
-            "{:.2}",
-            stats.physical_abilities[ability_index].global_cooldown
-        );
-    }
+        .find(|&(_, &label)| label == StatLabel::Mana)
+        .unwrap();
+    text.sections[1].value = format!("{:.2}", stats.mana);
+    let (mut text, _) = stat_label_query
+        .iter_mut()
+        .find(|&(_, &label)| label == StatLabel::ManaRegen)
+        .unwrap();
+    text.sections[1].value = format!("{:.2}", stats.mana_regen);
+    let (mut text, _) = stat_label_query
+        .iter_mut()
+        .find(|&(_, &label)| label == StatLabel::Population)
+        .unwrap();
+    text.sections[1].value = population.to_string();
+
+    let physical_abilities = stats.physical_abilities.iter().map(|ability| {
+        let range = if ability.is_ranged() {
+            format!(", range {:.0}", ability.range)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{} (cooldown {:.2}{range})\n- Damage: {:.2}, Stamina: {:.2}",
+            ability.name, ability.global_cooldown, ability.damage, ability.stamina_cost
+        )
+    });
+    let magic_abilities = stats.magic_abilities.iter().map(|ability| {
+        format!(
+            "{} (cooldown {:.2})\n- {}: {:.2}\n- Mana: {:.2}, Range: {:.2}",
+            ability.kind.name(),
+            ability.cooldown,
+            ability.kind.power_name(),
+            ability.power,
+            ability.mana_cost,
+            ability.range
+        )
+    });
+
+    let (mut text, _) = stat_label_query
+        .iter_mut()
+        .find(|&(_, &label)| label == StatLabel::Abilities)
+        .unwrap();
+    text.sections[0].value = physical_abilities
+        .chain(magic_abilities)
+        .collect::<Vec<_>>()
+        .join("\n");
 }
 
 fn play_combine_sound(
@@ -0,0 +1,419 @@
+use bevy::prelude::*;
+
+use crate::{
+    balance::Balance,
+    creature::{
+        body_part_images, body_part_variant, BodyPart, BodyPartAtlas, CreatureStats, PopulationSize,
+    },
+    deployment::{assign_deployment, Deployed, Deployment},
+    loading::TextureAssets,
+    naming::SpeciesName,
+    rounds::Difficulty,
+    ui::{create_basic_button, create_change_state_button},
+    GameState, WINDOW_SIZE,
+};
+
+use super::new_creature_screen::PlayerCreature;
+
+const BACKGROUND_Z: f32 = -20.0;
+const PANEL_WIDTH: f32 = 1200.0;
+const ENTRY_WIDTH: f32 = 380.0;
+const THUMBNAIL_SIZE: f32 = 48.0;
+const DEPLOY_BUTTON_SIZE: f32 = 32.0;
+const COUNT_WIDTH: f32 = 80.0;
+/// Change of a deploy button while shift is held.
+const SHIFT_STEP: i32 = 10;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const RESERVE_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+pub struct DeploymentScreenPlugin;
+
+impl Plugin for DeploymentScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Deployment),
+            setup.after(assign_deployment),
+        )
+        .add_systems(
+            Update,
+            (
+                handle_deploy_buttons,
+                update_deployment_texts,
+                handle_next_button,
+            )
+                .chain()
+                .run_if(in_state(GameState::Deployment)),
+        )
+        .add_systems(OnExit(GameState::Deployment), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct DeploymentScreenItem;
+
+/// Changes the deployment of a species.
+#[derive(Component)]
+struct DeployButton {
+    entity: Entity,
+    change: i32,
+}
+
+/// Deployment and population of a species.
+#[derive(Component)]
+struct DeploymentText(Entity);
+
+/// Deployed creatures of all species and the cap of the round.
+#[derive(Component)]
+struct TotalText;
+
+#[derive(Component)]
+struct NextButton;
+
+fn setup(
+    mut commands: Commands,
+    creature_query: Query<
+        (
+            Entity,
+            &CreatureStats,
+            &PopulationSize,
+            &Children,
+            &SpeciesName,
+        ),
+        With<PlayerCreature>,
+    >,
+    body_part_query: Query<&Sprite, With<BodyPart>>,
+    (textures, atlas, balance): (Res<TextureAssets>, Res<BodyPartAtlas>, Res<Balance>),
+) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.menu_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        DeploymentScreenItem,
+    ));
+
+    let mut species = creature_query
+        .iter()
+        .filter(|(_, _, &PopulationSize(size), _, _)| size > 0)
+        .collect::<Vec<_>>();
+    species.sort_by_key(|(_, stats, _, _, _)| stats.generation);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::top(Val::Px(32.0)),
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            DeploymentScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Deploy your legion",
+                TextStyle {
+                    font_size: 64.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 32.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                ),
+                TotalText,
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Reserves are safe from the battle and breed x{} instead of x{}, \
+                     shift changes by {SHIFT_STEP}",
+                    balance.deployment.reserve_breeding_multiplier, balance.breeding_multiplier
+                ),
+                TextStyle {
+                    font_size: 18.0,
+                    color: DETAIL_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(PANEL_WIDTH),
+                        max_height: Val::Px(WINDOW_SIZE.y * 0.6),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        align_content: AlignContent::FlexStart,
+                        row_gap: Val::Px(8.0),
+                        column_gap: Val::Px(16.0),
+                        padding: UiRect::all(Val::Px(16.0)),
+                        margin: UiRect::top(Val::Px(8.0)),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    background_color: Color::BLACK.with_alpha(0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (entity, stats, _, children, name) in species {
+                        let body_parts = children
+                            .iter()
+                            .filter_map(|&child| body_part_query.get(child).ok())
+                            .map(body_part_variant)
+                            .collect::<Vec<_>>();
+                        let thumbnail = body_part_images(
+                            &textures,
+                            &atlas,
+                            &body_parts,
+                            &stats.hues,
+                            THUMBNAIL_SIZE,
+                        );
+
+                        spawn_species_row(parent, entity, stats, name, thumbnail);
+                    }
+                });
+        });
+
+    let button = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.35, 0.9),
+        GameState::CreatureManager,
+    );
+    commands.entity(button).insert(DeploymentScreenItem);
+    let button = create_basic_button(&mut commands, "Next", WINDOW_SIZE * Vec2::new(0.65, 0.9));
+    commands
+        .entity(button)
+        .insert((DeploymentScreenItem, NextButton));
+}
+
+fn spawn_species_row(
+    parent: &mut ChildBuilder,
+    entity: Entity,
+    stats: &CreatureStats,
+    name: &SpeciesName,
+    thumbnail: Vec<(ImageBundle, TextureAtlas)>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(ENTRY_WIDTH),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(THUMBNAIL_SIZE),
+                        height: Val::Px(THUMBNAIL_SIZE),
+                        flex_shrink: 0.0,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for image in thumbnail {
+                        parent.spawn(image);
+                    }
+                });
+
+            parent.spawn(
+                TextBundle::from_sections([
+                    TextSection::new(
+                        name.0.clone(),
+                        TextStyle {
+                            font_size: 20.0,
+                            color: TEXT_COLOR,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        format!("\n#{} {}", stats.generation, stats.archetype.name()),
+                        TextStyle {
+                            font_size: 14.0,
+                            color: DETAIL_COLOR,
+                            ..default()
+                        },
+                    ),
+                ])
+                .with_style(Style {
+                    flex_grow: 1.0,
+                    ..default()
+                }),
+            );
+
+            spawn_deploy_button(parent, entity, -1);
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center)
+                .with_style(Style {
+                    width: Val::Px(COUNT_WIDTH),
+                    ..default()
+                }),
+                DeploymentText(entity),
+            ));
+            spawn_deploy_button(parent, entity, 1);
+        });
+}
+
+fn spawn_deploy_button(parent: &mut ChildBuilder, entity: Entity, change: i32) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(DEPLOY_BUTTON_SIZE),
+                    height: Val::Px(DEPLOY_BUTTON_SIZE),
+                    flex_shrink: 0.0,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                ..default()
+            },
+            DeployButton { entity, change },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                match change < 0 {
+                    true => "-",
+                    false => "+",
+                },
+                TextStyle {
+                    font_size: 24.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Deploys more or fewer creatures of a species, the cap of the round is never exceeded. The new
+/// count is kept as the choice of the player for the following rounds.
+fn handle_deploy_buttons(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &DeployButton), Changed<Interaction>>,
+    mut creature_query: Query<(&PopulationSize, &mut Deployed), With<PlayerCreature>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    difficulty: Res<Difficulty>,
+    balance: Res<Balance>,
+) {
+    let step = match keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        true => SHIFT_STEP,
+        false => 1,
+    };
+    let cap = difficulty.deployment_cap(&balance.deployment);
+
+    for (&interaction, button) in button_query.iter() {
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let total = creature_query
+            .iter()
+            .map(|(_, &Deployed(count))| count)
+            .sum::<u32>();
+        let Ok((&PopulationSize(size), mut deployed)) = creature_query.get_mut(button.entity)
+        else {
+            continue;
+        };
+
+        let max = size.min(deployed.0 + cap.saturating_sub(total));
+        deployed.0 = deployed
+            .0
+            .saturating_add_signed(button.change * step)
+            .min(max);
+        commands
+            .entity(button.entity)
+            .insert(Deployment(deployed.0));
+    }
+}
+
+fn update_deployment_texts(
+    mut count_query: Query<(&DeploymentText, &mut Text), Without<TotalText>>,
+    mut total_query: Query<&mut Text, With<TotalText>>,
+    creature_query: Query<(&PopulationSize, &Deployed), With<PlayerCreature>>,
+    difficulty: Res<Difficulty>,
+    balance: Res<Balance>,
+) {
+    for (&DeploymentText(entity), mut text) in count_query.iter_mut() {
+        let Ok((&PopulationSize(size), &Deployed(count))) = creature_query.get(entity) else {
+            continue;
+        };
+        let value = format!("{count} / {size}");
+        let color = match count {
+            0 => RESERVE_COLOR,
+            _ => TEXT_COLOR,
+        };
+
+        if text.sections[0].value != value || text.sections[0].style.color != color {
+            text.sections[0].value = value;
+            text.sections[0].style.color = color;
+        }
+    }
+
+    let total = creature_query
+        .iter()
+        .map(|(_, &Deployed(count))| count)
+        .sum::<u32>();
+    let value = format!(
+        "{total} / {} creatures deployed",
+        difficulty.deployment_cap(&balance.deployment)
+    );
+    for mut text in total_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+/// Moves on to the placement of the species once at least one creature is deployed.
+fn handle_next_button(
+    next_button_query: Query<&Interaction, (With<NextButton>, Changed<Interaction>)>,
+    creature_query: Query<&Deployed, With<PlayerCreature>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = next_button_query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Pressed);
+    if !pressed && !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    if creature_query.iter().any(|&Deployed(count)| count > 0) {
+        next_state.set(GameState::Placement);
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<DeploymentScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -8,22 +8,27 @@ use crate::{
     audio::SOUND_EFFECTS_GLOBAL_VOLUME,
     creature::CreatureStats,
     loading::AudioAssets,
+    records::{record_run, RunHistory, RunRecord},
     rounds::{Difficulty, Round},
+    seed::RunSeed,
     ui::create_change_state_button,
     GameState, WINDOW_SIZE,
 };
 
+/// Species listed on the game over screen, the rest is only counted.
+const MAX_LISTED_SPECIES: usize = 10;
+
 pub struct GameOverScreenPlugin;
 
 impl Plugin for GameOverScreenPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             OnEnter(GameState::GameOver(GameResult::Victory)),
-            (setup, trigger_game_ended_event),
+            (setup.after(record_run), trigger_game_ended_event),
         )
         .add_systems(
             OnEnter(GameState::GameOver(GameResult::Defeat)),
-            (setup, trigger_game_ended_event),
+            (setup.after(record_run), trigger_game_ended_event),
         )
         .add_systems(OnExit(GameState::GameOver(GameResult::Victory)), cleanup)
         .add_systems(OnExit(GameState::GameOver(GameResult::Defeat)), cleanup);
@@ -43,6 +48,8 @@ fn setup(
     audio: Res<Audio>,
     audio_assets: Res<AudioAssets>,
     game_statistics: Res<GameStatistics>,
+    run_seed: Res<RunSeed>,
+    history: Res<RunHistory>,
 ) {
     commands.spawn((
         Text2dBundle {
@@ -143,6 +150,49 @@ fn setup(
         GameOverScreenItem,
     ));
 
+    if let Some(run) = history.last_run() {
+        spawn_legion_list(&mut commands, run);
+    }
+
+    if history.last_run_is_record() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "New Record!",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::linear_rgb(1.0, 0.8, 0.0),
+                        ..default()
+                    },
+                ),
+                text_anchor: bevy::sprite::Anchor::Center,
+                transform: Transform::from_translation(
+                    (WINDOW_SIZE * Vec2::new(0.0, -0.35)).extend(0.0),
+                ),
+                ..default()
+            },
+            GameOverScreenItem,
+        ));
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                format!("seed: {}", run_seed.0),
+                TextStyle {
+                    font_size: 24.0,
+                    ..default()
+                },
+            ),
+            text_anchor: bevy::sprite::Anchor::Center,
+            transform: Transform::from_translation(
+                (WINDOW_SIZE * Vec2::new(0.0, -0.475)).extend(0.0),
+            ),
+            ..default()
+        },
+        GameOverScreenItem,
+    ));
+
     let entity = create_change_state_button(
         &mut commands,
         "Main Menu",
@@ -152,6 +202,41 @@ fn setup(
     commands.entity(entity).insert(GameOverScreenItem);
 }
 
+/// Names of the species fielded in the last battle, the largest first.
+fn spawn_legion_list(commands: &mut Commands, run: &RunRecord) {
+    let mut legion = run.legion.iter().collect::<Vec<_>>();
+    legion.sort_by_key(|species| std::cmp::Reverse(species.population));
+
+    let mut text = String::from("Your legion");
+    for species in legion.iter().take(MAX_LISTED_SPECIES) {
+        text += &format!(
+            "\n{} #{} {}x",
+            species.name, species.generation, species.population
+        );
+    }
+    if legion.len() > MAX_LISTED_SPECIES {
+        text += &format!("\n...and {} more", legion.len() - MAX_LISTED_SPECIES);
+    }
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    font_size: 24.0,
+                    ..default()
+                },
+            ),
+            text_anchor: bevy::sprite::Anchor::TopLeft,
+            transform: Transform::from_translation(
+                (WINDOW_SIZE * Vec2::new(-0.47, 0.1)).extend(0.0),
+            ),
+            ..default()
+        },
+        GameOverScreenItem,
+    ));
+}
+
 fn cleanup(
     mut commands: Commands,
     query: Query<Entity, With<GameOverScreenItem>>,
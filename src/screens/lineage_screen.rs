@@ -0,0 +1,439 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use crate::{
+    creature::{body_part_images, BodyPartAtlas, CreatureStats},
+    lineage::{Lineage, SpeciesLineage, TraitOrigin},
+    loading::TextureAssets,
+    ui::{create_basic_button, create_small_button},
+    GameState, WINDOW_SIZE,
+};
+
+use super::{creature_manager_screen::SelectedCreatures, new_creature_screen::PlayerCreature};
+
+/// Generations shown at once, older ancestors are reached by clicking on a shown one.
+const TREE_DEPTH: usize = 3;
+const TREE_WIDTH: f32 = 720.0;
+const THUMBNAIL_SIZES: [f32; TREE_DEPTH] = [96.0, 72.0, 56.0];
+const TRAITS_WIDTH: f32 = 340.0;
+const PANEL_Z_INDEX: i32 = 10;
+const CLOSE_BUTTON_SIZE: Vec2 = Vec2::new(160.0, 48.0);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const PARENT_COLORS: [Color; 2] = [Color::srgb(0.4, 0.7, 1.0), Color::srgb(1.0, 0.5, 0.4)];
+const MUTATED_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+const CARD_HOVERED_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+
+pub struct LineageScreenPlugin;
+
+impl Plugin for LineageScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LineageView>()
+            .add_systems(OnEnter(GameState::CreatureManager), create_lineage_button)
+            .add_systems(
+                Update,
+                (
+                    handle_lineage_button,
+                    handle_ancestor_buttons,
+                    handle_close_button,
+                    handle_lineage_keys,
+                    update_lineage_panel,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::CreatureManager)),
+            )
+            .add_systems(OnExit(GameState::CreatureManager), cleanup);
+    }
+}
+
+/// Species whose family tree is shown, the panel is closed when there is none.
+#[derive(Resource, Default)]
+struct LineageView {
+    root: Option<u64>,
+}
+
+#[derive(Component)]
+struct LineageScreenItem;
+
+#[derive(Component)]
+struct LineageButton;
+
+#[derive(Component)]
+struct LineagePanel;
+
+#[derive(Component)]
+struct AncestorButton(u64);
+
+#[derive(Component)]
+struct CloseLineageButton;
+
+fn create_lineage_button(mut commands: Commands) {
+    let button = create_basic_button(&mut commands, "Lineage", WINDOW_SIZE * Vec2::new(0.7, 0.86));
+    commands
+        .entity(button)
+        .insert((LineageScreenItem, LineageButton));
+}
+
+/// Opens the tree of the selected species, or of the newest one when none is selected.
+fn handle_lineage_button(
+    lineage_button_query: Query<&Interaction, (With<LineageButton>, Changed<Interaction>)>,
+    selected: Res<SelectedCreatures>,
+    creature_query: Query<&CreatureStats, With<PlayerCreature>>,
+    mut view: ResMut<LineageView>,
+) {
+    if !lineage_button_query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Pressed)
+    {
+        return;
+    }
+
+    let selected = selected
+        .0
+        .iter()
+        .find_map(|&entity| creature_query.get(entity).ok());
+    let shown = selected.or_else(|| creature_query.iter().max_by_key(|stats| stats.generation));
+
+    view.root = shown.map(|stats| stats.generation);
+}
+
+fn handle_ancestor_buttons(
+    mut query: Query<(&Interaction, &AncestorButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut view: ResMut<LineageView>,
+) {
+    for (interaction, ancestor, mut color) in query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => view.root = Some(ancestor.0),
+            Interaction::Hovered => *color = CARD_HOVERED_COLOR.into(),
+            Interaction::None => *color = Color::NONE.into(),
+        }
+    }
+}
+
+fn handle_close_button(
+    query: Query<&Interaction, (With<CloseLineageButton>, Changed<Interaction>)>,
+    mut view: ResMut<LineageView>,
+) {
+    if query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Pressed)
+    {
+        view.root = None;
+    }
+}
+
+fn handle_lineage_keys(keys: Res<ButtonInput<KeyCode>>, mut view: ResMut<LineageView>) {
+    if keys.just_pressed(KeyCode::Escape) && view.root.is_some() {
+        view.root = None;
+    }
+}
+
+fn update_lineage_panel(
+    mut commands: Commands,
+    view: Res<LineageView>,
+    panel_query: Query<Entity, With<LineagePanel>>,
+    lineage: Res<Lineage>,
+    textures: Res<TextureAssets>,
+    atlas: Res<BodyPartAtlas>,
+) {
+    if !view.is_changed() {
+        return;
+    }
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some(root) = view.root else {
+        return;
+    };
+
+    // every level of the tree has a slot for both parents of every species above it
+    let mut levels = vec![vec![Some(root)]];
+    while levels.len() < TREE_DEPTH {
+        let next = levels
+            .last()
+            .unwrap()
+            .iter()
+            .flat_map(|&generation| {
+                match generation.and_then(|generation| lineage.get(generation)?.parents) {
+                    Some([parent1, parent2]) => [Some(parent1), Some(parent2)],
+                    None => [None, None],
+                }
+            })
+            .collect::<Vec<_>>();
+        if next.iter().all(Option::is_none) {
+            break;
+        }
+        levels.push(next);
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(0.85).into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(PANEL_Z_INDEX),
+                ..default()
+            },
+            LineagePanel,
+            LineageScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("Lineage of species #{root}"),
+                TextStyle {
+                    font_size: 48.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(32.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(TREE_WIDTH),
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(12.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for (depth, level) in levels.iter().enumerate() {
+                                spawn_tree_level(parent, &lineage, &textures, &atlas, depth, level);
+                            }
+                        });
+
+                    spawn_traits(parent, &lineage, root);
+                });
+
+            parent.spawn(TextBundle::from_section(
+                "Click on an ancestor to follow its line, Esc to close",
+                TextStyle {
+                    font_size: 20.0,
+                    color: DETAIL_COLOR,
+                    ..default()
+                },
+            ));
+        });
+
+    let button = create_small_button(
+        &mut commands,
+        "Close",
+        WINDOW_SIZE * Vec2::new(0.5, 0.93),
+        CLOSE_BUTTON_SIZE,
+        24.0,
+    );
+    commands.entity(button).insert((
+        ZIndex::Global(PANEL_Z_INDEX + 1),
+        CloseLineageButton,
+        LineagePanel,
+        LineageScreenItem,
+    ));
+}
+
+fn spawn_tree_level(
+    parent: &mut ChildBuilder,
+    lineage: &Lineage,
+    textures: &TextureAssets,
+    atlas: &BodyPartAtlas,
+    depth: usize,
+    level: &[Option<u64>],
+) {
+    let slot_width = TREE_WIDTH / level.len() as f32;
+    let thumbnail_size = THUMBNAIL_SIZES[depth];
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (i, &generation) in level.iter().enumerate() {
+                let Some((generation, species)) =
+                    generation.and_then(|generation| Some((generation, lineage.get(generation)?)))
+                else {
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(slot_width),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                    continue;
+                };
+
+                let mut slot = parent.spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(slot_width),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::vertical(Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    // the parents of the shown species are framed in the colors of the traits
+                    border_color: match depth {
+                        1 => PARENT_COLORS[i].into(),
+                        _ => Color::NONE.into(),
+                    },
+                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                    ..default()
+                });
+                if depth > 0 {
+                    slot.insert(AncestorButton(generation));
+                }
+
+                slot.with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(thumbnail_size),
+                                height: Val::Px(thumbnail_size),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for image in body_part_images(
+                                textures,
+                                atlas,
+                                &species.body_parts,
+                                &species.hues,
+                                thumbnail_size,
+                            ) {
+                                parent.spawn(image);
+                            }
+                        });
+
+                    let origin = match species.parents {
+                        Some([parent1, parent2]) => format!("from #{parent1} and #{parent2}"),
+                        None => "from the wild".to_string(),
+                    };
+                    parent.spawn(TextBundle::from_sections([
+                        TextSection::new(
+                            format!("#{generation} {}", species.archetype.name()),
+                            TextStyle {
+                                font_size: 20.0,
+                                color: TEXT_COLOR,
+                                ..default()
+                            },
+                        ),
+                        TextSection::new(
+                            format!("\n{origin}"),
+                            TextStyle {
+                                font_size: 14.0,
+                                color: DETAIL_COLOR,
+                                ..default()
+                            },
+                        ),
+                    ]));
+                });
+            }
+        });
+}
+
+/// Lists which parent passed down every trait of the species, which traits mutated and which hidden
+/// alleles the species carries.
+fn spawn_traits(parent: &mut ChildBuilder, lineage: &Lineage, generation: u64) {
+    let text_style = |color| TextStyle {
+        font_size: 18.0,
+        color,
+        ..default()
+    };
+
+    let mut sections = vec![TextSection::new(
+        "Inherited traits\n",
+        text_style(TEXT_COLOR),
+    )];
+    match lineage.get(generation) {
+        Some(SpeciesLineage {
+            parents: Some(parents),
+            traits,
+            carried,
+            ..
+        }) => {
+            for inherited in traits.iter() {
+                let (origin, color) = match inherited.origin {
+                    TraitOrigin::FirstParent => (format!("#{}", parents[0]), PARENT_COLORS[0]),
+                    TraitOrigin::SecondParent => (format!("#{}", parents[1]), PARENT_COLORS[1]),
+                    TraitOrigin::Blend => ("both".to_string(), TEXT_COLOR),
+                    TraitOrigin::New => ("new".to_string(), TEXT_COLOR),
+                };
+
+                sections.push(TextSection::new(
+                    format!("\n{}: ", inherited.name),
+                    text_style(DETAIL_COLOR),
+                ));
+                sections.push(TextSection::new(origin, text_style(color)));
+                if inherited.recessive {
+                    sections.push(TextSection::new(" hidden", text_style(DETAIL_COLOR)));
+                }
+                if inherited.mutated {
+                    sections.push(TextSection::new(" mutated", text_style(MUTATED_COLOR)));
+                }
+            }
+            if !carried.is_empty() {
+                sections.push(TextSection::new(
+                    format!("\n\nCarries hidden: {}", carried.join(", ")),
+                    text_style(DETAIL_COLOR),
+                ));
+            }
+        }
+        _ => sections.push(TextSection::new(
+            "\nJoined the legion from the wild.",
+            text_style(DETAIL_COLOR),
+        )),
+    }
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(TRAITS_WIDTH),
+                padding: UiRect::all(Val::Px(16.0)),
+                align_self: AlignSelf::FlexStart,
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_sections(sections));
+        });
+}
+
+fn cleanup(
+    mut commands: Commands,
+    query: Query<Entity, With<LineageScreenItem>>,
+    mut view: ResMut<LineageView>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    view.root = None;
+}
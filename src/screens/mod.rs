@@ -1,5 +1,17 @@
+pub mod battle_hud_screen;
+pub mod battle_report_screen;
 pub mod battle_screen;
 pub mod creature_manager_screen;
+pub mod deployment_screen;
 pub mod game_over_screen;
+pub mod lineage_screen;
 pub mod new_creature_screen;
+pub mod offspring_preview_screen;
+pub mod placement_screen;
+pub mod records_screen;
+pub mod replay_screen;
+pub mod replays_screen;
+pub mod roster_screen;
+pub mod scouting_screen;
+pub mod settings_screen;
 pub mod tutorial_screen;
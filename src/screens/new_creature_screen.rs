@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
+    balance::Balance,
     creature::{generate_creature, CreatureGeneration, GenerateCreatureRng},
     loading::TextureAssets,
     ui::create_change_state_button,
@@ -47,6 +48,7 @@ fn spawn_creature(
     mut generate_creature_rng: ResMut<GenerateCreatureRng>,
     mut creature_generation: ResMut<CreatureGeneration>,
     textures: Res<TextureAssets>,
+    balance: Res<Balance>,
 ) {
     for _ in 0..2 {
         let tier = generate_creature_rng
@@ -56,6 +58,7 @@ fn spawn_creature(
             &mut commands,
             &mut generate_creature_rng.0,
             &textures,
+            &balance,
             tier,
             creature_generation.0,
             1.0,
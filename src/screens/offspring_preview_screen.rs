@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::{
+    balance::{Balance, StatRange},
+    creature::{CreatureStats, PopulationSize},
+    genetics::{predict_offspring, StatPrediction},
+    rounds::GameSettings,
+    GameState, WINDOW_SIZE,
+};
+
+use super::{
+    creature_manager_screen::{CreatureButton, PopulationText, SelectedCreatures},
+    new_creature_screen::PlayerCreature,
+};
+
+/// The panel takes the place of the stats windows, which are only shown while a species is
+/// hovered.
+const PANEL_POS: Vec2 = Vec2::new(0.75, 0.02);
+const PANEL_SIZE: Vec2 = Vec2::new(300.0, 570.0);
+const TITLE_FONT_SIZE: f32 = 18.0;
+const STAT_FONT_SIZE: f32 = 14.0;
+const ABILITY_FONT_SIZE: f32 = 12.0;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const PARENT_COLORS: [Color; 2] = [Color::srgb(0.4, 0.7, 1.0), Color::srgb(1.0, 0.5, 0.4)];
+const WARNING_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
+
+pub struct OffspringPreviewScreenPlugin;
+
+impl Plugin for OffspringPreviewScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::CreatureManager), setup)
+            .add_systems(
+                Update,
+                update_preview.run_if(in_state(GameState::CreatureManager)),
+            )
+            .add_systems(OnExit(GameState::CreatureManager), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct OffspringPreviewItem;
+
+#[derive(Component)]
+struct OffspringPreviewText;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(WINDOW_SIZE.x * PANEL_POS.x),
+                    top: Val::Px(WINDOW_SIZE.y * PANEL_POS.y),
+                    width: Val::Px(PANEL_SIZE.x),
+                    max_height: Val::Px(PANEL_SIZE.y),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                background_color: Color::linear_rgba(0.16, 0.16, 0.16, 0.9).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            OffspringPreviewItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::default(), OffspringPreviewText));
+        });
+}
+
+/// Shows what combining the two selected species can give, hidden while the stats of a species
+/// are shown instead.
+fn update_preview(
+    creature_button_query: Query<&Interaction, With<CreatureButton>>,
+    selected: Res<SelectedCreatures>,
+    mut panel_query: Query<&mut Visibility, With<OffspringPreviewItem>>,
+    mut preview_text_query: Query<&mut Text, With<OffspringPreviewText>>,
+    population_text_query: Query<&Text, (With<PopulationText>, Without<OffspringPreviewText>)>,
+    creature_query: Query<(&CreatureStats, &PopulationSize), With<PlayerCreature>>,
+    (balance, game_settings): (Res<Balance>, Res<GameSettings>),
+) {
+    let hovered = creature_button_query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Hovered);
+    let selected = selected
+        .0
+        .iter()
+        .filter_map(|&entity| creature_query.get(entity).ok())
+        .collect::<Vec<_>>();
+
+    let Ok(mut visibility) = panel_query.get_single_mut() else {
+        return;
+    };
+    let [(parent1, &PopulationSize(size1)), (parent2, &PopulationSize(size2))] = selected[..]
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = match hovered {
+        true => Visibility::Hidden,
+        false => Visibility::Visible,
+    };
+
+    let population = population_text_query
+        .get_single()
+        .ok()
+        .and_then(|text| text.sections[0].value.parse::<u32>().ok())
+        .unwrap_or(1);
+    let prediction = predict_offspring(
+        game_settings.inheritance.model(),
+        [parent1, parent2],
+        &balance,
+    );
+
+    let style = |font_size, color| TextStyle {
+        font_size,
+        color,
+        ..default()
+    };
+    let mut sections = vec![
+        TextSection::new("Offspring of ", style(TITLE_FONT_SIZE, TEXT_COLOR)),
+        TextSection::new(
+            format!("#{}", parent1.generation),
+            style(TITLE_FONT_SIZE, PARENT_COLORS[0]),
+        ),
+        TextSection::new(" and ", style(TITLE_FONT_SIZE, TEXT_COLOR)),
+        TextSection::new(
+            format!("#{}", parent2.generation),
+            style(TITLE_FONT_SIZE, PARENT_COLORS[1]),
+        ),
+        TextSection::new(
+            format!(
+                "\ngenetics: {}, mutated values in brackets{}",
+                game_settings.inheritance.name(),
+                match game_settings.inheritance.model().carries_alleles() {
+                    true => ", hidden alleles after the bar",
+                    false => "",
+                }
+            ),
+            style(ABILITY_FONT_SIZE, DETAIL_COLOR),
+        ),
+    ];
+
+    // combining takes the selected population from both parents and gives twice as many children
+    sections.push(TextSection::new(
+        format!(
+            "\nCost: {population} of each parent\nOffspring: {}",
+            population * 2
+        ),
+        style(STAT_FONT_SIZE, TEXT_COLOR),
+    ));
+    for (stats, size) in [(parent1, size1), (parent2, size2)] {
+        if size < population {
+            sections.push(TextSection::new(
+                format!("\n#{} has only {size}", stats.generation),
+                style(STAT_FONT_SIZE, WARNING_COLOR),
+            ));
+        }
+    }
+
+    sections.push(TextSection::new(
+        format!(
+            "\n\nArchetype: Melee {:.0}%, Ranged {:.0}%",
+            prediction.melee_chance * 100.0,
+            (1.0 - prediction.melee_chance) * 100.0
+        ),
+        style(STAT_FONT_SIZE, TEXT_COLOR),
+    ));
+    if let Some(hidden_melee_chance) = prediction.hidden_melee_chance {
+        sections.push(TextSection::new(
+            format!(
+                " | Melee {:.0}%, Ranged {:.0}%",
+                hidden_melee_chance * 100.0,
+                (1.0 - hidden_melee_chance) * 100.0
+            ),
+            style(STAT_FONT_SIZE, DETAIL_COLOR),
+        ));
+    }
+    for stat in prediction.stats.iter() {
+        sections.push(TextSection::new(
+            format!("\n{}: {}", stat.name, format_stat(stat)),
+            style(STAT_FONT_SIZE, TEXT_COLOR),
+        ));
+        if let Some(hidden) = stat.hidden {
+            sections.push(TextSection::new(
+                format!(" | {}", format_range(hidden)),
+                style(STAT_FONT_SIZE, DETAIL_COLOR),
+            ));
+        }
+    }
+
+    for ability in prediction.abilities.iter() {
+        let (holder, color) = match ability.holders {
+            [true, true] => ("both".to_string(), TEXT_COLOR),
+            [true, false] => (format!("#{}", parent1.generation), PARENT_COLORS[0]),
+            _ => (format!("#{}", parent2.generation), PARENT_COLORS[1]),
+        };
+
+        sections.push(TextSection::new(
+            format!("\n{} {:.0}% ", ability.name, ability.chance * 100.0),
+            style(STAT_FONT_SIZE, TEXT_COLOR),
+        ));
+        sections.push(TextSection::new(
+            format!("from {holder}"),
+            style(ABILITY_FONT_SIZE, color),
+        ));
+        for stat in ability.stats.iter() {
+            sections.push(TextSection::new(
+                format!("\n- {}: {}", stat.name, format_stat(stat)),
+                style(ABILITY_FONT_SIZE, DETAIL_COLOR),
+            ));
+        }
+    }
+
+    if let Ok(mut text) = preview_text_query.get_single_mut() {
+        text.sections = sections;
+    }
+}
+
+fn format_stat(stat: &StatPrediction) -> String {
+    format!(
+        "{} ({})",
+        format_range(stat.inherited),
+        format_range(stat.mutated)
+    )
+}
+
+fn format_range(range: StatRange) -> String {
+    match range.max - range.min < 0.005 {
+        true => format!("{:.2}", range.min),
+        false => format!("{:.2}-{:.2}", range.min, range.max),
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<OffspringPreviewItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
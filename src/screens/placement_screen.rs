@@ -0,0 +1,286 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    creature::{body_part_tint, CreatureStats},
+    deployment::{apply_formation, Deployed, Formation, Placement, PLACEMENT_ZONE},
+    loading::TextureAssets,
+    naming::SpeciesName,
+    ui::{create_change_state_button, create_small_button},
+    GameState, WINDOW_SIZE,
+};
+
+use super::new_creature_screen::PlayerCreature;
+
+const BACKGROUND_Z: f32 = -20.0;
+const ZONE_Z: f32 = -10.0;
+const BLOCK_Z: f32 = 0.0;
+const LABEL_Z: f32 = 1.0;
+const ZONE_COLOR: Color = Color::srgba(0.3, 0.6, 1.0, 0.15);
+const BLOCK_ALPHA: f32 = 0.6;
+const LABEL_FONT_SIZE: f32 = 16.0;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const FORMATION_BUTTON_SIZE: Vec2 = Vec2::new(240.0, 40.0);
+const FORMATION_BUTTON_SPACING: f32 = 52.0;
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+pub struct PlacementScreenPlugin;
+
+impl Plugin for PlacementScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Placement), setup.after(apply_formation))
+            .add_systems(
+                Update,
+                (
+                    handle_formation_buttons,
+                    update_formation_buttons,
+                    drag_blocks,
+                    update_blocks,
+                    handle_placement_keys,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Placement)),
+            )
+            .add_systems(OnExit(GameState::Placement), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct PlacementScreenItem;
+
+/// Region of a deployed species, it can be dragged within the placement zone.
+#[derive(Component)]
+struct PlacementBlock(Entity);
+
+#[derive(Component)]
+struct FormationButton(Formation);
+
+fn setup(
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    creature_query: Query<(Entity, &CreatureStats, &Deployed, &SpeciesName), With<PlayerCreature>>,
+) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.battle_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        PlacementScreenItem,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: ZONE_COLOR,
+                custom_size: Some(PLACEMENT_ZONE.size()),
+                ..default()
+            },
+            transform: Transform::from_translation(PLACEMENT_ZONE.center().extend(ZONE_Z)),
+            ..default()
+        },
+        PlacementScreenItem,
+    ));
+
+    for (entity, stats, &Deployed(count), name) in creature_query.iter() {
+        if count == 0 {
+            continue;
+        }
+
+        commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: body_part_tint(stats.hues[0]).with_alpha(BLOCK_ALPHA),
+                        ..default()
+                    },
+                    ..default()
+                },
+                PlacementBlock(entity),
+                PlacementScreenItem,
+            ))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        format!(
+                            "{}\n#{} {}\n{count}x",
+                            name.0,
+                            stats.generation,
+                            stats.archetype.name()
+                        ),
+                        TextStyle {
+                            font_size: LABEL_FONT_SIZE,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    transform: Transform::from_xyz(0.0, 0.0, LABEL_Z),
+                    ..default()
+                });
+            });
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Drag your species into place",
+            TextStyle {
+                font_size: 32.0,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(WINDOW_SIZE.x * 0.58),
+            top: Val::Px(WINDOW_SIZE.y * 0.1),
+            ..default()
+        }),
+        PlacementScreenItem,
+    ));
+
+    for (i, formation) in Formation::ALL.into_iter().enumerate() {
+        let button = create_small_button(
+            &mut commands,
+            formation.name(),
+            Vec2::new(
+                WINDOW_SIZE.x * 0.75,
+                WINDOW_SIZE.y * 0.3 + i as f32 * FORMATION_BUTTON_SPACING,
+            ),
+            FORMATION_BUTTON_SIZE,
+            24.0,
+        );
+        commands
+            .entity(button)
+            .insert((FormationButton(formation), PlacementScreenItem));
+    }
+
+    let button = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.65, 0.86),
+        GameState::Deployment,
+    );
+    commands.entity(button).insert(PlacementScreenItem);
+    let button = create_change_state_button(
+        &mut commands,
+        "Fight!",
+        WINDOW_SIZE * Vec2::new(0.9, 0.86),
+        GameState::Battle,
+    );
+    commands.entity(button).insert(PlacementScreenItem);
+}
+
+fn handle_formation_buttons(
+    button_query: Query<(&Interaction, &FormationButton), Changed<Interaction>>,
+    mut formation: ResMut<Formation>,
+) {
+    for (&interaction, &FormationButton(chosen)) in button_query.iter() {
+        // choosing the same formation again resets the dragged species
+        if interaction == Interaction::Pressed {
+            *formation = chosen;
+        }
+    }
+}
+
+fn update_formation_buttons(
+    button_query: Query<(Ref<FormationButton>, &Children)>,
+    mut text_query: Query<&mut Text>,
+    formation: Res<Formation>,
+) {
+    for (button, children) in button_query.iter() {
+        if !formation.is_changed() && !button.is_added() {
+            continue;
+        }
+
+        let color = match button.0 == *formation {
+            true => SELECTED_COLOR,
+            false => TEXT_COLOR,
+        };
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+/// Moves the region of the species under the cursor while the mouse button is held, regions stay
+/// inside of the placement zone.
+fn drag_blocks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    block_query: Query<&PlacementBlock>,
+    mut placement_query: Query<&mut Placement>,
+    mut dragged: Local<Option<(Entity, Vec2)>>,
+) {
+    if !mouse.pressed(MouseButton::Left) {
+        *dragged = None;
+        return;
+    }
+
+    let (camera, camera_transform) = camera_query.single();
+    let Some(cursor) = window_query
+        .single()
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        *dragged = block_query
+            .iter()
+            .filter_map(|&PlacementBlock(entity)| {
+                let &Placement(region) = placement_query.get(entity).ok()?;
+                region
+                    .contains(cursor)
+                    .then(|| (entity, region.center() - cursor))
+            })
+            .last();
+    }
+
+    let Some((entity, offset)) = *dragged else {
+        return;
+    };
+    let Ok(mut placement) = placement_query.get_mut(entity) else {
+        return;
+    };
+
+    let half_size = placement.0.half_size();
+    let center = (cursor + offset).clamp(
+        PLACEMENT_ZONE.min + half_size,
+        PLACEMENT_ZONE.max - half_size,
+    );
+    placement.0 = Rect::from_center_half_size(center, half_size);
+}
+
+fn update_blocks(
+    mut block_query: Query<(&PlacementBlock, &mut Transform, &mut Sprite)>,
+    placement_query: Query<&Placement, Changed<Placement>>,
+) {
+    for (&PlacementBlock(entity), mut transform, mut sprite) in block_query.iter_mut() {
+        let Ok(&Placement(region)) = placement_query.get(entity) else {
+            continue;
+        };
+
+        transform.translation = region.center().extend(BLOCK_Z);
+        sprite.custom_size = Some(region.size());
+    }
+}
+
+fn handle_placement_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Enter) {
+        next_state.set(GameState::Battle);
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<PlacementScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::{
+    loading::TextureAssets,
+    records::{RunHistory, RunMode, RunRecord},
+    ui::create_change_state_button,
+    GameState, WINDOW_SIZE,
+};
+
+const BACKGROUND_Z: f32 = -20.0;
+/// Number of runs shown for every mode.
+const SHOWN_RUNS: usize = 5;
+const COLUMN_WIDTH: f32 = 560.0;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const VICTORY_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+
+pub struct RecordsScreenPlugin;
+
+impl Plugin for RecordsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Records), setup)
+            .add_systems(
+                Update,
+                handle_records_keys.run_if(in_state(GameState::Records)),
+            )
+            .add_systems(OnExit(GameState::Records), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct RecordsScreenItem;
+
+fn setup(mut commands: Commands, textures: Res<TextureAssets>, history: Res<RunHistory>) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.menu_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        RecordsScreenItem,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::top(Val::Px(32.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            RecordsScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Records",
+                TextStyle {
+                    font_size: 96.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(32.0),
+                        margin: UiRect::top(Val::Px(16.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for mode in RunMode::ALL {
+                        spawn_mode_column(parent, mode, &history.best_runs(mode));
+                    }
+                });
+        });
+
+    let entity = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.5, 0.9),
+        GameState::Menu,
+    );
+    commands.entity(entity).insert(RecordsScreenItem);
+}
+
+fn spawn_mode_column(parent: &mut ChildBuilder, mode: RunMode, runs: &[&RunRecord]) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(COLUMN_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                mode.name(),
+                TextStyle {
+                    font_size: 40.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            if runs.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    "no finished runs yet",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: DETAIL_COLOR,
+                        ..default()
+                    },
+                ));
+            }
+
+            for (rank, run) in runs.iter().take(SHOWN_RUNS).enumerate() {
+                parent.spawn(TextBundle::from_sections([
+                    TextSection::new(
+                        format!(
+                            "{}. {} rounds in {}",
+                            rank + 1,
+                            run.survived_rounds,
+                            run.play_time()
+                        ),
+                        TextStyle {
+                            font_size: 26.0,
+                            color: TEXT_COLOR,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        if run.victory { "  victory" } else { "" },
+                        TextStyle {
+                            font_size: 26.0,
+                            color: VICTORY_COLOR,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        format!(
+                            "\nkills {}, deaths {}, combinations {}, legion {} in {} species\nseed {}",
+                            run.ally_kills,
+                            run.ally_deaths,
+                            run.combination_count,
+                            run.legion_size(),
+                            run.legion.len(),
+                            run.seed,
+                        ),
+                        TextStyle {
+                            font_size: 18.0,
+                            color: DETAIL_COLOR,
+                            ..default()
+                        },
+                    ),
+                ]));
+            }
+        });
+}
+
+fn handle_records_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<RecordsScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
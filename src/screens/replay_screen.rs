@@ -0,0 +1,407 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition, utils::HashSet};
+
+use crate::{
+    creature::body_part_sprites,
+    loading::TextureAssets,
+    replay::{read_replay, Replay, ReplayEvent, SelectedReplay},
+    ui::create_change_state_button,
+    GameState, WINDOW_SIZE,
+};
+
+use super::battle_screen::{spawn_creature_sprite, TeamRing};
+
+const BACKGROUND_Z: f32 = -20.0;
+const BLOOD_PUDDLE_Z: f32 = -10.0;
+const DAMAGE_EFFECT_Z: f32 = 40.0;
+/// Seconds skipped by the arrow keys.
+const SEEK_SECONDS: f32 = 5.0;
+const TIMELINE_SIZE: Vec2 = Vec2::new(800.0, 16.0);
+const TIMELINE_BOTTOM: f32 = 48.0;
+const HUD_FONT_SIZE: f32 = 24.0;
+
+pub struct ReplayScreenPlugin;
+
+impl Plugin for ReplayScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Replay), setup)
+            .add_systems(
+                Update,
+                (
+                    handle_replay_keys,
+                    handle_timeline,
+                    advance_playback,
+                    update_creatures,
+                    update_blood_puddles,
+                    update_hud,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Replay).and_then(resource_exists::<Playback>)),
+            )
+            .add_systems(OnExit(GameState::Replay), cleanup);
+    }
+}
+
+#[derive(Resource)]
+struct Playback {
+    replay: Replay,
+    time: f32,
+    playing: bool,
+}
+
+impl Playback {
+    fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.replay.duration());
+    }
+}
+
+#[derive(Component)]
+struct ReplayScreenItem;
+
+#[derive(Component)]
+struct ReplayCreature(u32);
+
+#[derive(Component)]
+struct DamageOverlay;
+
+/// Blood puddle which appears once the playback reaches the death of its creature.
+#[derive(Component)]
+struct BloodPuddle {
+    time: f32,
+}
+
+#[derive(Component)]
+struct Timeline;
+
+#[derive(Component)]
+struct TimelineProgress;
+
+#[derive(Component)]
+struct ReplayInfo;
+
+fn setup(
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    ring: Res<TeamRing>,
+    selected: Res<SelectedReplay>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(replay) = selected.0.and_then(read_replay) else {
+        next_state.set(GameState::Replays);
+        return;
+    };
+
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.battle_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        ReplayScreenItem,
+    ));
+
+    let body_parts = replay
+        .species
+        .iter()
+        .map(|species| body_part_sprites(&textures, &species.body_parts, &species.stats.hues))
+        .collect::<Vec<_>>();
+
+    for frame in replay.frames.iter() {
+        for event in frame.events.iter() {
+            match *event {
+                ReplayEvent::Spawn {
+                    creature,
+                    species,
+                    pos: (x, y),
+                } => {
+                    let is_enemy = replay.species[species as usize].is_enemy;
+                    let position = Vec2::new(x as f32, y as f32);
+                    let size = replay.species[species as usize].stats.size;
+
+                    spawn_creature_sprite(
+                        &mut commands,
+                        &ring,
+                        &body_parts[species as usize],
+                        (position, size),
+                        is_enemy,
+                    )
+                    .insert((ReplayCreature(creature), ReplayScreenItem))
+                    .with_children(|children| {
+                        children.spawn((
+                            SpriteBundle {
+                                texture: textures.damaged.clone(),
+                                transform: Transform::from_xyz(0.0, 0.0, DAMAGE_EFFECT_Z),
+                                sprite: Sprite {
+                                    flip_x: is_enemy,
+                                    ..default()
+                                },
+                                visibility: Visibility::Hidden,
+                                ..default()
+                            },
+                            DamageOverlay,
+                        ));
+                    });
+                }
+                ReplayEvent::Die {
+                    creature,
+                    pos: (x, y),
+                } => {
+                    let rect_pos = (creature % 5) as f32 * Vec2::X * Vec2::splat(64.0);
+
+                    commands.spawn((
+                        SpriteBundle {
+                            texture: textures.blood.clone(),
+                            sprite: Sprite {
+                                rect: Some(Rect::from_corners(
+                                    rect_pos,
+                                    rect_pos + Vec2::splat(64.0),
+                                )),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(x as f32, y as f32, BLOOD_PUDDLE_Z),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        BloodPuddle { time: frame.time },
+                        ReplayScreenItem,
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // round and time
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: HUD_FONT_SIZE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            top: Val::Px(16.0),
+            ..default()
+        }),
+        ReplayInfo,
+        ReplayScreenItem,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Space: play/pause, Left/Right: seek, Esc: back",
+            TextStyle {
+                font_size: HUD_FONT_SIZE * 0.75,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0),
+            ..default()
+        }),
+        ReplayScreenItem,
+    ));
+
+    // timeline, clicking or dragging on it scrubs through the replay
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(TIMELINE_SIZE.x),
+                    height: Val::Px(TIMELINE_SIZE.y),
+                    left: Val::Px((WINDOW_SIZE.x - TIMELINE_SIZE.x) / 2.0),
+                    bottom: Val::Px(TIMELINE_BOTTOM),
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(0.5).into(),
+                ..default()
+            },
+            RelativeCursorPosition::default(),
+            Timeline,
+            ReplayScreenItem,
+        ))
+        .with_children(|children| {
+            children.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.9, 0.2, 0.2).into(),
+                    ..default()
+                },
+                TimelineProgress,
+            ));
+        });
+
+    let entity = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.9, 0.1),
+        GameState::Replays,
+    );
+    commands.entity(entity).insert(ReplayScreenItem);
+
+    commands.insert_resource(Playback {
+        replay,
+        time: 0.0,
+        playing: true,
+    });
+}
+
+fn handle_replay_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut playback: ResMut<Playback>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        if playback.time >= playback.replay.duration() {
+            playback.time = 0.0;
+        }
+        playback.playing = !playback.playing;
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        let time = playback.time - SEEK_SECONDS;
+        playback.seek(time);
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        let time = playback.time + SEEK_SECONDS;
+        playback.seek(time);
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Replays);
+    }
+}
+
+fn handle_timeline(
+    query: Query<(&Interaction, &RelativeCursorPosition), With<Timeline>>,
+    mut playback: ResMut<Playback>,
+) {
+    for (&interaction, cursor) in query.iter() {
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(normalized) = cursor.normalized {
+            let time = normalized.x * playback.replay.duration();
+            playback.seek(time);
+        }
+    }
+}
+
+fn advance_playback(mut playback: ResMut<Playback>, time: Res<Time>) {
+    if !playback.playing {
+        return;
+    }
+
+    let time = playback.time + time.delta_seconds();
+    playback.seek(time);
+    if playback.time >= playback.replay.duration() {
+        playback.playing = false;
+    }
+}
+
+fn update_creatures(
+    playback: Res<Playback>,
+    mut creature_query: Query<(&ReplayCreature, &mut Transform, &mut Visibility, &Children)>,
+    mut overlay_query: Query<&mut Visibility, (With<DamageOverlay>, Without<ReplayCreature>)>,
+) {
+    let frames = &playback.replay.frames;
+    let index = frames
+        .partition_point(|frame| frame.time <= playback.time)
+        .saturating_sub(1);
+    let Some(frame) = frames.get(index) else {
+        return;
+    };
+    let next_frame = frames.get(index + 1);
+
+    // events of the next frame happen between the two frames
+    let damaged = next_frame
+        .iter()
+        .flat_map(|frame| frame.events.iter())
+        .filter_map(|event| match event {
+            ReplayEvent::Damage { target, .. } => Some(*target),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    for (&ReplayCreature(creature), mut transform, mut visibility, children) in
+        creature_query.iter_mut()
+    {
+        let Some(position) = frame.position(creature) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        let position = match next_frame.and_then(|next| Some((next, next.position(creature)?))) {
+            Some((next, next_position)) => {
+                let t = (playback.time - frame.time) / (next.time - frame.time);
+                position.lerp(next_position, t.clamp(0.0, 1.0))
+            }
+            None => position,
+        };
+        transform.translation = position.extend(transform.translation.z);
+
+        for &child in children.iter() {
+            if let Ok(mut overlay_visibility) = overlay_query.get_mut(child) {
+                *overlay_visibility = match damaged.contains(&creature) {
+                    true => Visibility::Inherited,
+                    false => Visibility::Hidden,
+                };
+            }
+        }
+    }
+}
+
+fn update_blood_puddles(
+    playback: Res<Playback>,
+    mut query: Query<(&BloodPuddle, &mut Visibility)>,
+) {
+    for (puddle, mut visibility) in query.iter_mut() {
+        *visibility = match puddle.time <= playback.time {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_hud(
+    playback: Res<Playback>,
+    mut info_query: Query<&mut Text, With<ReplayInfo>>,
+    mut progress_query: Query<&mut Style, With<TimelineProgress>>,
+) {
+    if !playback.is_changed() {
+        return;
+    }
+
+    let duration = playback.replay.duration();
+    for mut text in info_query.iter_mut() {
+        text.sections[0].value = format!(
+            "Round {}  {:.1} / {:.1} s{}",
+            playback.replay.round,
+            playback.time,
+            duration,
+            if playback.playing { "" } else { "  (paused)" },
+        );
+    }
+
+    for mut style in progress_query.iter_mut() {
+        style.width = Val::Percent(100.0 * playback.time / duration.max(f32::EPSILON));
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<ReplayScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<Playback>();
+}
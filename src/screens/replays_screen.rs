@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+
+use crate::{
+    loading::TextureAssets,
+    replay::{RecordedBattle, ReplayIndex, SelectedReplay},
+    ui::{create_change_state_button, ButtonColors},
+    GameState, WINDOW_SIZE,
+};
+
+const BACKGROUND_Z: f32 = -20.0;
+/// Number of the most recent runs which are shown.
+const SHOWN_RUNS: usize = 6;
+const COLUMN_WIDTH: f32 = 380.0;
+const ROUND_BUTTON_SIZE: Vec2 = Vec2::new(104.0, 40.0);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+pub struct ReplaysScreenPlugin;
+
+impl Plugin for ReplaysScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Replays), setup)
+            .add_systems(
+                Update,
+                (handle_replay_buttons, handle_replays_keys).run_if(in_state(GameState::Replays)),
+            )
+            .add_systems(OnExit(GameState::Replays), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct ReplaysScreenItem;
+
+#[derive(Component)]
+struct ReplayButton(RecordedBattle);
+
+fn setup(mut commands: Commands, textures: Res<TextureAssets>, index: Res<ReplayIndex>) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.menu_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        ReplaysScreenItem,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::top(Val::Px(32.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            ReplaysScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Replays",
+                TextStyle {
+                    font_size: 96.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        max_width: Val::Px(3.0 * COLUMN_WIDTH + 2.0 * 32.0),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        column_gap: Val::Px(32.0),
+                        row_gap: Val::Px(16.0),
+                        margin: UiRect::top(Val::Px(16.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (seed, rounds) in index.runs().into_iter().take(SHOWN_RUNS) {
+                        spawn_run_column(parent, seed, &rounds);
+                    }
+                });
+        });
+
+    let entity = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.5, 0.9),
+        GameState::Menu,
+    );
+    commands.entity(entity).insert(ReplaysScreenItem);
+}
+
+fn spawn_run_column(parent: &mut ChildBuilder, seed: u64, rounds: &[u32]) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(COLUMN_WIDTH),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                column_gap: Val::Px(8.0),
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Run {seed}"),
+                    TextStyle {
+                        font_size: 26.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    width: Val::Percent(100.0),
+                    ..default()
+                }),
+            );
+
+            for &round in rounds {
+                let button_colors = ButtonColors::default();
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(ROUND_BUTTON_SIZE.x),
+                                height: Val::Px(ROUND_BUTTON_SIZE.y),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: button_colors.normal.into(),
+                            ..default()
+                        },
+                        button_colors,
+                        ReplayButton(RecordedBattle { seed, round }),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            format!("Round {round}"),
+                            TextStyle {
+                                font_size: 20.0,
+                                color: TEXT_COLOR,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_replay_buttons(
+    query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut selected: ResMut<SelectedReplay>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (&interaction, ReplayButton(battle)) in query.iter() {
+        if interaction == Interaction::Pressed {
+            selected.0 = Some(*battle);
+            next_state.set(GameState::Replay);
+        }
+    }
+}
+
+fn handle_replays_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<ReplaysScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
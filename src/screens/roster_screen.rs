@@ -0,0 +1,436 @@
+use std::cmp::Ordering;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    creature::{CreatureStats, PopulationSize},
+    naming::SpeciesName,
+    ui::create_small_button,
+    GameState,
+};
+
+use super::{creature_manager_screen::ROSTER_PAGE_SIZE, new_creature_screen::PlayerCreature};
+
+const BAR_Y: f32 = 20.0;
+const BAR_FONT_SIZE: f32 = 18.0;
+const SORT_BUTTON_SIZE: Vec2 = Vec2::new(190.0, 28.0);
+const ORDER_BUTTON_SIZE: Vec2 = Vec2::new(60.0, 28.0);
+const SEARCH_SIZE: Vec2 = Vec2::new(340.0, 28.0);
+const PAGE_BUTTON_SIZE: Vec2 = Vec2::new(36.0, 28.0);
+const MAX_SEARCH_LENGTH: usize = 40;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const PLACEHOLDER_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+pub struct RosterScreenPlugin;
+
+impl Plugin for RosterScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RosterView>()
+            .add_systems(OnEnter(GameState::CreatureManager), create_roster_bar)
+            .add_systems(
+                Update,
+                (
+                    handle_roster_buttons,
+                    handle_search_input,
+                    update_roster_bar,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::CreatureManager)),
+            )
+            .add_systems(OnExit(GameState::CreatureManager), cleanup);
+    }
+}
+
+/// Which species the roster shows and in which order, kept for the whole run.
+#[derive(Resource, Default)]
+pub struct RosterView {
+    pub page: usize,
+    sort: RosterSort,
+    descending: bool,
+    search: String,
+    /// Whether typed characters go into the search.
+    typing: bool,
+}
+
+impl RosterView {
+    pub fn is_typing(&self) -> bool {
+        self.typing
+    }
+
+    pub fn stop_typing(&mut self) {
+        self.typing = false;
+    }
+
+    /// Species which match the search, in the order of the roster.
+    pub fn arrange<'a>(
+        &self,
+        species: impl Iterator<Item = (Entity, &'a CreatureStats, &'a str, u32)>,
+    ) -> Vec<Entity> {
+        let terms = self
+            .search
+            .split_whitespace()
+            .map(SearchTerm::parse)
+            .collect::<Vec<_>>();
+
+        let mut species = species
+            .filter(|&(_, stats, name, population)| {
+                population > 0
+                    && terms
+                        .iter()
+                        .all(|term| term.matches(stats, name, population))
+            })
+            .collect::<Vec<_>>();
+        species.sort_by(
+            |&(_, stats1, _, population1), &(_, stats2, _, population2)| {
+                let ordering = self
+                    .sort
+                    .value(stats1, population1)
+                    .partial_cmp(&self.sort.value(stats2, population2))
+                    .unwrap_or(Ordering::Equal)
+                    .then(stats1.generation.cmp(&stats2.generation));
+
+                match self.descending {
+                    true => ordering.reverse(),
+                    false => ordering,
+                }
+            },
+        );
+
+        species
+            .into_iter()
+            .map(|(entity, _, _, _)| entity)
+            .collect()
+    }
+}
+
+/// Field the roster is sorted by, also usable in search filters by its key.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum RosterSort {
+    #[default]
+    Generation,
+    Population,
+    Archetype,
+    Hp,
+    MovementSpeed,
+    Stamina,
+    StaminaRegen,
+    Mana,
+    ManaRegen,
+    Size,
+}
+
+impl RosterSort {
+    const ALL: [RosterSort; 10] = [
+        RosterSort::Generation,
+        RosterSort::Population,
+        RosterSort::Archetype,
+        RosterSort::Hp,
+        RosterSort::MovementSpeed,
+        RosterSort::Stamina,
+        RosterSort::StaminaRegen,
+        RosterSort::Mana,
+        RosterSort::ManaRegen,
+        RosterSort::Size,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            RosterSort::Generation => "generation",
+            RosterSort::Population => "population",
+            RosterSort::Archetype => "archetype",
+            RosterSort::Hp => "HP",
+            RosterSort::MovementSpeed => "speed",
+            RosterSort::Stamina => "stamina",
+            RosterSort::StaminaRegen => "stamina regen",
+            RosterSort::Mana => "mana",
+            RosterSort::ManaRegen => "mana regen",
+            RosterSort::Size => "size",
+        }
+    }
+
+    /// Name of the field in search filters like `hp>40`.
+    fn key(&self) -> &'static str {
+        match self {
+            RosterSort::Generation => "gen",
+            RosterSort::Population => "pop",
+            RosterSort::Archetype => "archetype",
+            RosterSort::Hp => "hp",
+            RosterSort::MovementSpeed => "speed",
+            RosterSort::Stamina => "stamina",
+            RosterSort::StaminaRegen => "staminaregen",
+            RosterSort::Mana => "mana",
+            RosterSort::ManaRegen => "manaregen",
+            RosterSort::Size => "size",
+        }
+    }
+
+    fn value(&self, stats: &CreatureStats, population: u32) -> f32 {
+        match self {
+            RosterSort::Generation => stats.generation as f32,
+            RosterSort::Population => population as f32,
+            RosterSort::Archetype => stats.archetype as u8 as f32,
+            RosterSort::Hp => stats.hp,
+            RosterSort::MovementSpeed => stats.movement_speed,
+            RosterSort::Stamina => stats.stamina,
+            RosterSort::StaminaRegen => stats.stamina_regen,
+            RosterSort::Mana => stats.mana,
+            RosterSort::ManaRegen => stats.mana_regen,
+            RosterSort::Size => stats.size,
+        }
+    }
+
+    fn next(&self) -> Self {
+        let index = RosterSort::ALL
+            .iter()
+            .position(|sort| sort == self)
+            .unwrap();
+        RosterSort::ALL[(index + 1) % RosterSort::ALL.len()]
+    }
+}
+
+/// Part of the search, either a filter like `hp>40` or a word which has to be part of the name
+/// or of an ability of the species.
+enum SearchTerm {
+    AtLeast(RosterSort, f32),
+    AtMost(RosterSort, f32),
+    Word(String),
+}
+
+impl SearchTerm {
+    fn parse(term: &str) -> Self {
+        let term = term.to_lowercase();
+
+        for (separator, at_least) in [('>', true), ('<', false)] {
+            let Some((key, value)) = term.split_once(separator) else {
+                continue;
+            };
+            let field = RosterSort::ALL.into_iter().find(|sort| sort.key() == key);
+            if let (Some(field), Ok(value)) = (field, value.parse()) {
+                return match at_least {
+                    true => SearchTerm::AtLeast(field, value),
+                    false => SearchTerm::AtMost(field, value),
+                };
+            }
+        }
+
+        SearchTerm::Word(term)
+    }
+
+    fn matches(&self, stats: &CreatureStats, name: &str, population: u32) -> bool {
+        match self {
+            SearchTerm::AtLeast(field, value) => field.value(stats, population) >= *value,
+            SearchTerm::AtMost(field, value) => field.value(stats, population) <= *value,
+            SearchTerm::Word(word) => {
+                let name = format!("#{} {} {name}", stats.generation, stats.archetype.name());
+                let abilities = stats
+                    .physical_abilities
+                    .iter()
+                    .map(|ability| ability.name.as_str())
+                    .chain(
+                        stats
+                            .magic_abilities
+                            .iter()
+                            .map(|ability| ability.kind.name()),
+                    );
+
+                std::iter::once(name.as_str())
+                    .chain(abilities)
+                    .any(|text| text.to_lowercase().contains(word))
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct RosterScreenItem;
+
+#[derive(Component, Clone, Copy)]
+enum RosterButton {
+    Sort,
+    Order,
+    Search,
+    PreviousPage,
+    NextPage,
+}
+
+#[derive(Component)]
+struct PageText;
+
+fn create_roster_bar(mut commands: Commands) {
+    let buttons = [
+        (RosterButton::Sort, 105.0, SORT_BUTTON_SIZE),
+        (RosterButton::Order, 240.0, ORDER_BUTTON_SIZE),
+        (RosterButton::Search, 450.0, SEARCH_SIZE),
+        (RosterButton::PreviousPage, 660.0, PAGE_BUTTON_SIZE),
+        (RosterButton::NextPage, 810.0, PAGE_BUTTON_SIZE),
+    ];
+
+    for (button, x, size) in buttons {
+        let title = match button {
+            RosterButton::PreviousPage => "<",
+            RosterButton::NextPage => ">",
+            _ => "",
+        };
+        let entity = create_small_button(
+            &mut commands,
+            title,
+            Vec2::new(x, BAR_Y),
+            size,
+            BAR_FONT_SIZE,
+        );
+        commands.entity(entity).insert((button, RosterScreenItem));
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: BAR_FONT_SIZE,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(690.0),
+            top: Val::Px(BAR_Y - BAR_FONT_SIZE / 2.0 - 2.0),
+            ..default()
+        }),
+        PageText,
+        RosterScreenItem,
+    ));
+}
+
+fn handle_roster_buttons(
+    query: Query<(&Interaction, &RosterButton), Changed<Interaction>>,
+    creature_query: Query<
+        (Entity, &CreatureStats, &SpeciesName, &PopulationSize),
+        With<PlayerCreature>,
+    >,
+    mut view: ResMut<RosterView>,
+) {
+    let species = view.arrange(creature_query.iter().map(
+        |(entity, stats, name, &PopulationSize(size))| (entity, stats, name.0.as_str(), size),
+    ));
+
+    for (&interaction, &button) in query.iter() {
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if !matches!(button, RosterButton::Search) {
+            view.typing = false;
+        }
+        match button {
+            RosterButton::Sort => view.sort = view.sort.next(),
+            RosterButton::Order => view.descending = !view.descending,
+            RosterButton::Search => view.typing = !view.typing,
+            RosterButton::PreviousPage => view.page = view.page.saturating_sub(1),
+            RosterButton::NextPage => {
+                if (view.page + 1) * ROSTER_PAGE_SIZE < species.len() {
+                    view.page += 1;
+                }
+            }
+        }
+    }
+}
+
+fn handle_search_input(
+    mut er_keyboard_input: EventReader<KeyboardInput>,
+    mut view: ResMut<RosterView>,
+) {
+    if !view.typing {
+        er_keyboard_input.clear();
+        return;
+    }
+
+    for event in er_keyboard_input.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(character)
+                if view.search.len() + character.len() <= MAX_SEARCH_LENGTH =>
+            {
+                view.search.push_str(character);
+                view.page = 0;
+            }
+            Key::Space if view.search.len() < MAX_SEARCH_LENGTH => view.search.push(' '),
+            Key::Backspace => {
+                view.search.pop();
+                view.page = 0;
+            }
+            Key::Enter | Key::Escape => view.typing = false,
+            _ => {}
+        }
+    }
+}
+
+fn update_roster_bar(
+    view: Res<RosterView>,
+    button_query: Query<(&RosterButton, &Children)>,
+    added_query: Query<(), Added<RosterButton>>,
+    mut page_text_query: Query<&mut Text, With<PageText>>,
+    mut text_query: Query<&mut Text, Without<PageText>>,
+    creature_query: Query<
+        (Entity, &CreatureStats, &SpeciesName, &PopulationSize),
+        With<PlayerCreature>,
+    >,
+) {
+    let species = view.arrange(creature_query.iter().map(
+        |(entity, stats, name, &PopulationSize(size))| (entity, stats, name.0.as_str(), size),
+    ));
+    let pages = species.len().div_ceil(ROSTER_PAGE_SIZE).max(1);
+    let page = format!("{}/{pages}", view.page.min(pages - 1) + 1);
+    if let Ok(mut text) = page_text_query.get_single_mut() {
+        if text.sections[0].value != page {
+            text.sections[0].value = page;
+        }
+    }
+
+    if !view.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        let (label, color) = match button {
+            RosterButton::Sort => (format!("sort: {}", view.sort.name()), TEXT_COLOR),
+            RosterButton::Order => match view.descending {
+                true => ("desc".to_string(), TEXT_COLOR),
+                false => ("asc".to_string(), TEXT_COLOR),
+            },
+            RosterButton::Search => match (view.search.is_empty(), view.typing) {
+                (true, false) => ("search, e.g. bite hp>40".to_string(), PLACEHOLDER_COLOR),
+                (_, true) => (format!("{}_", view.search), TEXT_COLOR),
+                (false, false) => (view.search.clone(), TEXT_COLOR),
+            },
+            _ => continue,
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = label.clone();
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+fn cleanup(
+    mut commands: Commands,
+    query: Query<Entity, With<RosterScreenItem>>,
+    mut view: ResMut<RosterView>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    view.typing = false;
+}
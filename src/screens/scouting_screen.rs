@@ -0,0 +1,554 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use crate::{
+    balance::Balance,
+    creature::{
+        body_part_images, body_part_variant, estimate_tier, BodyPart, BodyPartAtlas, CreatureStats,
+        PopulationSize,
+    },
+    deployment::Deployment,
+    loading::TextureAssets,
+    naming::SpeciesName,
+    scouting::{generate_enemy_army, EnemyCreature},
+    ui::create_small_button,
+    GameState, WINDOW_SIZE,
+};
+
+use super::{creature_manager_screen::CountText, new_creature_screen::PlayerCreature};
+
+const COLUMN_WIDTH: f32 = 600.0;
+const ENTRY_WIDTH: f32 = 280.0;
+const THUMBNAIL_SIZE: f32 = 48.0;
+const TOGGLE_SIZE: Vec2 = Vec2::new(84.0, 28.0);
+const PANEL_Z_INDEX: i32 = 10;
+const SCOUT_BUTTON_SIZE: Vec2 = Vec2::new(200.0, 40.0);
+const CLOSE_BUTTON_SIZE: Vec2 = Vec2::new(200.0, 48.0);
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const DETAIL_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FIELDED_COLOR: Color = Color::srgb(0.2, 0.45, 0.25);
+const BENCHED_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+const COUNT_COLOR: Color = Color::BLACK;
+const BENCHED_COUNT_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+
+pub struct ScoutingScreenPlugin;
+
+impl Plugin for ScoutingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoutingView>()
+            .add_systems(
+                OnEnter(GameState::CreatureManager),
+                create_scout_button.after(generate_enemy_army),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_scout_button,
+                    handle_close_button,
+                    handle_scouting_keys,
+                    handle_bench_toggles,
+                    update_scouting_panel,
+                    update_bench_toggles,
+                    update_count_texts,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::CreatureManager)),
+            )
+            .add_systems(OnExit(GameState::CreatureManager), cleanup);
+    }
+}
+
+/// Whether the scouting panel is open, it opens at the start of every round.
+#[derive(Resource, Default)]
+struct ScoutingView {
+    open: bool,
+}
+
+#[derive(Component)]
+struct ScoutingScreenItem;
+
+#[derive(Component)]
+struct ScoutButton;
+
+#[derive(Component)]
+struct ScoutingPanel;
+
+#[derive(Component)]
+struct CloseScoutingButton;
+
+/// Fields or benches the player species.
+#[derive(Component)]
+struct BenchToggle(Entity);
+
+fn create_scout_button(mut commands: Commands, mut view: ResMut<ScoutingView>) {
+    let button = create_small_button(
+        &mut commands,
+        "Scout enemies",
+        WINDOW_SIZE * Vec2::new(0.8, 0.95),
+        SCOUT_BUTTON_SIZE,
+        24.0,
+    );
+    commands
+        .entity(button)
+        .insert((ScoutingScreenItem, ScoutButton));
+
+    view.open = true;
+}
+
+fn handle_scout_button(
+    query: Query<&Interaction, (With<ScoutButton>, Changed<Interaction>)>,
+    mut view: ResMut<ScoutingView>,
+) {
+    if query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Pressed)
+    {
+        view.open = true;
+    }
+}
+
+fn handle_close_button(
+    query: Query<&Interaction, (With<CloseScoutingButton>, Changed<Interaction>)>,
+    mut view: ResMut<ScoutingView>,
+) {
+    if query
+        .iter()
+        .any(|&interaction| interaction == Interaction::Pressed)
+    {
+        view.open = false;
+    }
+}
+
+fn handle_scouting_keys(keys: Res<ButtonInput<KeyCode>>, mut view: ResMut<ScoutingView>) {
+    if keys.just_pressed(KeyCode::Escape) && view.open {
+        view.open = false;
+    }
+}
+
+/// Benches or fields the species, at least one species always stays in the field.
+fn handle_bench_toggles(
+    mut commands: Commands,
+    toggle_query: Query<(&Interaction, &BenchToggle), Changed<Interaction>>,
+    creature_query: Query<(&PopulationSize, Option<&Deployment>), With<PlayerCreature>>,
+) {
+    for (&interaction, &BenchToggle(entity)) in toggle_query.iter() {
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok((_, deployment)) = creature_query.get(entity) else {
+            continue;
+        };
+
+        // a fielded species is deployed as far as the cap allows
+        if Deployment::is_benched(deployment) {
+            commands.entity(entity).remove::<Deployment>();
+            continue;
+        }
+
+        let fielded = creature_query
+            .iter()
+            .filter(|&(&PopulationSize(size), deployment)| {
+                size > 0 && !Deployment::is_benched(deployment)
+            })
+            .count();
+        if fielded > 1 {
+            commands.entity(entity).insert(Deployment(0));
+        }
+    }
+}
+
+fn update_scouting_panel(
+    mut commands: Commands,
+    view: Res<ScoutingView>,
+    panel_query: Query<Entity, With<ScoutingPanel>>,
+    enemy_query: Query<
+        (&CreatureStats, &PopulationSize, &Children, &SpeciesName),
+        With<EnemyCreature>,
+    >,
+    player_query: Query<
+        (
+            Entity,
+            &CreatureStats,
+            &PopulationSize,
+            &Children,
+            &SpeciesName,
+        ),
+        With<PlayerCreature>,
+    >,
+    body_part_query: Query<&Sprite, With<BodyPart>>,
+    (textures, atlas, balance): (Res<TextureAssets>, Res<BodyPartAtlas>, Res<Balance>),
+) {
+    if !view.is_changed() {
+        return;
+    }
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if !view.open {
+        return;
+    }
+
+    let mut enemies = enemy_query
+        .iter()
+        .filter(|(_, &PopulationSize(size), _, _)| size > 0)
+        .collect::<Vec<_>>();
+    enemies.sort_by_key(|(_, &PopulationSize(size), _, _)| std::cmp::Reverse(size));
+    let mut species = player_query
+        .iter()
+        .filter(|(_, _, &PopulationSize(size), _, _)| size > 0)
+        .collect::<Vec<_>>();
+    species.sort_by_key(|(_, stats, _, _, _)| stats.generation);
+
+    let body_parts = |children: &Children| {
+        children
+            .iter()
+            .filter_map(|&child| body_part_query.get(child).ok())
+            .map(body_part_variant)
+            .collect::<Vec<_>>()
+    };
+    let thumbnail = |parent: &mut ChildBuilder, stats: &CreatureStats, children: &Children| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(THUMBNAIL_SIZE),
+                    height: Val::Px(THUMBNAIL_SIZE),
+                    flex_shrink: 0.0,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for image in body_part_images(
+                    &textures,
+                    &atlas,
+                    &body_parts(children),
+                    &stats.hues,
+                    THUMBNAIL_SIZE,
+                ) {
+                    parent.spawn(image);
+                }
+            });
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(0.85).into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(PANEL_Z_INDEX),
+                ..default()
+            },
+            ScoutingPanel,
+            ScoutingScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Scouted enemy army",
+                TextStyle {
+                    font_size: 48.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(32.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let total = enemies
+                        .iter()
+                        .map(|(_, &PopulationSize(size), _, _)| size)
+                        .sum::<u32>();
+                    spawn_column(
+                        parent,
+                        "Enemies",
+                        format!("{} species, {total} creatures", enemies.len()),
+                        |parent| {
+                            for &(stats, &PopulationSize(size), children, name) in enemies.iter() {
+                                spawn_entry(parent, |parent| {
+                                    thumbnail(parent, stats, children);
+                                    spawn_description(parent, &balance, name, None, stats, size);
+                                });
+                            }
+                        },
+                    );
+
+                    let total = species
+                        .iter()
+                        .map(|(_, _, &PopulationSize(size), _, _)| size)
+                        .sum::<u32>();
+                    spawn_column(
+                        parent,
+                        "Your legion",
+                        format!("{} species, {total} creatures", species.len()),
+                        |parent| {
+                            for &(entity, stats, &PopulationSize(size), children, name) in
+                                species.iter()
+                            {
+                                spawn_entry(parent, |parent| {
+                                    thumbnail(parent, stats, children);
+                                    spawn_description(
+                                        parent,
+                                        &balance,
+                                        name,
+                                        Some(stats.generation),
+                                        stats,
+                                        size,
+                                    );
+                                    spawn_bench_toggle(parent, entity);
+                                });
+                            }
+                        },
+                    );
+                });
+
+            parent.spawn(TextBundle::from_section(
+                "Benched species sit out the battle, Esc to close",
+                TextStyle {
+                    font_size: 20.0,
+                    color: DETAIL_COLOR,
+                    ..default()
+                },
+            ));
+        });
+
+    let button = create_small_button(
+        &mut commands,
+        "To the legion",
+        WINDOW_SIZE * Vec2::new(0.5, 0.93),
+        CLOSE_BUTTON_SIZE,
+        24.0,
+    );
+    commands.entity(button).insert((
+        ZIndex::Global(PANEL_Z_INDEX + 1),
+        CloseScoutingButton,
+        ScoutingPanel,
+        ScoutingScreenItem,
+    ));
+}
+
+fn spawn_column(
+    parent: &mut ChildBuilder,
+    title: &str,
+    summary: String,
+    spawn_entries: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(COLUMN_WIDTH),
+                max_height: Val::Px(WINDOW_SIZE.y * 0.7),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                align_content: AlignContent::FlexStart,
+                row_gap: Val::Px(8.0),
+                column_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.5).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_sections([
+                    TextSection::new(
+                        title,
+                        TextStyle {
+                            font_size: 32.0,
+                            color: TEXT_COLOR,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        format!("  {summary}"),
+                        TextStyle {
+                            font_size: 18.0,
+                            color: DETAIL_COLOR,
+                            ..default()
+                        },
+                    ),
+                ])
+                .with_style(Style {
+                    width: Val::Percent(100.0),
+                    ..default()
+                }),
+            );
+
+            spawn_entries(parent);
+        });
+}
+
+fn spawn_entry(parent: &mut ChildBuilder, spawn_content: impl FnOnce(&mut ChildBuilder)) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(ENTRY_WIDTH),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(spawn_content);
+}
+
+/// Name, population, approximate tier and abilities of a species, enemies have no generation.
+fn spawn_description(
+    parent: &mut ChildBuilder,
+    balance: &Balance,
+    name: &SpeciesName,
+    generation: Option<u64>,
+    stats: &CreatureStats,
+    population: u32,
+) {
+    let kind = match generation {
+        Some(generation) => format!("#{generation} {}", stats.archetype.name()),
+        None => stats.archetype.name().to_string(),
+    };
+    let abilities = stats
+        .physical_abilities
+        .iter()
+        .map(|ability| ability.name.as_str())
+        .chain(
+            stats
+                .magic_abilities
+                .iter()
+                .map(|ability| ability.kind.name()),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    parent.spawn(
+        TextBundle::from_sections([
+            TextSection::new(
+                format!("{} {population}x", name.0),
+                TextStyle {
+                    font_size: 20.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                format!(
+                    "\n{kind}, tier ~{:.0}, {abilities}",
+                    estimate_tier(balance, stats).round()
+                ),
+                TextStyle {
+                    font_size: 14.0,
+                    color: DETAIL_COLOR,
+                    ..default()
+                },
+            ),
+        ])
+        .with_style(Style {
+            flex_grow: 1.0,
+            ..default()
+        }),
+    );
+}
+
+fn spawn_bench_toggle(parent: &mut ChildBuilder, entity: Entity) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(TOGGLE_SIZE.x),
+                    height: Val::Px(TOGGLE_SIZE.y),
+                    flex_shrink: 0.0,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            BenchToggle(entity),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 16.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn update_bench_toggles(
+    mut toggle_query: Query<(&BenchToggle, &mut BackgroundColor, &Children)>,
+    creature_query: Query<Option<&Deployment>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (&BenchToggle(entity), mut color, children) in toggle_query.iter_mut() {
+        let benched = Deployment::is_benched(creature_query.get(entity).ok().flatten());
+        let (label, background) = match benched {
+            true => ("benched", BENCHED_COLOR),
+            false => ("fielded", FIELDED_COLOR),
+        };
+
+        if color.0 != background {
+            color.0 = background;
+        }
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.to_string();
+                }
+            }
+        }
+    }
+}
+
+/// Greys out the population of benched species in the roster.
+fn update_count_texts(
+    mut count_query: Query<(&CountText, &mut Text)>,
+    creature_query: Query<(&PopulationSize, Option<&Deployment>)>,
+) {
+    for (&CountText(entity), mut text) in count_query.iter_mut() {
+        let Ok((&PopulationSize(size), deployment)) = creature_query.get(entity) else {
+            continue;
+        };
+        let (value, color) = match Deployment::is_benched(deployment) {
+            true => (format!("({size}x)"), BENCHED_COUNT_COLOR),
+            false => (format!("{size}x"), COUNT_COLOR),
+        };
+
+        if text.sections[0].value != value || text.sections[0].style.color != color {
+            text.sections[0].value = value;
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+fn cleanup(
+    mut commands: Commands,
+    query: Query<Entity, With<ScoutingScreenItem>>,
+    mut view: ResMut<ScoutingView>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    view.open = false;
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+
+use crate::{
+    loading::TextureAssets,
+    settings::DisplaySettings,
+    ui::{create_change_state_button, create_small_button},
+    GameState, WINDOW_SIZE,
+};
+
+const BACKGROUND_Z: f32 = -20.0;
+const TOGGLE_SIZE: Vec2 = Vec2::new(420.0, 56.0);
+const TOGGLE_FONT_SIZE: f32 = 26.0;
+const TOGGLE_SPACING: f32 = 0.1;
+const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Settings), setup)
+            .add_systems(
+                Update,
+                (handle_toggles, update_toggle_labels, handle_settings_keys)
+                    .chain()
+                    .run_if(in_state(GameState::Settings)),
+            )
+            .add_systems(OnExit(GameState::Settings), cleanup);
+    }
+}
+
+#[derive(Component)]
+struct SettingsScreenItem;
+
+#[derive(Component, Clone, Copy)]
+enum SettingToggle {
+    CreatureBars,
+    SpeciesBars,
+    DamageNumbers,
+}
+
+impl SettingToggle {
+    const ALL: [SettingToggle; 3] = [
+        SettingToggle::CreatureBars,
+        SettingToggle::SpeciesBars,
+        SettingToggle::DamageNumbers,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SettingToggle::CreatureBars => "HP and stamina bars",
+            SettingToggle::SpeciesBars => "species health",
+            SettingToggle::DamageNumbers => "damage numbers",
+        }
+    }
+
+    fn value(&self, settings: &DisplaySettings) -> bool {
+        match self {
+            SettingToggle::CreatureBars => settings.creature_bars,
+            SettingToggle::SpeciesBars => settings.species_bars,
+            SettingToggle::DamageNumbers => settings.damage_numbers,
+        }
+    }
+
+    fn value_mut<'a>(&self, settings: &'a mut DisplaySettings) -> &'a mut bool {
+        match self {
+            SettingToggle::CreatureBars => &mut settings.creature_bars,
+            SettingToggle::SpeciesBars => &mut settings.species_bars,
+            SettingToggle::DamageNumbers => &mut settings.damage_numbers,
+        }
+    }
+}
+
+fn setup(mut commands: Commands, textures: Res<TextureAssets>) {
+    // background
+    commands.spawn((
+        SpriteBundle {
+            texture: textures.menu_background.clone(),
+            transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z),
+            ..default()
+        },
+        SettingsScreenItem,
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::top(Val::Px(32.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            SettingsScreenItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Settings",
+                TextStyle {
+                    font_size: 96.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        });
+
+    for (i, toggle) in SettingToggle::ALL.into_iter().enumerate() {
+        let button = create_small_button(
+            &mut commands,
+            toggle.label(),
+            WINDOW_SIZE * Vec2::new(0.5, 0.35 + i as f32 * TOGGLE_SPACING),
+            TOGGLE_SIZE,
+            TOGGLE_FONT_SIZE,
+        );
+        commands.entity(button).insert((toggle, SettingsScreenItem));
+    }
+
+    let entity = create_change_state_button(
+        &mut commands,
+        "Back",
+        WINDOW_SIZE * Vec2::new(0.5, 0.9),
+        GameState::Menu,
+    );
+    commands.entity(entity).insert(SettingsScreenItem);
+}
+
+fn handle_toggles(
+    query: Query<(&Interaction, &SettingToggle), Changed<Interaction>>,
+    mut settings: ResMut<DisplaySettings>,
+) {
+    for (&interaction, toggle) in query.iter() {
+        if interaction == Interaction::Pressed {
+            let value = toggle.value_mut(&mut settings);
+            *value = !*value;
+        }
+    }
+}
+
+fn update_toggle_labels(
+    settings: Res<DisplaySettings>,
+    toggle_query: Query<(&SettingToggle, &Children)>,
+    added_query: Query<(), Added<SettingToggle>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (toggle, children) in toggle_query.iter() {
+        let on = toggle.value(&settings);
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value =
+                    format!("{}: {}", toggle.label(), if on { "on" } else { "off" });
+            }
+        }
+    }
+}
+
+fn handle_settings_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn cleanup(mut commands: Commands, query: Query<Entity, With<SettingsScreenItem>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battle::AttackRng,
+    creature::GenerateCreatureRng,
+    rounds::{Difficulty, Round},
+    screens::{
+        battle_screen::{BattleVisualsRng, CreaturePositionRng},
+        creature_manager_screen::CombinationRng,
+    },
+    GameState,
+};
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunSeed>()
+            .add_systems(OnExit(GameState::Tutorial), reseed_rngs)
+            .add_systems(OnExit(GameState::Battle), reseed_rngs);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum RngStream {
+    GenerateCreature,
+    CreaturePosition,
+    Attack,
+    BattleVisuals,
+    Combination,
+    Difficulty,
+}
+
+/// Master seed of the current run. Every RNG of the game is reseeded from it per subsystem at the
+/// start of each round, so the same seed and the same player inputs give the same creatures, enemy
+/// armies, combinations and battle rolls.
+#[derive(Resource, Clone, Copy, Serialize, Deserialize)]
+pub struct RunSeed(pub u64);
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl RunSeed {
+    pub fn rng(&self, stream: RngStream, round: u32) -> StdRng {
+        let seed = split_mix(split_mix(self.0 ^ stream as u64) ^ round as u64);
+
+        StdRng::seed_from_u64(seed)
+    }
+}
+
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+    z ^ (z >> 31)
+}
+
+pub fn reseed_rngs(
+    run_seed: Res<RunSeed>,
+    round: Res<Round>,
+    mut generate_creature_rng: ResMut<GenerateCreatureRng>,
+    (mut creature_position_rng, mut attack_rng, mut battle_visuals_rng): (
+        ResMut<CreaturePositionRng>,
+        ResMut<AttackRng>,
+        ResMut<BattleVisualsRng>,
+    ),
+    mut combination_rng: ResMut<CombinationRng>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    generate_creature_rng.0 = run_seed.rng(RngStream::GenerateCreature, round.0);
+    creature_position_rng.0 = run_seed.rng(RngStream::CreaturePosition, round.0);
+    attack_rng.0 = run_seed.rng(RngStream::Attack, round.0);
+    battle_visuals_rng.0 = run_seed.rng(RngStream::BattleVisuals, round.0);
+    combination_rng.0 = run_seed.rng(RngStream::Combination, round.0);
+    difficulty.set_rng(run_seed.rng(RngStream::Difficulty, round.0));
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Increase whenever the format of [`DisplaySettings`] changes, older settings are then ignored.
+const SETTINGS_VERSION: u32 = 1;
+const SETTINGS_KEY: &str = "settings.ron";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(read_settings().unwrap_or_default())
+            .add_systems(Update, write_settings);
+    }
+}
+
+/// What is drawn over the battlefield, kept across runs. Large battles stay more readable with
+/// less of it.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    version: u32,
+    /// HP and stamina bars over every creature.
+    pub creature_bars: bool,
+    /// Total HP of every species at the top of the screen.
+    pub species_bars: bool,
+    pub damage_numbers: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            creature_bars: true,
+            species_bars: true,
+            damage_numbers: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SettingsHeader {
+    version: u32,
+}
+
+fn read_settings() -> Option<DisplaySettings> {
+    let data = storage::read(SETTINGS_KEY)?;
+
+    let version = ron::from_str::<SettingsHeader>(&data).map(|header| header.version);
+    if version != Ok(SETTINGS_VERSION) {
+        warn!("Ignoring settings with version {version:?}, expected version {SETTINGS_VERSION}");
+        return None;
+    }
+
+    match ron::from_str::<DisplaySettings>(&data) {
+        Ok(settings) => Some(settings),
+        Err(error) => {
+            warn!("Failed to deserialize settings {error:?}");
+            None
+        }
+    }
+}
+
+fn write_settings(settings: Res<DisplaySettings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    match ron::to_string(&*settings) {
+        Ok(data) => storage::write(SETTINGS_KEY, &data),
+        Err(error) => warn!("Failed to serialize settings {error:?}"),
+    }
+}
@@ -0,0 +1,213 @@
+use bevy::{prelude::*, utils::HashMap};
+use rand::rngs::StdRng;
+
+use crate::{
+    balance::{Balance, SizeRules},
+    battle::{
+        random_spawn_position, AttackRng, BattleCreature, BattleCreatureBundle, BattlePlugin,
+        BattleSet, BattleStep, BattleTime, DamageTakenEvent, Enemy, Stance,
+    },
+    creature::{CreatureStats, PopulationChangedEvent, PopulationSize},
+    seed::{RngStream, RunSeed},
+};
+
+const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+const DEFAULT_TIME_LIMIT: f32 = 300.0;
+
+pub type Army = Vec<(CreatureStats, PopulationSize, Stance)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleSide {
+    Allies,
+    Enemies,
+}
+
+#[derive(Debug, Clone)]
+pub struct BattleSimulationSettings {
+    pub seed: u64,
+    /// Simulated seconds per step.
+    pub timestep: f32,
+    /// Simulated seconds after which the battle ends as a draw.
+    pub time_limit: f32,
+    /// How the size of a species changes its HP and movement speed.
+    pub size: SizeRules,
+}
+
+impl Default for BattleSimulationSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            timestep: DEFAULT_TIMESTEP,
+            time_limit: DEFAULT_TIME_LIMIT,
+            size: Balance::default().size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeciesResult {
+    pub survivors: u32,
+    pub damage_dealt: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattleResult {
+    /// `None` when the time limit was reached before one of the armies was destroyed.
+    pub winner: Option<BattleSide>,
+    pub allies: Vec<SpeciesResult>,
+    pub enemies: Vec<SpeciesResult>,
+    pub elapsed_seconds: f32,
+}
+
+#[derive(Resource, Default)]
+struct DamageDealt(HashMap<Entity, f32>);
+
+/// Runs a battle between two armies without any rendering or audio. The battle is driven by the
+/// same systems as the battle screen, but with a fixed timestep and a seeded RNG, so the same
+/// input always produces the same result.
+pub struct BattleSimulation {
+    app: App,
+    settings: BattleSimulationSettings,
+    ally_templates: Vec<Entity>,
+    enemy_templates: Vec<Entity>,
+    elapsed_seconds: f32,
+}
+
+impl BattleSimulation {
+    pub fn new(allies: &Army, enemies: &Army, settings: BattleSimulationSettings) -> Self {
+        let run_seed = RunSeed(settings.seed);
+
+        let mut app = App::new();
+        app.add_plugins(BattlePlugin)
+            .insert_resource(AttackRng(run_seed.rng(RngStream::Attack, 0)))
+            .init_resource::<DamageDealt>()
+            .add_event::<PopulationChangedEvent>()
+            .add_systems(BattleStep, record_damage.in_set(BattleSet::React))
+            .add_systems(Update, run_battle_step);
+
+        app.world_mut()
+            .resource_mut::<BattleTime>()
+            .set_delta_seconds(settings.timestep);
+
+        let mut position_rng = run_seed.rng(RngStream::CreaturePosition, 0);
+        let world = app.world_mut();
+        let ally_templates = spawn_army(world, &mut position_rng, allies, false, &settings.size);
+        let enemy_templates = spawn_army(world, &mut position_rng, enemies, true, &settings.size);
+
+        Self {
+            app,
+            settings,
+            ally_templates,
+            enemy_templates,
+            elapsed_seconds: 0.0,
+        }
+    }
+
+    /// Advances the battle by one timestep. Returns the result once the battle is over.
+    pub fn step(&mut self) -> Option<BattleResult> {
+        if let Some(winner) = self.winner() {
+            return Some(self.result(winner));
+        }
+        if self.elapsed_seconds >= self.settings.time_limit {
+            return Some(self.result(None));
+        }
+
+        self.app.update();
+        self.elapsed_seconds += self.settings.timestep;
+
+        None
+    }
+
+    pub fn run(mut self) -> BattleResult {
+        loop {
+            if let Some(result) = self.step() {
+                return result;
+            }
+        }
+    }
+
+    fn winner(&mut self) -> Option<Option<BattleSide>> {
+        let world = self.app.world_mut();
+        let allies_alive = world
+            .query_filtered::<(), (With<BattleCreature>, Without<Enemy>)>()
+            .iter(world)
+            .next()
+            .is_some();
+        let enemies_alive = world
+            .query_filtered::<(), (With<BattleCreature>, With<Enemy>)>()
+            .iter(world)
+            .next()
+            .is_some();
+
+        match (allies_alive, enemies_alive) {
+            (true, true) => None,
+            (true, false) => Some(Some(BattleSide::Allies)),
+            (false, true) => Some(Some(BattleSide::Enemies)),
+            (false, false) => Some(None),
+        }
+    }
+
+    fn result(&self, winner: Option<BattleSide>) -> BattleResult {
+        BattleResult {
+            winner,
+            allies: self.species_results(&self.ally_templates),
+            enemies: self.species_results(&self.enemy_templates),
+            elapsed_seconds: self.elapsed_seconds,
+        }
+    }
+
+    fn species_results(&self, templates: &[Entity]) -> Vec<SpeciesResult> {
+        let world = self.app.world();
+        let damage_dealt = world.resource::<DamageDealt>();
+
+        templates
+            .iter()
+            .map(|&template| SpeciesResult {
+                survivors: world.get::<PopulationSize>(template).unwrap().0,
+                damage_dealt: damage_dealt.0.get(&template).copied().unwrap_or(0.0),
+            })
+            .collect()
+    }
+}
+
+fn spawn_army(
+    world: &mut World,
+    rng: &mut StdRng,
+    army: &Army,
+    is_enemy: bool,
+    size: &SizeRules,
+) -> Vec<Entity> {
+    let mut templates = Vec::new();
+
+    for (stats, population, stance) in army.iter() {
+        let template = world.spawn((stats.clone(), *population)).id();
+        templates.push(template);
+
+        for _ in 0..population.0 {
+            let position = random_spawn_position(rng, is_enemy);
+            let mut entity = world.spawn((
+                TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+                BattleCreatureBundle::new(template, stats, *stance, size),
+            ));
+
+            if is_enemy {
+                entity.insert(Enemy);
+            }
+        }
+    }
+
+    templates
+}
+
+fn run_battle_step(world: &mut World) {
+    world.run_schedule(BattleStep);
+}
+
+fn record_damage(
+    mut er_damage_taken: EventReader<DamageTakenEvent>,
+    mut damage_dealt: ResMut<DamageDealt>,
+) {
+    for event in er_damage_taken.read() {
+        *damage_dealt.0.entry(event.source).or_default() += event.damage;
+    }
+}
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+
+/// Maximal number of cells along one axis, the cells get larger when the points are spread further.
+const MAX_CELLS_PER_AXIS: f32 = 256.0;
+
+/// Uniform grid of points used to answer nearest point and radius queries without checking every
+/// point. It is meant to be rebuilt from scratch every frame, rebuilding reuses the allocations.
+pub struct SpatialGrid {
+    /// Preferred cell size, the actual cell size can be larger.
+    base_cell_size: f32,
+    cell_size: f32,
+    origin: Vec2,
+    size: IVec2,
+    /// Entries of cell `i` are `entries[cell_starts[i]..cell_starts[i + 1]]`.
+    cell_starts: Vec<usize>,
+    entries: Vec<(Entity, Vec2)>,
+    unsorted: Vec<(Entity, Vec2)>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            base_cell_size: cell_size,
+            cell_size,
+            origin: Vec2::ZERO,
+            size: IVec2::ZERO,
+            cell_starts: Vec::new(),
+            entries: Vec::new(),
+            unsorted: Vec::new(),
+        }
+    }
+
+    pub fn rebuild(&mut self, points: impl IntoIterator<Item = (Entity, Vec2)>) {
+        self.unsorted.clear();
+        self.unsorted.extend(points);
+        self.entries.clear();
+        self.cell_starts.clear();
+
+        if self.unsorted.is_empty() {
+            self.size = IVec2::ZERO;
+            return;
+        }
+
+        let (min, max) = self.unsorted.iter().fold(
+            (Vec2::INFINITY, Vec2::NEG_INFINITY),
+            |(min, max), &(_, pos)| (min.min(pos), max.max(pos)),
+        );
+        let extent = max - min;
+        self.cell_size = self
+            .base_cell_size
+            .max(extent.max_element() / MAX_CELLS_PER_AXIS);
+        self.origin = min;
+        self.size = (extent / self.cell_size).floor().as_ivec2() + IVec2::ONE;
+
+        // counting sort of the points by their cell
+        let cell_count = (self.size.x * self.size.y) as usize;
+        self.cell_starts.resize(cell_count + 1, 0);
+        for &(_, pos) in self.unsorted.iter() {
+            let index = self.cell_index(self.cell_of(pos));
+            self.cell_starts[index + 1] += 1;
+        }
+        for i in 0..cell_count {
+            self.cell_starts[i + 1] += self.cell_starts[i];
+        }
+
+        let mut next = self.cell_starts.clone();
+        self.entries
+            .resize(self.unsorted.len(), (Entity::PLACEHOLDER, Vec2::ZERO));
+        for &(entity, pos) in self.unsorted.iter() {
+            let index = self.cell_index(self.cell_of(pos));
+            self.entries[next[index]] = (entity, pos);
+            next[index] += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the point closest to `pos`.
+    pub fn nearest(&self, pos: Vec2) -> Option<(Entity, Vec2)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let center = self.cell_of(pos);
+        let max_ring = (center - IVec2::ZERO)
+            .abs()
+            .max((center - (self.size - IVec2::ONE)).abs())
+            .max_element();
+
+        let mut best = None;
+        let mut best_distance_squared = f32::INFINITY;
+        for ring in 0..=max_ring {
+            self.visit_ring(center, ring, |entries| {
+                for &(entity, point) in entries {
+                    let distance_squared = pos.distance_squared(point);
+                    if distance_squared < best_distance_squared {
+                        best_distance_squared = distance_squared;
+                        best = Some((entity, point));
+                    }
+                }
+            });
+
+            // points in further rings are at least `ring` cells away
+            let ring_distance = ring as f32 * self.cell_size;
+            if best.is_some() && best_distance_squared <= ring_distance * ring_distance {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Returns all points which are at most `radius` away from `pos`.
+    pub fn within_radius(
+        &self,
+        pos: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let (min, max) = if self.is_empty() {
+            (IVec2::ONE, IVec2::ZERO)
+        } else {
+            (
+                self.cell_of(pos - Vec2::splat(radius)).max(IVec2::ZERO),
+                self.cell_of(pos + Vec2::splat(radius))
+                    .min(self.size - IVec2::ONE),
+            )
+        };
+
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .flat_map(move |cell| self.cell(cell).iter().copied())
+            .filter(move |&(_, point)| pos.distance_squared(point) <= radius * radius)
+    }
+
+    fn cell_of(&self, pos: Vec2) -> IVec2 {
+        ((pos - self.origin) / self.cell_size).floor().as_ivec2()
+    }
+
+    fn cell_index(&self, cell: IVec2) -> usize {
+        let cell = cell.clamp(IVec2::ZERO, self.size - IVec2::ONE);
+
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    fn cell(&self, cell: IVec2) -> &[(Entity, Vec2)] {
+        let index = self.cell_index(cell);
+
+        &self.entries[self.cell_starts[index]..self.cell_starts[index + 1]]
+    }
+
+    /// Calls `visit` with the entries of every cell inside of the grid which is exactly `ring`
+    /// cells away from `center`.
+    fn visit_ring(&self, center: IVec2, ring: i32, mut visit: impl FnMut(&[(Entity, Vec2)])) {
+        let min = (center - IVec2::splat(ring)).max(IVec2::ZERO);
+        let max = (center + IVec2::splat(ring)).min(self.size - IVec2::ONE);
+
+        for y in min.y..=max.y {
+            if y == center.y - ring || y == center.y + ring {
+                for x in min.x..=max.x {
+                    visit(self.cell(IVec2::new(x, y)));
+                }
+            } else {
+                for x in [center.x - ring, center.x + ring] {
+                    if (min.x..=max.x).contains(&x) {
+                        visit(self.cell(IVec2::new(x, y)));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    battle::CreatureDieEvent,
     rounds::{GameEndedEvent, GameStartedEvent, RoundOverEvent},
-    screens::{battle_screen::CreatureDieEvent, creature_manager_screen::CreatureCombinedEvent},
+    screens::creature_manager_screen::CreatureCombinedEvent,
 };
 
 pub struct StatisticsPlugin;
@@ -10,11 +12,12 @@ pub struct StatisticsPlugin;
 impl Plugin for StatisticsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GameStatistics>()
-            .add_systems(Update, update_statistics);
+            // Runs after the screens, so the statistics are up to date when the state changes.
+            .add_systems(PostUpdate, update_statistics);
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
 pub struct GameStatistics {
     is_game_on: bool,
     pub elapsed_seconds: f32,
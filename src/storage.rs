@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+/// Persistent key-value storage. On desktop every key is a file in the platform data directory,
+/// on the web it is an entry in the local storage.
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::{env, fs, io, path::PathBuf};
+
+    const GAME_DIR: &str = "tiny_legion";
+
+    fn data_dir() -> Option<PathBuf> {
+        #[cfg(target_os = "windows")]
+        let dir = env::var_os("APPDATA").map(PathBuf::from);
+        #[cfg(target_os = "macos")]
+        let dir = env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        });
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let dir = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+
+        dir.map(|dir| dir.join(GAME_DIR))
+    }
+
+    fn path(key: &str) -> io::Result<PathBuf> {
+        data_dir()
+            .map(|dir| dir.join(key))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "data directory not found"))
+    }
+
+    pub fn read(key: &str) -> io::Result<String> {
+        fs::read_to_string(path(key)?)
+    }
+
+    pub fn write(key: &str, data: &str) -> io::Result<()> {
+        let path = path(key)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, data)
+    }
+
+    pub fn delete(key: &str) -> io::Result<()> {
+        match fs::remove_file(path(key)?) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use web_sys::Storage;
+
+    const KEY_PREFIX: &str = "tiny_legion/";
+
+    fn local_storage() -> Result<Storage, String> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| "local storage not available".to_string())
+    }
+
+    pub fn read(key: &str) -> Result<String, String> {
+        local_storage()?
+            .get_item(&(KEY_PREFIX.to_string() + key))
+            .map_err(|error| format!("{error:?}"))?
+            .ok_or_else(|| "not found".to_string())
+    }
+
+    pub fn write(key: &str, data: &str) -> Result<(), String> {
+        local_storage()?
+            .set_item(&(KEY_PREFIX.to_string() + key), data)
+            .map_err(|error| format!("{error:?}"))
+    }
+
+    pub fn delete(key: &str) -> Result<(), String> {
+        local_storage()?
+            .remove_item(&(KEY_PREFIX.to_string() + key))
+            .map_err(|error| format!("{error:?}"))
+    }
+}
+
+pub fn read(key: &str) -> Option<String> {
+    backend::read(key).ok()
+}
+
+pub fn write(key: &str, data: &str) {
+    if let Err(error) = backend::write(key, data) {
+        warn!("Failed to write {key} {error:?}");
+    }
+}
+
+pub fn delete(key: &str) {
+    if let Err(error) = backend::delete(key) {
+        warn!("Failed to delete {key} {error:?}");
+    }
+}
@@ -22,9 +22,9 @@ impl Plugin for UIPlugin {
 }
 
 #[derive(Component)]
-struct ButtonColors {
-    normal: Color,
-    hovered: Color,
+pub struct ButtonColors {
+    pub normal: Color,
+    pub hovered: Color,
 }
 
 impl Default for ButtonColors {
@@ -98,6 +98,46 @@ pub fn create_mini_button(commands: &mut Commands, title: &'static str, pos: Vec
     create_button(commands, title, pos, Vec2::splat(BUTTON_SIZE.y))
 }
 
+/// Creates a button with absolute position, `pos` is the center of the button in UI coordinates.
+pub fn create_small_button(
+    commands: &mut Commands,
+    title: &'static str,
+    pos: Vec2,
+    size: Vec2,
+    font_size: f32,
+) -> Entity {
+    let button_colors = ButtonColors::default();
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(size.x),
+                    height: Val::Px(size.y),
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(pos.x - size.x / 2.0),
+                    top: Val::Px(pos.y - size.y / 2.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: button_colors.normal.into(),
+                ..default()
+            },
+            button_colors,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size,
+                    color: Color::linear_rgb(0.9, 0.9, 0.9),
+                    ..default()
+                },
+            ));
+        })
+        .id()
+}
+
 fn handle_button_hover(
     mut query: Query<
         (&Interaction, &mut BackgroundColor, &ButtonColors),
@@ -0,0 +1,117 @@
+use tiny_legion::{
+    simulation::{Army, BattleResult, BattleSide, BattleSimulation, BattleSimulationSettings},
+    Archetype, CreatureStats, PhysicalAbility, PopulationSize, Stance,
+};
+
+/// Melee species without magic, so the outcome only depends on HP and damage.
+fn species(hp: f32, damage: f32) -> CreatureStats {
+    CreatureStats {
+        archetype: Archetype::Melee,
+        movement_speed: 200.0,
+        hp,
+        stamina: 100.0,
+        stamina_regen: 20.0,
+        mana: 0.0,
+        mana_regen: 0.0,
+        generation: 0,
+        physical_abilities: vec![PhysicalAbility {
+            name: "bite".to_string(),
+            stamina_cost: 10.0,
+            damage,
+            global_cooldown: 0.5,
+            range: 32.0,
+        }],
+        magic_abilities: Vec::new(),
+        size: 1.0,
+        hues: vec![0.0; 4],
+        recessive: None,
+    }
+}
+
+fn army(species: &[(CreatureStats, u32)]) -> Army {
+    species
+        .iter()
+        .map(|(stats, population)| {
+            (
+                stats.clone(),
+                PopulationSize(*population),
+                Stance::default(),
+            )
+        })
+        .collect()
+}
+
+fn run(allies: &Army, enemies: &Army, settings: BattleSimulationSettings) -> BattleResult {
+    BattleSimulation::new(allies, enemies, settings).run()
+}
+
+#[test]
+fn stronger_army_wins() {
+    let allies = army(&[(species(200.0, 20.0), 8), (species(150.0, 15.0), 4)]);
+    let enemies = army(&[(species(50.0, 5.0), 10)]);
+    let settings = BattleSimulationSettings::default();
+    let time_limit = settings.time_limit;
+
+    let result = run(&allies, &enemies, settings);
+
+    assert_eq!(result.winner, Some(BattleSide::Allies));
+    assert!(result.elapsed_seconds > 0.0 && result.elapsed_seconds < time_limit);
+
+    assert_eq!(result.allies.len(), 2);
+    assert!(result.allies[0].survivors <= 8 && result.allies[1].survivors <= 4);
+    assert!(result.allies.iter().any(|species| species.survivors > 0));
+    assert_eq!(result.enemies.len(), 1);
+    assert_eq!(result.enemies[0].survivors, 0);
+
+    // every enemy was beaten down to 0 HP
+    let ally_damage = result.allies.iter().map(|s| s.damage_dealt).sum::<f32>();
+    assert!(ally_damage >= 10.0 * 50.0);
+    // the allies lost less than their whole HP
+    assert!(result.enemies[0].damage_dealt < 8.0 * 200.0 + 4.0 * 150.0);
+}
+
+#[test]
+fn weaker_army_loses() {
+    let allies = army(&[(species(50.0, 5.0), 5)]);
+    let enemies = army(&[(species(250.0, 20.0), 10)]);
+
+    let result = run(&allies, &enemies, BattleSimulationSettings::default());
+
+    assert_eq!(result.winner, Some(BattleSide::Enemies));
+    assert_eq!(result.allies[0].survivors, 0);
+    assert!(result.enemies[0].survivors > 0);
+    assert!(result.enemies[0].damage_dealt >= 5.0 * 50.0);
+}
+
+#[test]
+fn same_seed_gives_the_same_battle() {
+    let allies = army(&[(species(120.0, 12.0), 6), (species(80.0, 18.0), 6)]);
+    let enemies = army(&[(species(100.0, 15.0), 12)]);
+    let settings = BattleSimulationSettings {
+        seed: 7,
+        ..Default::default()
+    };
+
+    let first = run(&allies, &enemies, settings.clone());
+    let second = run(&allies, &enemies, settings);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn time_limit_ends_the_battle_as_a_draw() {
+    let harmless = army(&[(species(100.0, 0.0), 3)]);
+    let settings = BattleSimulationSettings {
+        time_limit: 5.0,
+        ..Default::default()
+    };
+
+    let result = run(&harmless, &harmless, settings);
+
+    assert_eq!(result.winner, None);
+    assert!(result.elapsed_seconds >= 5.0);
+    for side in [&result.allies, &result.enemies] {
+        assert_eq!(side[0].survivors, 3);
+        assert_eq!(side[0].damage_dealt, 0.0);
+    }
+}